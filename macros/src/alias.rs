@@ -0,0 +1,71 @@
+use syn::{parse::Parse, punctuated::Punctuated, Ident, LitStr, Result, Token, Visibility};
+
+/// `name = token` or `name = "spec"`.
+pub struct Alias {
+    pub name: Ident,
+    pub spec: LitStr,
+}
+
+impl Parse for Alias {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let name = input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+        let spec = if input.peek(LitStr) {
+            input.parse::<LitStr>()?
+        } else {
+            let token = input.parse::<Ident>()?;
+            LitStr::new(&token.to_string(), token.span())
+        };
+        Ok(Self { name, spec })
+    }
+}
+
+/// `[vis] macro name { alias, .. }`, the input of `comat_alias!`.
+pub struct Define {
+    pub vis: Visibility,
+    pub name: Ident,
+    pub aliases: Punctuated<Alias, Token![,]>,
+}
+
+impl Parse for Define {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let vis = input.parse()?;
+        if let Visibility::Public(token) = vis {
+            return Err(syn::Error::new(
+                token.span,
+                "a `macro_rules!` cannot be `pub` outside its crate, so use `pub(crate)`",
+            ));
+        }
+        input.parse::<Token![macro]>()?;
+        let name = input.parse()?;
+        let content;
+        syn::braced!(content in input);
+        Ok(Self {
+            vis,
+            name,
+            aliases: Punctuated::parse_terminated(&content)?,
+        })
+    }
+}
+
+/// `{ alias, .. } name (args)`, what the macro `comat_alias!` defines gives `comat_aliased!`.
+pub struct Aliased {
+    pub aliases: Punctuated<Alias, Token![,]>,
+    pub name: Ident,
+    pub args: proc_macro2::TokenStream,
+}
+
+impl Parse for Aliased {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let content;
+        syn::braced!(content in input);
+        let aliases = Punctuated::parse_terminated(&content)?;
+        let name = input.parse()?;
+        let args = input.parse::<proc_macro2::Group>()?.stream();
+        Ok(Self {
+            aliases,
+            name,
+            args,
+        })
+    }
+}
@@ -0,0 +1,801 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use proc_macro2::Literal;
+use quote::{ToTokens, TokenStreamExt};
+use syn::{parse::Parse, punctuated::Punctuated, Expr, Result, Token};
+
+use comat_parser::{Close, Depth, Made, Theme, FORMATTERS};
+
+use crate::expand::Source;
+
+/// Per call settings, given before the format string.
+#[derive(Default)]
+pub struct Options {
+    /// `@style`s applied to the whole string, restored after every reset.
+    pub base: Vec<syn::Ident>,
+    /// `dedent`, `tmux`, `tags` and `close_*`, and the `base` for the parser.
+    pub parser: comat_parser::Options,
+    /// `@ansi16`, `@ansi256`, `@truecolor` or `@plain`.
+    pub force: Option<Force>,
+}
+
+/// How a call said to show its string, whatever the terminal or the runtime settings are.
+#[derive(Clone, Copy)]
+pub enum Force {
+    /// With this many colors.
+    Depth(Depth),
+    /// Without escapes.
+    Plain,
+}
+
+impl Parse for Options {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let mut options = Self::default();
+        loop {
+            if input.peek(Token![@]) {
+                input.parse::<Token![@]>()?;
+                let style = input.parse::<syn::Ident>()?;
+                let force = match &*style.to_string() {
+                    "ansi16" => Force::Depth(Depth::Ansi16),
+                    "ansi256" => Force::Depth(Depth::Ansi256),
+                    "truecolor" => Force::Depth(Depth::Truecolor),
+                    "plain" => Force::Plain,
+                    _ => {
+                        options.parser.base.push(style.to_string());
+                        options.base.push(style);
+                        input.parse::<Token![,]>()?;
+                        continue;
+                    }
+                };
+                if options.force.replace(force).is_some() {
+                    return Err(syn::Error::new(
+                        style.span(),
+                        "only one of @ansi16, @ansi256, @truecolor and @plain can be given",
+                    ));
+                }
+                options.parser.plain = matches!(force, Force::Plain);
+            } else if input.peek(syn::Ident) && input.peek2(Token![,]) {
+                let flag = input.fork().parse::<syn::Ident>()?;
+                match &*flag.to_string() {
+                    "dedent" => options.parser.dedent = true,
+                    "tmux" => options.parser.tmux = true,
+                    "tags" => options.parser.tags = true,
+                    "close_reset" => options.parser.close = Some(Close::Reset),
+                    "close_default" => options.parser.close = Some(Close::Default),
+                    "close_none" => options.parser.close = Some(Close::None),
+                    // a ctemplate!, which could be given a string argument, if named like a constant
+                    name if !input.peek3(syn::LitStr) || name.starts_with(char::is_uppercase) => {
+                        break
+                    }
+                    _ => return Err(syn::Error::new(flag.span(), "unknown option")),
+                }
+                input.parse::<syn::Ident>()?;
+            } else {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(options)
+    }
+}
+
+/// A parsed format string.
+pub struct CFStr {
+    /// The format string. For a `ctemplate!`, only what goes after the template (like a newline).
+    text: String,
+    /// The files it was themed with.
+    files: Vec<PathBuf>,
+    /// The arguments its blocks made (see [`Made`]).
+    made: Vec<(String, Made)>,
+    /// For a [`merged`](Self::merged) string, every version of each of [`made`](Self::made), by variant.
+    versions: Vec<Vec<Made>>,
+    /// For a [`merged`](Self::merged) string, the `__comat_text_{n}` arguments put in for the text that differs, and its versions, by variant.
+    chosen: Vec<(String, Vec<String>)>,
+    /// The `ctemplate!` this is.
+    template: Option<syn::Path>,
+    source: Source,
+    /// The other versions of it that are different, for the printing macros to choose from at runtime.
+    variants: Vec<(Variant, CFStr)>,
+    /// How the call said to show it, so the printing macros should leave it as it is.
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    force: Option<Force>,
+}
+
+/// A version of a string, chosen at runtime.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    /// With [markers](comat_parser::Options::markers), for screen readers.
+    Markers,
+    /// With the `n`th (from 1) of the [theme's variants](Theme::variants).
+    Theme(usize),
+    /// With the truecolor downgraded to 256 colors, for terminals without it.
+    Ansi256,
+    /// With the `n`th theme variant, downgraded to 256 colors.
+    ThemeAnsi256(usize),
+}
+
+impl CFStr {
+    /// Items that make the crate rebuild when the files this string was themed with change.
+    pub fn track(&self) -> proc_macro2::TokenStream {
+        self.files
+            .iter()
+            .filter_map(|path| path.to_str())
+            .map(|path| quote::quote! { const _: &[u8] = include_bytes!(#path); })
+            .collect()
+    }
+
+    /// The string, as a byte string literal.
+    pub fn bytes(&self) -> Literal {
+        Literal::byte_string(self.text.as_bytes())
+    }
+
+    /// Add a newline to the end, for the `ln` macros.
+    pub fn newline(&mut self) {
+        self.text.push('\n');
+        for (_, variant) in &mut self.variants {
+            variant.newline();
+        }
+    }
+
+    #[cfg(feature = "std")]
+    /// How the call said to show this, if it did.
+    pub fn force(&self) -> Option<Force> {
+        self.force
+    }
+
+    #[cfg(feature = "std")]
+    /// The other versions of this, that are different.
+    pub fn variants(&self) -> &[(Variant, Self)] {
+        &self.variants
+    }
+
+    #[cfg(feature = "std")]
+    /// This and its [variants](Self::variants) as one string, which takes the text and escapes that differ from `__comat_variant`,
+    /// the index of the version to show: 0 for this, then from 1 for the variants that are given back, in order.
+    /// So the arguments are only given once, and only which strings to show is chosen at runtime.
+    ///
+    /// Variants that differ by more than their text and escapes, which the parser does not make, are left out.
+    pub fn merged(&self) -> (Self, Vec<Variant>) {
+        let (texts, blocks) = pieces(&self.text);
+        let (mut kept, mut versions) = (vec![], vec![texts]);
+        for (variant, other) in &self.variants {
+            let (texts, other_blocks) = pieces(&other.text);
+            let alike = other_blocks == blocks
+                && other.made.len() == self.made.len()
+                && other
+                    .made
+                    .iter()
+                    .zip(&self.made)
+                    .all(|((a, x), (b, y))| a == b && alike(x, y));
+            if alike {
+                kept.push((*variant, other));
+                versions.push(texts);
+            }
+        }
+        let mut text = String::with_capacity(self.text.len());
+        let mut chosen = vec![];
+        for (i, own) in versions[0].iter().enumerate() {
+            let texts = versions
+                .iter()
+                .map(|texts| texts[i].clone())
+                .collect::<Vec<_>>();
+            if texts.iter().all(|t| t == own) {
+                text.push_str(&own.replace('{', "{{").replace('}', "}}"));
+            } else {
+                let name = format!("__comat_text_{}", chosen.len());
+                text.push('{');
+                text.push_str(&name);
+                text.push('}');
+                chosen.push((name, texts));
+            }
+            if let Some(block) = blocks.get(i) {
+                text.push('{');
+                text.push_str(block);
+                text.push('}');
+            }
+        }
+        let versions = (0..self.made.len())
+            .map(|i| {
+                std::iter::once(&self.made[i].1)
+                    .chain(kept.iter().map(|(_, other)| &other.made[i].1))
+                    .cloned()
+                    .collect()
+            })
+            .collect();
+        let merged = Self {
+            text,
+            files: self.files.clone(),
+            made: self.made.clone(),
+            versions,
+            chosen,
+            template: None,
+            source: self.source.clone(),
+            variants: vec![],
+            force: self.force,
+        };
+        (
+            merged,
+            kept.into_iter().map(|(variant, _)| variant).collect(),
+        )
+    }
+
+    /// The string as format arguments: the string, `args`, and the arguments its blocks made.
+    ///
+    /// Or an error, if the string (with the colors taken out) is not a valid format string.
+    pub fn with_args(&self, args: &Punctuated<Expr, Token![,]>) -> proc_macro2::TokenStream {
+        if let Err(e) = self.check() {
+            return e.to_compile_error();
+        }
+        self.arguments(args)
+            .unwrap_or_else(|e| e.to_compile_error())
+    }
+
+    /// [`with_args`](Self::with_args), after the string is checked.
+    fn arguments(&self, args: &Punctuated<Expr, Token![,]>) -> Result<proc_macro2::TokenStream> {
+        // the arguments, by name if named. a block made from a named one takes it, leaving `None`,
+        // and one made from a positional one takes its place
+        let mut given = args.iter().map(|arg| Some(named(arg))).collect::<Vec<_>>();
+        let uses = self.uses();
+        let mut text = self.text.clone();
+        let mut made = vec![];
+        for (i, (arg, m)) in self.made.iter().enumerate() {
+            let name = quote::format_ident!("__comat_{i}");
+            let expr = |e: &str| e.parse::<proc_macro2::TokenStream>().unwrap_or_default();
+            let versions = self
+                .versions
+                .get(i)
+                .map_or(std::slice::from_ref(m), Vec::as_slice);
+            let value = match m {
+                Made::Literal(_) => {
+                    let literal = choose(versions, |made| match made {
+                        Made::Literal(literal) => literal,
+                        _ => unreachable!("merged strings make the same kinds of arguments"),
+                    });
+                    made.push(quote::quote! { #name = #literal });
+                    continue;
+                }
+                Made::Source(path, line) => {
+                    let (path, line) = (expr(path), expr(line));
+                    made.push(quote::quote! { #name = ::comat::Location(&(#path), #line) });
+                    continue;
+                }
+                Made::Notify(title, body) => {
+                    let (title, body) = (expr(title), expr(body));
+                    made.push(quote::quote! { #name = ::comat::Notification(&(#title), &(#body)) });
+                    continue;
+                }
+                Made::Image(path, width) => {
+                    let (path, width) = (expr(path), expr(width));
+                    made.push(quote::quote! { #name = ::comat::Image(&(#path), #width) });
+                    continue;
+                }
+                Made::Formatter(_) | Made::Scale(..) | Made::Style(_) => {
+                    self.value(&uses.keys[i], &mut given, &uses.count)?
+                }
+            };
+            let (value, positional) = match value {
+                Value::Ref(value) => (value, None),
+                Value::Positional(at) => {
+                    let (_, expr) = given[at].take().expect("positional");
+                    (quote::quote! { &(#expr) }, Some(at))
+                }
+            };
+            let value = from(versions, &value);
+            match positional {
+                // the block shows the positional argument again, which is now what it made, so the positions stay the same
+                Some(at) => {
+                    given[at] = Some((None, value));
+                    for close in ['}', ':'] {
+                        text =
+                            text.replace(&format!("{{{name}{close}"), &format!("{{{arg}{close}"));
+                    }
+                }
+                None => made.push(quote::quote! { #name = #value }),
+            }
+        }
+        let text = if text == self.text {
+            self.to_token_stream()
+        } else {
+            Literal::string(&text).into_token_stream()
+        };
+        for (name, versions) in &self.chosen {
+            let name = proc_macro2::Ident::new(name, proc_macro2::Span::call_site());
+            let text = choose(versions, String::as_str);
+            made.push(quote::quote! { #name = #text });
+        }
+        let given = given.into_iter().flatten().map(|(name, arg)| match name {
+            Some(name) => quote::quote! { #name = #arg },
+            None => arg,
+        });
+        Ok(quote::quote! { #text #(, #given)* #(, #made)* })
+    }
+
+    /// Where the value a block made from the argument `key` shows comes from: the named argument `key`, which it takes from `given`,
+    /// the positional argument at the index `key`, or the variable `key`, when there is no such argument.
+    fn value(
+        &self,
+        key: &str,
+        given: &mut [Option<(Option<syn::Ident>, proc_macro2::TokenStream)>],
+        uses: &HashMap<String, usize>,
+    ) -> Result<Value> {
+        // an argument can only be taken once
+        let once = || {
+            if uses.get(key).copied().unwrap_or(0) > 1 {
+                return Err(syn::Error::new(
+                    self.source.span,
+                    format!("the argument `{key}` is used by more than one block, so it cannot be given to a formatter, scale or [style]. put it in a variable"),
+                ));
+            }
+            Ok(())
+        };
+        if let Ok(index) = key.parse::<usize>() {
+            let at = given
+                .iter()
+                .enumerate()
+                .filter(|(_, given)| matches!(given, Some((None, _))))
+                .nth(index)
+                .map(|(at, _)| at)
+                .ok_or_else(|| {
+                    syn::Error::new(self.source.span, format!("there is no argument {index}"))
+                })?;
+            once()?;
+            return Ok(Value::Positional(at));
+        }
+        let named = given
+            .iter()
+            .position(|given| matches!(given, Some((Some(name), _)) if name == key));
+        let Some(at) = named else {
+            let key = proc_macro2::Ident::new(key, proc_macro2::Span::call_site());
+            return Ok(Value::Ref(quote::quote! { &#key }));
+        };
+        once()?;
+        let (_, expr) = given[at].take().expect("found");
+        Ok(Value::Ref(quote::quote! { &(#expr) }))
+    }
+
+    /// How many blocks of the string use each argument, by name or position, counting the arguments the blocks made were made from.
+    fn uses(&self) -> Uses {
+        let mut uses = Uses {
+            count: HashMap::new(),
+            keys: vec![String::new(); self.made.len()],
+        };
+        // the position of the next `{}`
+        let mut next = 0_usize;
+        let mut positional = || {
+            next += 1;
+            (next - 1).to_string()
+        };
+        for block in blocks(&self.text) {
+            let (arg, spec) = block.split_once(':').unwrap_or((block, ""));
+            // `.*` takes the precision from the next position, before the value
+            if spec.contains(".*") {
+                *uses.count.entry(positional()).or_default() += 1;
+            }
+            let made = arg
+                .strip_prefix("__comat_")
+                .and_then(|i| i.parse::<usize>().ok())
+                .filter(|&i| {
+                    matches!(
+                        self.made.get(i),
+                        Some((_, Made::Formatter(_) | Made::Scale(..) | Made::Style(_)))
+                    )
+                });
+            let arg = made.map_or(arg, |i| &self.made[i].0);
+            let key = if arg.is_empty() {
+                positional()
+            } else {
+                arg.to_string()
+            };
+            if let Some(i) = made {
+                uses.keys[i].clone_from(&key);
+            }
+            *uses.count.entry(key).or_default() += 1;
+            // widths and precisions, like `{x:>width$}`
+            for count in spec.split('$').rev().skip(1) {
+                let start = count
+                    .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .map_or(0, |i| i + 1);
+                *uses.count.entry(count[start..].to_string()).or_default() += 1;
+            }
+        }
+        uses
+    }
+
+    /// Error if this has formatters, scales or styles, for the macros that dont format.
+    pub fn plain(self, span: proc_macro2::Span) -> Result<Self> {
+        self.inline(span, false)
+    }
+
+    /// Like [`plain`](Self::plain), for a string that will be formatted later.
+    pub fn template(self, span: proc_macro2::Span) -> Result<Self> {
+        let template = self.inline(span, true)?;
+        template.check()?;
+        Ok(template)
+    }
+
+    /// Error if the string is not a valid format string, pointing at the block in the source if it can.
+    fn check(&self) -> Result<()> {
+        comat_parser::check(&self.text).map_err(|mut error| {
+            // the blocks that are not colors are left as they were written, so they can be found
+            error.at = error.at.and_then(|at| {
+                let written = &self.text[at];
+                let mut found = self.source.value.match_indices(written);
+                match (found.next(), found.next()) {
+                    (Some((start, _)), None) => Some(start..start + written.len()),
+                    _ => None,
+                }
+            });
+            self.source.error(error)
+        })
+    }
+
+    /// Put the literal blocks in, erroring if there are arguments to make.
+    fn inline(self, span: proc_macro2::Span, format: bool) -> Result<Self> {
+        if self.template.is_some() {
+            Err(syn::Error::new(
+                span,
+                "templates only work in the macros that format, like cformat!",
+            ))
+        } else if self
+            .made
+            .iter()
+            .all(|(_, made)| matches!(made, Made::Literal(_)))
+        {
+            let text = comat_parser::inline(&self.text, &self.made, format);
+            Ok(Self {
+                text,
+                made: vec![],
+                ..self
+            })
+        } else {
+            Err(syn::Error::new(
+                span,
+                "formatters, scales, [style]s, src(..), notify(..) and image(..) only work in the macros that format, like cformat!",
+            ))
+        }
+    }
+
+    /// Put `open` and `close` around every run of escape sequences, for shell prompts.
+    pub fn guard(&mut self, open: &str, close: &str) {
+        self.text = comat_parser::guard(&self.text, open, close);
+    }
+
+    /// Write the escape characters out as text, like `\x1b[1m`, so they can be seen.
+    pub fn show_escapes(&mut self) {
+        self.text = self.text.replace('\x1b', "\\x1b").replace('\x07', "\\x07");
+    }
+
+    /// Name the positional arguments (`{0}`) like the fields of a tuple struct are bound (`{_0}`).
+    pub fn tuple_fields(&mut self) {
+        let mut out = String::with_capacity(self.text.len());
+        let mut chars = self.text.chars().peekable();
+        while let Some(ch) = chars.next() {
+            out.push(ch);
+            match (ch, chars.peek()) {
+                ('{', Some('{')) => out.extend(chars.next()),
+                ('{', Some(c)) if c.is_ascii_digit() => out.push('_'),
+                _ => {}
+            }
+        }
+        self.text = out;
+        for (arg, _) in &mut self.made {
+            if arg.starts_with(|c: char| c.is_ascii_digit()) {
+                arg.insert(0, '_');
+            }
+        }
+        for (_, variant) in &mut self.variants {
+            variant.tuple_fields();
+        }
+    }
+
+    /// Whether this needs no formatting, having no `{}`s.
+    pub fn is_literal(&self) -> bool {
+        self.template.is_none() && !self.text.contains(['{', '}'])
+    }
+
+    /// `source` parsed with another `theme` or `options`, if that makes it different from `text`.
+    fn variant(
+        source: &Source,
+        text: &str,
+        theme: &Theme,
+        options: &comat_parser::Options,
+    ) -> Option<Self> {
+        let parsed = comat_parser::parse(&source.value, theme, options).ok()?;
+        (parsed.text != text).then(|| Self {
+            text: parsed.text,
+            files: vec![],
+            made: parsed.made,
+            versions: vec![],
+            chosen: vec![],
+            template: None,
+            source: source.clone(),
+            variants: vec![],
+            force: None,
+        })
+    }
+
+    /// The [variants](Self::variants) of the string `text` parsed from `source`, adding the files of the theme variants to `theme`.
+    fn versions(
+        source: &Source,
+        text: &str,
+        theme: &mut Theme,
+        options: &comat_parser::Options,
+    ) -> Result<Vec<(Variant, Self)>> {
+        let mut variants = vec![];
+        let markers = comat_parser::Options {
+            markers: true,
+            ..options.clone()
+        };
+        variants
+            .extend(Self::variant(source, text, theme, &markers).map(|v| (Variant::Markers, v)));
+        if theme.depth == Depth::Truecolor {
+            theme.depth = Depth::Ansi256;
+            variants
+                .extend(Self::variant(source, text, theme, options).map(|v| (Variant::Ansi256, v)));
+            theme.depth = Depth::Truecolor;
+        }
+        for (i, name) in theme.variants.iter().enumerate() {
+            let mut other =
+                Theme::load_variant(name).map_err(|e| syn::Error::new(source.span, e))?;
+            for file in &other.files {
+                if !theme.files.contains(file) {
+                    theme.files.push(file.clone());
+                }
+            }
+            let Some(themed) = Self::variant(source, text, &other, options) else {
+                // the same as the usual theme, whose downgrade is already there
+                continue;
+            };
+            let downgraded = (other.depth == Depth::Truecolor)
+                .then(|| {
+                    other.depth = Depth::Ansi256;
+                    Self::variant(source, &themed.text, &other, options)
+                })
+                .flatten();
+            variants.push((Variant::Theme(i + 1), themed));
+            variants.extend(downgraded.map(|v| (Variant::ThemeAnsi256(i + 1), v)));
+        }
+        Ok(variants)
+    }
+
+    /// Parse the format string, with [`Options`].
+    pub fn parse_with(stream: syn::parse::ParseStream, options: &Options) -> Result<Self> {
+        let mut source = Source::parse(stream)?;
+        if let Some(template) = source.template.take() {
+            if !options.base.is_empty()
+                || options.parser.dedent
+                || options.parser.tmux
+                || options.parser.tags
+                || options.parser.close.is_some()
+                || options.force.is_some()
+            {
+                return Err(syn::Error::new(
+                    source.span,
+                    "options go in the ctemplate!, not where it is used",
+                ));
+            }
+            return Ok(Self {
+                text: String::new(),
+                files: vec![],
+                made: vec![],
+                versions: vec![],
+                chosen: vec![],
+                template: Some(template),
+                source,
+                variants: vec![],
+                force: None,
+            });
+        }
+        let mut theme = Theme::load().map_err(|e| syn::Error::new(source.span, e))?;
+        if let Some(Force::Depth(depth)) = options.force {
+            theme.depth = depth;
+        }
+        theme.files.extend(std::mem::take(&mut source.files));
+        for style in &options.base {
+            if theme.get(&style.to_string()).is_none() {
+                return Err(syn::Error::new(style.span(), "unknown style"));
+            }
+        }
+        let parsed = comat_parser::parse(&source.value, &theme, &options.parser)
+            .map_err(|e| source.error(e))?;
+        for (_, made) in &parsed.made {
+            let expressions = match made {
+                Made::Scale(steps, _) => steps.iter().map(|(threshold, _)| threshold).collect(),
+                Made::Style(style) => vec![style],
+                Made::Source(a, b) | Made::Notify(a, b) | Made::Image(a, b) => vec![a, b],
+                Made::Formatter(_) | Made::Literal(_) => vec![],
+            };
+            for expression in expressions {
+                syn::parse_str::<syn::Expr>(expression).map_err(|_| {
+                    syn::Error::new(
+                        source.span,
+                        format!("expected an expression, not {expression:?}"),
+                    )
+                })?;
+            }
+        }
+        // a forced string is shown as it is, so it needs no others
+        let variants = if options.force.is_none() {
+            Self::versions(&source, &parsed.text, &mut theme, &options.parser)?
+        } else {
+            vec![]
+        };
+        Ok(Self {
+            text: parsed.text,
+            files: theme.files,
+            made: parsed.made,
+            versions: vec![],
+            chosen: vec![],
+            template: None,
+            source,
+            variants,
+            force: options.force,
+        })
+    }
+}
+
+impl Parse for CFStr {
+    fn parse(stream: syn::parse::ParseStream) -> Result<Self> {
+        let options = stream.parse::<Options>()?;
+        Self::parse_with(stream, &options)
+    }
+}
+
+impl ToTokens for CFStr {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match &self.template {
+            // format_args! expands macros in place of its string, as long as they give a literal
+            Some(template) if self.text.is_empty() => tokens.extend(quote::quote! { #template!() }),
+            Some(template) => {
+                let rest = &self.text;
+                tokens.extend(quote::quote! { concat!(#template!(), #rest) });
+            }
+            None => tokens.append(Literal::string(&self.text)),
+        }
+    }
+}
+
+/// The name of `arg`, if it is `name = expr`, and its expression.
+fn named(arg: &Expr) -> (Option<syn::Ident>, proc_macro2::TokenStream) {
+    if let Expr::Assign(assign) = arg {
+        if let Expr::Path(path) = &*assign.left {
+            if let Some(name) = path.path.get_ident() {
+                return (Some(name.clone()), assign.right.to_token_stream());
+            }
+        }
+    }
+    (None, arg.to_token_stream())
+}
+
+/// The argument a made makes from `value`, a reference to the value of the argument it was made from,
+/// given the `versions` of the made, by variant (see [`CFStr::merged`]).
+fn from(versions: &[Made], value: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    match &versions[0] {
+        Made::Formatter(formatter) => {
+            let formatter = proc_macro2::Ident::new(formatter, proc_macro2::Span::call_site());
+            let formatter = if FORMATTERS.contains(&&*formatter.to_string()) {
+                quote::quote! { ::comat::format::#formatter }
+            } else {
+                formatter.into_token_stream()
+            };
+            quote::quote! { ::comat::format::Formatted(#value, #formatter) }
+        }
+        Made::Scale(steps, _) => {
+            fn scale(made: &Made) -> (&[(String, String)], &str) {
+                match made {
+                    Made::Scale(steps, past) => (steps, past),
+                    _ => unreachable!("merged strings make the same kinds of arguments"),
+                }
+            }
+            let past = choose(versions, |made| scale(made).1);
+            let steps = steps.iter().enumerate().map(|(i, (threshold, _))| {
+                let threshold = threshold
+                    .parse::<proc_macro2::TokenStream>()
+                    .unwrap_or_default();
+                let ansi = choose(versions, |made| &scale(made).0[i].1);
+                quote::quote! { if *__comat_value < (#threshold) { #ansi } else }
+            });
+            quote::quote! { ::comat::format::Painted::scaled(#value, |__comat_value| #(#steps)* { #past }) }
+        }
+        Made::Style(style) => {
+            let style = style
+                .parse::<proc_macro2::TokenStream>()
+                .unwrap_or_default();
+            quote::quote! { ::comat::format::Styled(#value, &(#style)) }
+        }
+        Made::Literal(_) | Made::Source(..) | Made::Notify(..) | Made::Image(..) => {
+            unreachable!("these are not made from an argument")
+        }
+    }
+}
+
+/// A string with `versions`, by variant, as an expression: the string, if they are all the same, or the one for `__comat_variant`.
+fn choose<T>(versions: &[T], string: impl Fn(&T) -> &str) -> proc_macro2::TokenStream {
+    let strings = versions.iter().map(string).collect::<Vec<_>>();
+    if strings.iter().all(|s| *s == strings[0]) {
+        let string = strings[0];
+        quote::quote! { #string }
+    } else {
+        quote::quote! { [#(#strings),*][__comat_variant] }
+    }
+}
+
+#[cfg(feature = "std")]
+/// Whether `a` and `b`, made by versions of the same string, are the same but for their escapes, so one argument can make either.
+fn alike(a: &Made, b: &Made) -> bool {
+    match (a, b) {
+        (Made::Formatter(a), Made::Formatter(b)) | (Made::Style(a), Made::Style(b)) => a == b,
+        (Made::Scale(a, _), Made::Scale(b, _)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|((a, _), (b, _))| a == b)
+        }
+        (Made::Literal(_), Made::Literal(_)) => true,
+        (Made::Source(a, b), Made::Source(c, d))
+        | (Made::Notify(a, b), Made::Notify(c, d))
+        | (Made::Image(a, b), Made::Image(c, d)) => a == c && b == d,
+        _ => false,
+    }
+}
+
+#[cfg(feature = "std")]
+/// The text between the blocks of a format string, with `{{` and `}}` as `{` and `}`, and the insides of the blocks.
+/// There is one more text than there are blocks, as the string starts and ends with text.
+fn pieces(text: &str) -> (Vec<String>, Vec<&str>) {
+    let (mut texts, mut blocks) = (vec![String::new()], vec![]);
+    let mut rest = text;
+    while let Some(at) = rest.find(['{', '}']) {
+        let last = texts.last_mut().expect("one more text than blocks");
+        last.push_str(&rest[..at]);
+        let brace = &rest[at..=at];
+        rest = &rest[at + 1..];
+        if let Some(after) = rest.strip_prefix(brace) {
+            last.push_str(brace);
+            rest = after;
+        } else if brace == "}" {
+            // not a valid format string, which is checked elsewhere
+            last.push_str(brace);
+        } else {
+            let end = rest.find('}').unwrap_or(rest.len());
+            blocks.push(&rest[..end]);
+            rest = rest.get(end + 1..).unwrap_or_default();
+            texts.push(String::new());
+        }
+    }
+    texts
+        .last_mut()
+        .expect("one more text than blocks")
+        .push_str(rest);
+    (texts, blocks)
+}
+
+/// Where the value of a block made from an argument comes from.
+enum Value {
+    /// An expression for a reference to it.
+    Ref(proc_macro2::TokenStream),
+    /// The positional argument at this index of the arguments.
+    Positional(usize),
+}
+
+/// How a string uses its arguments.
+struct Uses {
+    /// How many blocks use each argument, by name or position.
+    count: HashMap<String, usize>,
+    /// The name or position of the argument each [`Made`] was made from.
+    keys: Vec<String>,
+}
+
+/// The insides of the `{..}` blocks of a format string.
+fn blocks(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || loop {
+        let at = rest.find('{')?;
+        rest = &rest[at + 1..];
+        if let Some(after) = rest.strip_prefix('{') {
+            rest = after;
+            continue;
+        }
+        let end = rest.find('}').unwrap_or(rest.len());
+        let block = &rest[..end];
+        rest = &rest[end..];
+        return Some(block);
+    })
+}
@@ -0,0 +1,214 @@
+//! `#[colorize]`: swap the std formatting macros for the comat ones, at the token level.
+use std::collections::HashSet;
+
+use comat_parser::{Options, Theme};
+use proc_macro2::{Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// The std macros with comat versions, and how many arguments come before their format string.
+const MACROS: &[(&str, &str, usize)] = &[
+    ("println", "cprintln", 0),
+    ("print", "cprint", 0),
+    ("format", "cformat", 0),
+    ("format_args", "cformat_args", 0),
+    ("panic", "cpanic", 0),
+    ("write", "cwrite", 1),
+    ("writeln", "cwriteln", 1),
+];
+
+/// What is needed to tell whether a format string is comat's.
+struct Context {
+    theme: Theme,
+    /// The identifiers in the item, which may be bindings a `{name}` captures.
+    bound: HashSet<String>,
+}
+
+/// Rewrite every `println!("..")` (and the rest of [`MACROS`]) in `tokens` whose format string is comat's (see [`comat`]).
+pub fn colorize(tokens: TokenStream) -> Result<TokenStream, String> {
+    let mut bound = HashSet::new();
+    idents(tokens.clone(), &mut bound);
+    let context = Context {
+        theme: Theme::load()?,
+        bound,
+    };
+    Ok(context.colorize(tokens))
+}
+
+impl Context {
+    fn colorize(&self, tokens: TokenStream) -> TokenStream {
+        let mut out = Vec::<TokenTree>::new();
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Group(group) => {
+                    let mut new = Group::new(group.delimiter(), self.colorize(group.stream()));
+                    new.set_span(group.span());
+                    out.push(new.into());
+                }
+                TokenTree::Ident(ident) => {
+                    // `std::println!` is left alone, as is anything else not followed by `!(..)`
+                    let pathed =
+                        matches!(&out[..], [.., TokenTree::Punct(p)] if p.as_char() == ':');
+                    let replacement = MACROS
+                        .iter()
+                        .find(|&&(name, ..)| ident == name)
+                        .filter(|_| !pathed)
+                        .filter(|_| matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == '!'));
+                    out.push(ident.clone().into());
+                    let Some(&(_, comat, before)) = replacement else {
+                        continue;
+                    };
+                    let bang = tokens.next().expect("peeked");
+                    match tokens.next() {
+                        Some(TokenTree::Group(args)) => {
+                            let rewritten = self.rewrite(args.stream(), before);
+                            if rewritten.is_some() {
+                                out.pop();
+                                out.push(Punct::new(':', Spacing::Joint).into());
+                                out.push(Punct::new(':', Spacing::Alone).into());
+                                out.push(Ident::new("comat", Span::call_site()).into());
+                                out.push(Punct::new(':', Spacing::Joint).into());
+                                out.push(Punct::new(':', Spacing::Alone).into());
+                                out.push(Ident::new(comat, ident.span()).into());
+                            }
+                            out.push(bang);
+                            let stream = rewritten.unwrap_or_else(|| args.stream());
+                            let mut new = Group::new(args.delimiter(), self.colorize(stream));
+                            new.set_span(args.span());
+                            out.push(new.into());
+                        }
+                        Some(other) => {
+                            out.push(bang);
+                            out.extend(self.colorize(TokenStream::from(other)));
+                        }
+                        None => out.push(bang),
+                    }
+                }
+                token => out.push(token),
+            }
+        }
+        out.into_iter().collect()
+    }
+
+    /// The arguments, with the format string after `before` commas escaped for comat, if it is a literal that is [`comat`](Self::comat)'s.
+    fn rewrite(&self, args: TokenStream, before: usize) -> Option<TokenStream> {
+        let mut args = args.into_iter().collect::<Vec<_>>();
+        let mut commas = 0;
+        let at = args.iter().position(|token| {
+            let at = commas == before;
+            commas += usize::from(matches!(token, TokenTree::Punct(p) if p.as_char() == ','));
+            at
+        })?;
+        let TokenTree::Literal(literal) = &args[at] else {
+            return None;
+        };
+        let syn::Lit::Str(string) = syn::Lit::new(literal.clone()) else {
+            return None;
+        };
+        let string = string.value();
+        if !self.comat(&string) {
+            return None;
+        }
+        let mut new = Literal::string(&escape(&string));
+        new.set_span(literal.span());
+        args[at] = new.into();
+        Some(args.into_iter().collect())
+    }
+
+    /// Whether comat should format `string`: when it has blocks comat colors, and none that std would format differently, so that code that worked keeps printing the same.
+    ///
+    /// A block std would format differently is a token that is also the name of something in the item, like `{error}` when there is an `error` variable,
+    /// or an argument with a std spec that is also a color, like `{n:b}` with the `aliases` feature.
+    fn comat(&self, string: &str) -> bool {
+        let mut colored = false;
+        for block in blocks(string) {
+            let whole = format!("{{{block}}}");
+            let untouched = comat_parser::parse(&whole, &self.theme, &Options::default())
+                .is_ok_and(|parsed| parsed.text == whole && parsed.made.is_empty());
+            if untouched {
+                continue;
+            }
+            let ambiguous = match block.split_once(':') {
+                None => ident(block) && self.bound.contains(block),
+                Some((arg, spec)) => (arg.is_empty() || ident(arg)) && std_spec(spec),
+            };
+            if ambiguous {
+                return false;
+            }
+            colored = true;
+        }
+        colored
+    }
+}
+
+/// Every identifier in `tokens` that could be a binding, which is any that does not come after a `.`, like a field or a method.
+fn idents(tokens: TokenStream, out: &mut HashSet<String>) {
+    let mut dot = false;
+    for token in tokens {
+        match &token {
+            TokenTree::Group(group) => idents(group.stream(), out),
+            TokenTree::Ident(ident) if !dot => {
+                out.insert(ident.to_string());
+            }
+            _ => {}
+        }
+        dot = matches!(&token, TokenTree::Punct(p) if p.as_char() == '.');
+    }
+}
+
+/// The insides of the `{..}` blocks of a format string, skipping `{{` and `}}`.
+fn blocks(string: &str) -> Vec<&str> {
+    let mut out = vec![];
+    let mut rest = string;
+    while let Some(at) = rest.find('{') {
+        rest = &rest[at + 1..];
+        if let Some(after) = rest.strip_prefix('{') {
+            rest = after;
+            continue;
+        }
+        let end = rest.find('}').unwrap_or(rest.len());
+        out.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    out
+}
+
+/// A std format string with its `{{` and `}}` doubled, so comat, which takes them as `{` and `}` of the format string it makes, keeps them escaped.
+fn escape(string: &str) -> String {
+    let mut out = String::with_capacity(string.len());
+    let mut chars = string.chars().peekable();
+    let mut inside = false;
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' | '}' if !inside && chars.peek() == Some(&ch) => {
+                chars.next();
+                out.extend([ch; 4]);
+            }
+            '{' => {
+                inside = true;
+                out.push(ch);
+            }
+            '}' => {
+                inside = false;
+                out.push(ch);
+            }
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Whether `s` is an identifier.
+fn ident(s: &str) -> bool {
+    syn::parse_str::<Ident>(s).is_ok()
+}
+
+/// Whether `spec` is a spec std would take, like `>8.2` or `x?`.
+fn std_spec(spec: &str) -> bool {
+    let kind = spec.trim_start_matches(|c: char| !c.is_ascii_alphabetic() && c != '?');
+    let rest = &spec[..spec.len() - kind.len()];
+    matches!(
+        kind,
+        "" | "?" | "x?" | "X?" | "x" | "X" | "o" | "b" | "e" | "E" | "p"
+    ) && !rest.contains(|c: char| c.is_alphabetic() || c == ':')
+        && !spec.is_empty()
+}
@@ -0,0 +1,84 @@
+//! `#[derive(ComatDisplay)]`.
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{punctuated::Punctuated, Attribute, Data, DeriveInput, Fields, Result};
+
+use crate::cfstr::CFStr;
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream> {
+    let arms = match &input.data {
+        Data::Struct(data) => vec![arm(
+            &input.attrs,
+            quote! { Self },
+            &data.fields,
+            &input.ident,
+        )?],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let name = &variant.ident;
+                arm(
+                    &variant.attrs,
+                    quote! { Self::#name },
+                    &variant.fields,
+                    name,
+                )
+            })
+            .collect::<Result<_>>()?,
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "ComatDisplay does not work on unions",
+            ))
+        }
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
+
+/// `path { fields } => write`, with the `#[comat("..")]` from `attrs`.
+fn arm(
+    attrs: &[Attribute],
+    path: TokenStream,
+    fields: &Fields,
+    name: &syn::Ident,
+) -> Result<TokenStream> {
+    let attr = attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("comat"))
+        .ok_or_else(|| {
+            syn::Error::new_spanned(name, "expected a #[comat(\"..\")] with the format string")
+        })?;
+    let mut cfstr = attr.parse_args::<CFStr>()?;
+    let track = cfstr.track();
+    let pattern = match fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote! { #path { #(#names),* } }
+        }
+        Fields::Unnamed(fields) => {
+            cfstr.tuple_fields();
+            let names = (0..fields.unnamed.len()).map(|i| format_ident!("_{i}"));
+            quote! { #path(#(#names),*) }
+        }
+        Fields::Unit => path,
+    };
+    let args = cfstr.with_args(&Punctuated::new());
+    Ok(quote! {
+        #pattern => {
+            #track
+            ::comat::pad(f, format_args!(#args))
+        }
+    })
+}
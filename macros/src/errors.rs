@@ -0,0 +1,72 @@
+//! `#[errors]`: run the `#[error("..")]` strings of a thiserror type through comat.
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::Result;
+
+use crate::cfstr::CFStr;
+
+/// Color every `#[error("..")]` in `tokens`, giving the new tokens and the items to track the files their colors came from.
+pub fn errors(tokens: TokenStream, track: &mut TokenStream) -> Result<TokenStream> {
+    let mut out = vec![];
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(p) if p.as_char() == '#' => {
+                out.push(TokenTree::Punct(p));
+                if let Some(TokenTree::Group(attr)) = tokens.peek() {
+                    if attr.delimiter() == Delimiter::Bracket {
+                        let attr = attr.clone();
+                        tokens.next();
+                        out.push(respan(&attr, attribute(attr.stream(), track)?).into());
+                    }
+                }
+            }
+            TokenTree::Group(group) => {
+                out.push(respan(&group, errors(group.stream(), track)?).into());
+            }
+            token => out.push(token),
+        }
+    }
+    Ok(out.into_iter().collect())
+}
+
+/// The inside of an attribute: `error("..", args)` gets its string colored, the rest is left alone.
+fn attribute(attr: TokenStream, track: &mut TokenStream) -> Result<TokenStream> {
+    let mut tokens = attr.clone().into_iter();
+    let (Some(TokenTree::Ident(name)), Some(TokenTree::Group(args)), None) =
+        (tokens.next(), tokens.next(), tokens.next())
+    else {
+        return Ok(attr);
+    };
+    if name != "error" || args.delimiter() != Delimiter::Parenthesis {
+        return Ok(attr);
+    }
+    let mut args = args.stream().into_iter();
+    let Some(TokenTree::Literal(fmt)) = args.next() else {
+        // `#[error(transparent)]`
+        return Ok(attr);
+    };
+    let cfstr = syn::parse2::<CFStr>(fmt.to_token_stream())?.plain(fmt.span())?;
+    track.extend(cfstr.track());
+    let stream = cfstr
+        .to_token_stream()
+        .into_iter()
+        .map(|mut token| {
+            token.set_span(fmt.span());
+            token
+        })
+        .chain(args)
+        .collect::<TokenStream>();
+    Ok([
+        TokenTree::Ident(name),
+        TokenTree::Group(Group::new(Delimiter::Parenthesis, stream)),
+    ]
+    .into_iter()
+    .collect())
+}
+
+fn respan(group: &Group, stream: TokenStream) -> Group {
+    let mut new = Group::new(group.delimiter(), stream);
+    new.set_span(group.span());
+    new
+}
@@ -0,0 +1,145 @@
+//! The format string: a literal (or literals next to each other), or `concat!` / `include_str!`, which are expanded here, as proc macros cannot expand them first.
+//! Or the name of a `ctemplate!`, which is already translated, and left for the compiler to expand.
+use std::path::PathBuf;
+
+use proc_macro2::{Literal, Span};
+use syn::{
+    parse::ParseStream, punctuated::Punctuated, spanned::Spanned, Expr, Lit, LitStr, Macro, Path,
+    Result, Token,
+};
+
+/// A format string, and the files that were read to make it.
+#[derive(Clone)]
+pub struct Source {
+    pub value: String,
+    pub span: Span,
+    /// The literal the string was written as, for pointing inside it.
+    pub literal: Option<Literal>,
+    pub files: Vec<PathBuf>,
+    /// The `ctemplate!` this is, instead of a string.
+    pub template: Option<Path>,
+}
+
+impl Source {
+    pub fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(LitStr) {
+            let lit = input.parse::<LitStr>()?;
+            let mut source = Self {
+                value: lit.value(),
+                span: lit.span(),
+                literal: Some(lit.token()),
+                files: vec![],
+                template: None,
+            };
+            // `"a" "b"`, like a string split over lines
+            while input.peek(LitStr) {
+                source.value.push_str(&input.parse::<LitStr>()?.value());
+                source.literal = None;
+            }
+            return Ok(source);
+        }
+        let fork = input.fork();
+        if fork.parse::<Path>().is_ok() && !fork.peek(Token![!]) {
+            let path = input.parse::<Path>()?;
+            return Ok(Self {
+                value: String::new(),
+                span: path.span(),
+                literal: None,
+                files: vec![],
+                template: Some(path),
+            });
+        }
+        let mac = input.parse::<Macro>().map_err(|e| {
+            syn::Error::new(
+                e.span(),
+                "expected a string literal, `concat!`, `include_str!` or a ctemplate!",
+            )
+        })?;
+        let mut files = vec![];
+        Ok(Self {
+            value: expand(&mac, &mut files)?,
+            span: mac
+                .path
+                .segments
+                .last()
+                .map_or_else(Span::call_site, |s| s.ident.span()),
+            literal: None,
+            files,
+            template: None,
+        })
+    }
+}
+
+impl Source {
+    /// An error at the bytes `at` of the string, or at the whole string if they cannot be pointed to.
+    pub fn error(&self, error: comat_parser::Error) -> syn::Error {
+        let Some(at) = error.at else {
+            return syn::Error::new(self.span, error.message);
+        };
+        match self.subspan(at.clone()) {
+            Some(span) => syn::Error::new(span, error.message),
+            None => syn::Error::new(
+                self.span,
+                format!("{} (at byte {} of the string)", error.message, at.start),
+            ),
+        }
+    }
+
+    /// The span of the bytes `at` of the string, on nightly, when it was written without escapes.
+    fn subspan(&self, at: std::ops::Range<usize>) -> Option<Span> {
+        let literal = self.literal.as_ref()?;
+        let repr = literal.to_string();
+        // `"`, or `r#"` for a raw string
+        let open = repr.find('"')? + 1;
+        let close = open + repr[open..].rfind('"')?;
+        (repr[open..close] == self.value)
+            .then(|| literal.subspan(at.start + open..at.end + open))
+            .flatten()
+    }
+}
+
+fn expand(mac: &Macro, files: &mut Vec<PathBuf>) -> Result<String> {
+    let name = mac.path.segments.last().map(|s| s.ident.to_string());
+    match name.as_deref() {
+        Some("concat") => {
+            let mut out = String::new();
+            for part in mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)? {
+                match part {
+                    Expr::Lit(lit) => match lit.lit {
+                        Lit::Str(s) => out.push_str(&s.value()),
+                        Lit::Char(c) => out.push(c.value()),
+                        Lit::Int(i) => out.push_str(i.base10_digits()),
+                        Lit::Float(f) => out.push_str(f.base10_digits()),
+                        Lit::Bool(b) => out.push_str(if b.value { "true" } else { "false" }),
+                        lit => return Err(syn::Error::new(lit.span(), "cannot concatenate this")),
+                    },
+                    Expr::Macro(m) => out.push_str(&expand(&m.mac, files)?),
+                    part => {
+                        return Err(syn::Error::new_spanned(
+                            part,
+                            "only literals and `concat!` / `include_str!` can be concatenated",
+                        ))
+                    }
+                }
+            }
+            Ok(out)
+        }
+        Some("include_str") => {
+            let path = mac.parse_body::<LitStr>()?;
+            // like include_str, relative to the file this is called from
+            let file = proc_macro::Span::call_site()
+                .local_file()
+                .and_then(|file| Some(file.parent()?.join(path.value())))
+                .unwrap_or_else(|| PathBuf::from(path.value()));
+            let contents = std::fs::read_to_string(&file).map_err(|e| {
+                syn::Error::new(path.span(), format!("couldnt read {}: {e}", file.display()))
+            })?;
+            files.push(std::fs::canonicalize(&file).unwrap_or(file));
+            Ok(contents)
+        }
+        _ => Err(syn::Error::new_spanned(
+            &mac.path,
+            "only `concat!` and `include_str!` can be used as the format string",
+        )),
+    }
+}
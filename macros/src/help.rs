@@ -0,0 +1,68 @@
+//! `#[clap]`: turn `#[comat_help("..")]`s into clap's `help`s.
+use proc_macro2::TokenStream;
+use syn::spanned::Spanned;
+use syn::{Attribute, Data, DeriveInput, Fields, Result};
+
+use crate::cfstr::CFStr;
+
+/// Replace the `#[comat_help("..")]` and `#[comat_long_help("..")]` attributes of `input` with clap ones,
+/// giving the items to track the files their colors came from.
+pub fn clap(input: &mut DeriveInput) -> Result<TokenStream> {
+    let mut track = TokenStream::new();
+    rewrite(&mut input.attrs, "command", "about", &mut track)?;
+    let fields: Vec<&mut Fields> = match &mut input.data {
+        Data::Struct(data) => vec![&mut data.fields],
+        Data::Enum(data) => {
+            let mut fields = vec![];
+            for variant in &mut data.variants {
+                rewrite(&mut variant.attrs, "command", "about", &mut track)?;
+                fields.push(&mut variant.fields);
+            }
+            fields
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span,
+                "clap does not work on unions",
+            ))
+        }
+    };
+    for field in fields.into_iter().flat_map(|fields| fields.iter_mut()) {
+        rewrite(&mut field.attrs, "arg", "help", &mut track)?;
+    }
+    Ok(track)
+}
+
+/// `#[comat_help("..")]` to `#[kind(help = "..")]`, and `#[comat_long_help("..")]` to `#[kind(long_help = "..")]`,
+/// where `help` is what the short help is called in `kind`.
+fn rewrite(attrs: &mut [Attribute], kind: &str, help: &str, track: &mut TokenStream) -> Result<()> {
+    let kind = syn::Ident::new(kind, proc_macro2::Span::call_site());
+    for attr in attrs {
+        let key = if attr.path().is_ident("comat_help") {
+            help.to_string()
+        } else if attr.path().is_ident("comat_long_help") {
+            format!("long_{help}")
+        } else {
+            continue;
+        };
+        let key = syn::Ident::new(&key, attr.path().span());
+        let cfstr = attr
+            .parse_args_with(|input: syn::parse::ParseStream| {
+                let cfstr = input.parse::<CFStr>()?;
+                if !input.is_empty() {
+                    return Err(input.error("help cannot take format arguments"));
+                }
+                Ok(cfstr)
+            })?
+            .plain(attr.path().span())?;
+        if !cfstr.is_literal() {
+            return Err(syn::Error::new_spanned(
+                &attr.meta,
+                "help cannot take format arguments",
+            ));
+        }
+        track.extend(cfstr.track());
+        *attr = syn::parse_quote! { #[#kind(#key = #cfstr)] };
+    }
+    Ok(())
+}
@@ -0,0 +1,2246 @@
+//! proc macros for [comat](https://docs.rs/comat). use that crate instead.
+#![forbid(unsafe_code)]
+#![warn(clippy::pedantic, clippy::dbg_macro, missing_docs)]
+use proc_macro::TokenStream;
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::{parse::Parse, parse_macro_input, punctuated::Punctuated, Expr, Result, Token};
+
+mod alias;
+mod cfstr;
+mod colorize;
+mod display;
+mod errors;
+mod expand;
+mod help;
+mod sgr;
+use cfstr::CFStr;
+
+#[proc_macro]
+/// Macro that simply modifies the format string to have colors.
+/// Mostly for testing. Use [`cformat_args!`] instead where possible.
+pub fn comat(input: TokenStream) -> TokenStream {
+    let str = parse_macro_input!(input as CFStr);
+    match str.plain(proc_macro2::Span::call_site()) {
+        Ok(str) => str.to_token_stream().into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+/// The [visible width](https://docs.rs/comat/latest/comat/fn.visible_width.html) of a colored string, at compile time.
+/// ```
+/// # use comat::cwidth;
+/// const WIDTH: usize = cwidth!("{bold}[{reset}{green}ok{reset}{bold}]{reset}");
+/// assert_eq!(WIDTH, 4);
+/// ```
+pub fn cwidth(input: TokenStream) -> TokenStream {
+    let str = parse_macro_input!(input as CFStr);
+    if !str.is_literal() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "cwidth! only works on text, without format arguments",
+        )
+        .into_compile_error()
+        .into();
+    }
+    quote! {{
+        const WIDTH: usize = ::comat::visible_width(#str);
+        WIDTH
+    }}
+    .into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Format text, colorfully, and [wrap](https://docs.rs/comat/latest/comat/fn.wrap.html) it at a width.
+/// ```
+/// # use comat::*;
+/// let help = cwrap!(12, "{bold}usage{reset}: {green}comat{reset} [options] <file>");
+/// assert_eq!(
+///     help,
+///     "\x1b[1musage\x1b[0m: \x1b[0;34;32mcomat\x1b[0m\n[options]\n<file>"
+/// );
+/// ```
+pub fn cwrap(input: TokenStream) -> TokenStream {
+    let Two { a, cfstr, args } = parse_macro_input!(input as Two);
+    let track = cfstr.track();
+    let args = cfstr.with_args(&args);
+    quote! {{ #track ::comat::wrap(&format!(#args), #a) }}.into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Put a colored prefix in front of every line of some text, with [`prefixed`](https://docs.rs/comat/latest/comat/fn.prefixed.html).
+///
+/// The text can be a format string, with its arguments:
+/// ```
+/// # use comat::*;
+/// let (a, b) = (1, 2);
+/// let s = cprefixed!("{dim}│ {reset}", "{a:red}\n{b:red}");
+/// assert_eq!(s, "\x1b[2m│ \x1b[0m\x1b[0;34;31m1\x1b[0m\n\x1b[2m│ \x1b[0m\x1b[0;34;31m2\x1b[0m");
+/// ```
+/// or anything that is [`AsRef<str>`]:
+/// ```
+/// # use comat::*;
+/// let output = String::from("compiling\nfinished");
+/// let s = cprefixed!("{bold_blue}>{reset} ", output);
+/// assert_eq!(s, "\x1b[1;34;34m>\x1b[0m compiling\n\x1b[1;34;34m>\x1b[0m finished");
+/// ```
+pub fn cprefixed(input: TokenStream) -> TokenStream {
+    let Prefixed { prefix, text } = parse_macro_input!(input as Prefixed);
+    let track = prefix.track();
+    let text = match text {
+        Ok(One { cfstr, args }) => {
+            let track = cfstr.track();
+            let args = cfstr.with_args(&args);
+            quote! { &{ #track format!(#args) } }
+        }
+        Err(text) => quote! { ::core::convert::AsRef::<str>::as_ref(&#text) },
+    };
+    quote! {{ #track ::comat::prefixed(#prefix, #text) }}.into()
+}
+
+#[cfg(feature = "std")]
+struct Prefixed {
+    prefix: CFStr,
+    text: std::result::Result<One, Expr>,
+}
+
+#[cfg(feature = "std")]
+impl Parse for Prefixed {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let prefix = input.parse::<CFStr>()?;
+        if !prefix.is_literal() {
+            return Err(input.error("the prefix cannot take format arguments"));
+        }
+        input.parse::<Token![,]>()?;
+        let text = if input.peek(syn::LitStr) {
+            Ok(input.parse::<One>()?)
+        } else {
+            let text = input.parse::<Expr>()?;
+            let _ = input.parse::<Token![,]>();
+            Err(text)
+        };
+        Ok(Self { prefix, text })
+    }
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Format text, colorfully, and draw a box around it, with [`boxed`](https://docs.rs/comat/latest/comat/fn.boxed.html).
+///
+/// Before the string, the lines can be picked (`single`, `double`, `rounded` or `heavy`) and `border(..)` colors them.
+/// ```
+/// # use comat::*;
+/// let version = "1.2";
+/// let b = cbox!(rounded, border(dim), "{bold}release v{version}{reset}\nall tests passed");
+/// assert_eq!(b, "\
+/// \x1b[2m╭──────────────────╮\x1b[0m
+/// \x1b[2m│ \x1b[0m\x1b[1mrelease v1.2\x1b[0m    \x1b[2m │\x1b[0m
+/// \x1b[2m│ \x1b[0mall tests passed\x1b[2m │\x1b[0m
+/// \x1b[2m╰──────────────────╯\x1b[0m");
+/// ```
+pub fn cbox(input: TokenStream) -> TokenStream {
+    let Boxed {
+        shape,
+        border,
+        text,
+    } = parse_macro_input!(input as Boxed);
+    let track = text.cfstr.track();
+    let border = border.map_or_else(|| quote! { "" }, ToTokens::into_token_stream);
+    quote! {{ #track ::comat::boxed(&format!(#text), #border, ::comat::Border::#shape) }}.into()
+}
+
+#[cfg(feature = "std")]
+struct Boxed {
+    shape: syn::Ident,
+    border: Option<CFStr>,
+    text: One,
+}
+
+#[cfg(feature = "std")]
+impl Parse for Boxed {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let mut shape = syn::Ident::new("Single", proc_macro2::Span::call_site());
+        let mut border = None;
+        while input.peek(syn::Ident) && (input.peek2(Token![,]) || input.peek2(syn::token::Paren)) {
+            let flag = input.fork().parse::<syn::Ident>()?;
+            match &*flag.to_string() {
+                "single" | "double" | "rounded" | "heavy" => {
+                    input.parse::<syn::Ident>()?;
+                    let name = flag.to_string();
+                    shape = syn::Ident::new(
+                        &format!("{}{}", name[..1].to_uppercase(), &name[1..]),
+                        flag.span(),
+                    );
+                }
+                "border" => {
+                    input.parse::<syn::Ident>()?;
+                    border = Some(style(input)?);
+                }
+                // leave the rest (like `dedent`) to the string
+                _ => break,
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(Self {
+            shape,
+            border,
+            text: input.parse()?,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// A colored progress [bar](https://docs.rs/comat/latest/comat/fn.bar.html), from a fraction (from 0 to 1) and a width in cells, to put in any string.
+///
+/// After them, `filled = ..` and `empty = ..` pick the colors of the two parts (`green` and `dim` if not given), like `filled = on_green` or `empty = (dim, italic)`.
+/// ```
+/// # use comat::*;
+/// let (done, total) = (3, 8);
+/// let progress = f64::from(done) / f64::from(total);
+/// cprintln!("{} {done}/{total}", cbar!(progress, 20));
+/// assert_eq!(cbar!(0.5, 2, filled = bold_green, empty = ()), cformat!("{bold_green}█{reset}░"));
+/// ```
+pub fn cbar(input: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let fraction = input.parse::<Expr>()?;
+        input.parse::<Token![,]>()?;
+        let width = input.parse::<Expr>()?;
+        let (mut filled, mut empty) = (quote! { green }, quote! { dim });
+        while input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let part = input.parse::<syn::Ident>()?;
+            input.parse::<Token![=]>()?;
+            let colors = colors(input)?;
+            match &*part.to_string() {
+                "filled" => filled = colors,
+                "empty" => empty = colors,
+                _ => return Err(syn::Error::new(part.span(), "expected filled or empty")),
+            }
+        }
+        Ok((fraction, width, code(&filled)?, code(&empty)?))
+    };
+    let (fraction, width, (filled, ftrack), (empty, etrack)) =
+        parse_macro_input!(input with parser);
+    quote! {{ #ftrack #etrack ::comat::bar(#fraction, #width, #filled, #empty) }}.into()
+}
+
+/// Some colors after a `name =`: one token (like `on_green`), or a list of them in parentheses (like `(dim, italic)`).
+fn colors(input: syn::parse::ParseStream) -> Result<proc_macro2::TokenStream> {
+    if input.peek(syn::token::Paren) {
+        let content;
+        syn::parenthesized!(content in input);
+        return content.parse();
+    }
+    let mut colors = proc_macro2::TokenStream::new();
+    while !input.is_empty() && !input.peek(Token![,]) {
+        colors.extend([input.parse::<proc_macro2::TokenTree>()?]);
+    }
+    Ok(colors)
+}
+
+#[proc_macro]
+/// A frame of a [spinner](https://docs.rs/comat/latest/comat/spinner/index.html), colored, for a counter that goes up as the work does.
+///
+/// After it, `frames = ..` picks the frames (an expression for a `&[&str]`, [`BRAILLE`](https://docs.rs/comat/latest/comat/spinner/constant.BRAILLE.html) if not given),
+/// and `style = ..` their colors (`cyan` if not given), like `style = bold_magenta` or `style = (bold, green)`.
+/// ```
+/// # use comat::*;
+/// for tick in 0..20 {
+///     cprint_flush!("\r{} {bold}building{reset}", cspinner!(tick));
+/// }
+/// assert_eq!(cspinner!(5, frames = spinner::LINE, style = ()).to_string(), "\\");
+/// assert_eq!(cspinner!(0, style = green).to_string(), cformat!("{green}⠋{reset}"));
+/// ```
+pub fn cspinner(input: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let tick = input.parse::<Expr>()?;
+        let (mut frames, mut style) = (quote! { ::comat::spinner::BRAILLE }, quote! { cyan });
+        while input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let part = input.parse::<syn::Ident>()?;
+            input.parse::<Token![=]>()?;
+            match &*part.to_string() {
+                "frames" => frames = input.parse::<Expr>()?.into_token_stream(),
+                "style" => style = colors(input)?,
+                _ => return Err(syn::Error::new(part.span(), "expected frames or style")),
+            }
+        }
+        Ok((tick, frames, code(&style)?))
+    };
+    let (tick, frames, (style, track)) = parse_macro_input!(input with parser);
+    let frame = quote! { ::comat::spinner::frame(#frames, #tick) };
+    if style.is_empty() {
+        return quote! {{ #track #frame }}.into();
+    }
+    quote! {{ #track ::comat::format::Paint(#frame, #style) }}.into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Line up colored cells into [columns](https://docs.rs/comat/latest/comat/fn.columns.html).
+///
+/// Each row is a list of format strings (which can capture variables).
+/// ```
+/// # use comat::*;
+/// let (ok, failed) = (12, 1);
+/// let table = ccolumns!(
+///     ["{bold}passed{reset}", "{ok:green}"],
+///     ["{bold}failed{reset}", "{failed:red}", "{bold_red}!{reset}"],
+/// );
+/// assert_eq!(table, cformat!("\
+/// {bold}passed{reset}  {ok:green}
+/// {bold}failed{reset}  {failed:red}   {bold_red}!{reset}"));
+/// ```
+pub fn ccolumns(input: TokenStream) -> TokenStream {
+    let parse = Punctuated::<Row, Token![,]>::parse_terminated;
+    let rows = parse_macro_input!(input with parse);
+    let mut track = proc_macro2::TokenStream::new();
+    let rows = rows.iter().map(|Row(cells)| {
+        let cells = cells.iter().map(|cell| {
+            track.extend(cell.track());
+            let args = cell.with_args(&Punctuated::new());
+            quote! { format!(#args) }
+        });
+        quote! { ::std::vec![#(#cells),*] }
+    });
+    let rows = rows.collect::<Vec<_>>();
+    quote! {{ #track ::comat::columns::<::std::vec::Vec<::std::string::String>, _>(&[#(#rows),*]) }}
+        .into()
+}
+
+#[cfg(feature = "std")]
+struct Row(Punctuated<CFStr, Token![,]>);
+
+#[cfg(feature = "std")]
+impl Parse for Row {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let content;
+        syn::bracketed!(content in input);
+        Ok(Self(Punctuated::parse_terminated(&content)?))
+    }
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// A colored unified [diff](https://docs.rs/comat/latest/comat/fn.diff.html) of two strings (anything [`AsRef<str>`]).
+///
+/// Starting with `inline,` also highlights what changed inside the lines.
+/// ```
+/// # use comat::*;
+/// let (expected, got) = ("one\ntwo\n", String::from("one\ntoo\n"));
+/// let d = cdiff!(inline, expected, got);
+/// assert_eq!(d, "\
+/// \x1b[0;34;36m@@ -1,2 +1,2 @@\x1b[0m
+///  one
+/// \x1b[0;34;31m-t\x1b[7mw\x1b[27mo\x1b[0m
+/// \x1b[0;34;32m+t\x1b[7mo\x1b[27mo\x1b[0m
+/// ");
+/// ```
+pub fn cdiff(input: TokenStream) -> TokenStream {
+    let parse = Punctuated::<Expr, Token![,]>::parse_terminated;
+    let args = parse_macro_input!(input with parse);
+    let mut args = args.into_iter().collect::<Vec<_>>();
+    let inline = matches!(&args[..], [Expr::Path(p), _, _] if p.path.is_ident("inline"));
+    if inline {
+        args.remove(0);
+    }
+    let [old, new] = &args[..] else {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "expected the old and new text (and optionally `inline` before them)",
+        )
+        .into_compile_error()
+        .into();
+    };
+    quote! {
+        ::comat::diff(
+            ::core::convert::AsRef::<str>::as_ref(&#old),
+            ::core::convert::AsRef::<str>::as_ref(&#new),
+            #inline,
+        )
+    }
+    .into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Assert that two strings (anything [`AsRef<str>`]) are the same, ignoring their escape sequences, showing a [`cdiff!`] if they are not.
+///
+/// Like [`assert_eq`], a message (and its arguments) can follow.
+/// ```
+/// # use comat::*;
+/// let out = cformat!("{bold}ok{reset}: {} tests", 3);
+/// assert_colored_eq!(out, "ok: 3 tests");
+/// assert_colored_eq!(out, cformat!("{green}ok{reset}: 3 tests"), "colors are ignored");
+/// ```
+/// ```should_panic
+/// # use comat::*;
+/// assert_colored_eq!(cformat!("{red}one\ntwo"), "one\nthree");
+/// ```
+pub fn assert_colored_eq(input: TokenStream) -> TokenStream {
+    let parse = Punctuated::<Expr, Token![,]>::parse_terminated;
+    let args = parse_macro_input!(input with parse);
+    let mut args = args.into_iter();
+    let (Some(left), Some(right)) = (args.next(), args.next()) else {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "expected the two strings to compare (and optionally a message)",
+        )
+        .into_compile_error()
+        .into();
+    };
+    let (format, message) = match args.next() {
+        Some(message) => {
+            let args = args.collect::<Vec<_>>();
+            (": {}\n{}", quote! { format_args!(#message #(, #args)*), })
+        }
+        None => ("\n{}", quote! {}),
+    };
+    let format = format!("assertion `left == right` failed, ignoring colors{format}");
+    quote! {
+        match (&#left, &#right) {
+            (left, right) => {
+                let left = ::comat::strip(::core::convert::AsRef::<str>::as_ref(left));
+                let right = ::comat::strip(::core::convert::AsRef::<str>::as_ref(right));
+                if left != right {
+                    panic!(
+                        #format,
+                        #message
+                        ::comat::Render(::comat::diff(&left, &right, true), ::comat::Stream::Stderr),
+                    );
+                }
+            }
+        }
+    }
+    .into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// A colored [hexdump](https://docs.rs/comat/latest/comat/struct.Hexdump.html) of some bytes (anything [`AsRef<[u8]>`](AsRef)).
+///
+/// Before the bytes, the colors of the `offset`s, `zero` bytes, `printable` ascii and `other` bytes can be changed.
+/// ```
+/// # use comat::*;
+/// let dump = chexdump!(offset(bold_black), printable(green), b"hi\0");
+/// assert_eq!(dump, "\
+/// \x1b[1;34;30m00000000\x1b[0m  \x1b[0;34;32m68 69 \x1b[0m\x1b[2m00 \x1b[0m                                         \
+/// |\x1b[0;34;32mhi\x1b[0m\x1b[2m.\x1b[0m|");
+/// ```
+pub fn chexdump(input: TokenStream) -> TokenStream {
+    let Styled { styles, value } =
+        match Styled::parse(input, &["offset", "zero", "printable", "other"]) {
+            Ok(x) => x,
+            Err(e) => return e.into_compile_error().into(),
+        };
+    quote! {{
+        #[allow(clippy::needless_update)]
+        let style = ::comat::Hexdump { #styles ..::comat::Hexdump::DEFAULT };
+        style.dump(::core::convert::AsRef::<[u8]>::as_ref(&#value))
+    }}
+    .into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Pretty print some [json](https://docs.rs/comat/latest/comat/struct.Json.html), colorfully.
+///
+/// Takes anything that [`Display`](std::fmt::Display)s as json, like a `&str`, and reads what it displays.
+/// With the `serde_json` feature, a `serde_json::Value` is [walked](https://docs.rs/comat/latest/comat/struct.Json.html#method.value) instead.
+/// Before it, the colors of `key`s, `string`s, `number`s, `literal`s (`true`, `false` and `null`) and `punctuation` can be changed.
+/// ```
+/// # use comat::*;
+/// let json = r#"{"name":"comat","tags":["color",1,null]}"#;
+/// assert_eq!(cjson!(key(bold), punctuation(), json), "{
+///   \x1b[1m\"name\"\x1b[0m: \x1b[0;34;32m\"comat\"\x1b[0m,
+///   \x1b[1m\"tags\"\x1b[0m: [
+///     \x1b[0;34;32m\"color\"\x1b[0m,
+///     \x1b[0;34;36m1\x1b[0m,
+///     \x1b[0;34;35mnull\x1b[0m
+///   ]
+/// }");
+/// ```
+pub fn cjson(input: TokenStream) -> TokenStream {
+    let Styled { styles, value } = match Styled::parse(
+        input,
+        &["key", "string", "number", "literal", "punctuation"],
+    ) {
+        Ok(x) => x,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    quote! {{
+        #[allow(clippy::needless_update)]
+        let style = ::comat::Json { #styles ..::comat::Json::DEFAULT };
+        #[allow(unused_imports)]
+        use ::comat::{__JsonLex as _, __JsonWalk as _};
+        (&::comat::__JsonSource(&#value)).json(&style)
+    }}
+    .into()
+}
+
+#[cfg(feature = "highlight")]
+#[proc_macro]
+/// Highlight some code (anything [`AsRef<str>`]), with [`Code`](https://docs.rs/comat/latest/comat/struct.Code.html).
+///
+/// The language is one of `rust`, `toml`, `sql`, `sh` (or `bash`), `python` and `json`.
+/// Before the code, the colors of `keyword`s, `string`s, `number`s and `comment`s can be changed.
+/// ```
+/// # use comat::*;
+/// let query = "select * from users -- all of them";
+/// assert_eq!(
+///     ccode!(sql, keyword(bold_blue), query),
+///     cformat!("{bold_blue}select{reset} * {bold_blue}from{reset} users {dim}-- all of them{reset}")
+/// );
+/// ```
+pub fn ccode(input: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let lang = input.parse::<syn::Ident>()?;
+        let variant = match &*lang.to_string() {
+            "rust" => "Rust",
+            "toml" => "Toml",
+            "sql" => "Sql",
+            "sh" | "bash" | "shell" => "Shell",
+            "python" => "Python",
+            "json" => "Json",
+            _ => return Err(syn::Error::new(lang.span(), "unknown language")),
+        };
+        input.parse::<Token![,]>()?;
+        let styled = Styled::parse_from(input, &["keyword", "string", "number", "comment"])?;
+        Ok((syn::Ident::new(variant, lang.span()), styled))
+    };
+    let (lang, Styled { styles, value }) = match syn::parse::Parser::parse(parser, input) {
+        Ok(x) => x,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    quote! {{
+        #[allow(clippy::needless_update)]
+        let style = ::comat::Code { #styles ..::comat::Code::DEFAULT };
+        style.highlight(::comat::Lang::#lang, ::core::convert::AsRef::<str>::as_ref(&#value))
+    }}
+    .into()
+}
+
+#[cfg(feature = "std")]
+/// Some `name(colors),` for the fields of a style struct, then a value.
+struct Styled {
+    styles: proc_macro2::TokenStream,
+    value: Expr,
+}
+
+#[cfg(feature = "std")]
+impl Styled {
+    fn parse(input: TokenStream, fields: &[&str]) -> Result<Self> {
+        syn::parse::Parser::parse(
+            |input: syn::parse::ParseStream| Self::parse_from(input, fields),
+            input,
+        )
+    }
+
+    fn parse_from(input: syn::parse::ParseStream, fields: &[&str]) -> Result<Self> {
+        let mut styles = proc_macro2::TokenStream::new();
+        while input.peek(syn::Ident) && input.peek2(syn::token::Paren) && input.peek3(Token![,]) {
+            let name = input.fork().parse::<syn::Ident>()?;
+            if !fields.contains(&&*name.to_string()) {
+                break;
+            }
+            input.parse::<syn::Ident>()?;
+            let style = style(input)?;
+            styles.extend(quote! { #name: #style, });
+            input.parse::<Token![,]>()?;
+        }
+        let value = input.parse()?;
+        let _ = input.parse::<Token![,]>();
+        Ok(Self { styles, value })
+    }
+}
+
+#[proc_macro_attribute]
+/// Color an existing function (or any other item): every `println!`, `print!`, `format!`, `format_args!`, `panic!`, `write!` and `writeln!`
+/// inside it with a string literal becomes its comat version ([`cprintln!`](https://docs.rs/comat/latest/comat/macro.cprintln.html), ..).
+/// ```
+/// # use comat::*;
+/// #[comat::colorize]
+/// fn status(name: &str, ok: bool) -> String {
+///     match ok {
+///         true => format!("{name:bold}: {green}ok{reset}"),
+///         false => format!("{name:bold}: {red}failed{reset}"),
+///     }
+/// }
+/// assert_eq!(status("build", true), cformat!("{:bold}: {green}ok{reset}", "build"));
+/// ```
+/// Only the strings with comat's blocks are colored, and only when std would not format them differently,
+/// so code that already worked prints the same: a string with a token that is also the name of something in the item
+/// (like `{error}`, with an `error` variable), or an argument with a spec std knows that is also a color (like `{n:b}`, with the `aliases` feature), is left to std.
+/// Paths (like `std::println!`) are left alone too, to opt out.
+pub fn colorize(args: TokenStream, item: TokenStream) -> TokenStream {
+    if let Some(arg) = proc_macro2::TokenStream::from(args).into_iter().next() {
+        return syn::Error::new(arg.span(), "colorize takes no arguments")
+            .into_compile_error()
+            .into();
+    }
+    match colorize::colorize(item.into()) {
+        Ok(item) => item.into(),
+        Err(e) => syn::Error::new(proc_macro2::Span::call_site(), e)
+            .into_compile_error()
+            .into(),
+    }
+}
+
+#[proc_macro_attribute]
+/// Color the messages of a [thiserror](https://docs.rs/thiserror) error: every `#[error("..")]` string inside goes through comat.
+///
+/// Put it above the `#[derive(Error)]`, so it runs first.
+/// ```ignore
+/// #[comat::errors]
+/// #[derive(Debug, thiserror::Error)]
+/// enum Error {
+///     #[error("{error}couldnt read{reset} {0:bold}")]
+///     Read(std::path::PathBuf, #[source] std::io::Error),
+///     #[error("{warn}{count}{reset} retries left")]
+///     Retry { count: usize },
+///     #[error(transparent)]
+///     Other(#[from] std::fmt::Error),
+/// }
+/// ```
+/// Formatters (`{x::human_bytes}`) dont work here, as thiserror does the formatting.
+pub fn errors(args: TokenStream, item: TokenStream) -> TokenStream {
+    if let Some(arg) = proc_macro2::TokenStream::from(args).into_iter().next() {
+        return syn::Error::new(arg.span(), "errors takes no arguments")
+            .into_compile_error()
+            .into();
+    }
+    let mut track = proc_macro2::TokenStream::new();
+    match errors::errors(item.into(), &mut track) {
+        Ok(item) => quote! { #item #track }.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+#[proc_macro_attribute]
+/// Colorful help for a [clap](https://docs.rs/clap) derive: `#[comat_help("..")]` and `#[comat_long_help("..")]`
+/// become `help` and `long_help` on the fields, and `about` and `long_about` on the command (and subcommands).
+///
+/// Put it above the `#[derive(Parser)]`, so it runs first.
+/// ```
+/// #[comat::clap]
+/// #[derive(clap::Parser)]
+/// #[comat_help("{bold}comat{reset}: a better coloring crate")]
+/// struct Args {
+///     #[comat_help("the {bold}file{reset} to color")]
+///     file: std::path::PathBuf,
+///     #[arg(short)]
+///     #[comat_help("print {dim}more{reset}")]
+///     #[comat_long_help("print {dim}more{reset}\n\nuse twice to print even more")]
+///     verbose: bool,
+/// }
+/// ```
+/// clap strips the colors when it is not coloring its output.
+///
+/// The help is made when it is compiled, so it cannot take format arguments:
+/// ```compile_fail
+/// #[comat::clap]
+/// #[derive(clap::Parser)]
+/// struct Args {
+///     #[comat_help("the {bold}file{reset} to color, like {}", "a.txt")]
+///     file: std::path::PathBuf,
+/// }
+/// ```
+/// Nor capture them:
+/// ```compile_fail
+/// #[comat::clap]
+/// #[derive(clap::Parser)]
+/// struct Args {
+///     #[comat_help("the {bold}file{reset} to color, like {example}")]
+///     file: std::path::PathBuf,
+/// }
+/// ```
+pub fn clap(args: TokenStream, item: TokenStream) -> TokenStream {
+    if let Some(arg) = proc_macro2::TokenStream::from(args).into_iter().next() {
+        return syn::Error::new(arg.span(), "clap takes no arguments")
+            .into_compile_error()
+            .into();
+    }
+    let mut item = parse_macro_input!(item as syn::DeriveInput);
+    match help::clap(&mut item) {
+        Ok(track) => quote! { #item #track }.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(ComatDisplay, attributes(comat))]
+/// Implement [`Display`](std::fmt::Display) with a comat string, given by `#[comat("..")]`, which can use the fields.
+///
+/// On enums, each variant has its own string. Tuple fields are `{0}`, `{1}`, ...
+/// ```
+/// # use comat::*;
+/// #[derive(ComatDisplay)]
+/// #[comat("{name:bold} <{email:cyan}>")]
+/// struct User {
+///     name: String,
+///     email: String,
+/// }
+///
+/// #[derive(ComatDisplay)]
+/// enum Status {
+///     #[comat("{green}online{reset}")]
+///     Online,
+///     #[comat("{dim}away for {0}m{reset}")]
+///     Away(u32),
+/// }
+///
+/// let user = User { name: "bendn".into(), email: "bend.n@outlook.com".into() };
+/// assert_eq!(user.to_string(), cformat!("{:bold} <{:cyan}>", "bendn", "bend.n@outlook.com"));
+/// assert_eq!(Status::Away(5).to_string(), cformat!("{dim}away for 5m{reset}"));
+/// ```
+/// Widths asked for by the format string it is used in count only the text, not the escapes (see [`pad`](https://docs.rs/comat/latest/comat/fn.pad.html)):
+/// ```
+/// # use comat::*;
+/// # #[derive(ComatDisplay)]
+/// # enum Status {
+/// #     #[comat("{green}online{reset}")]
+/// #     Online,
+/// # }
+/// assert_eq!(format!("{:<8}|", Status::Online), cformat!("{green}online{reset}  |"));
+/// ```
+pub fn comat_display(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    display::derive(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro]
+/// Like [`comat!`], but with every escape sequence wrapped in `\[` and `\]`, so bash knows how wide a prompt is.
+/// ```
+/// # use comat::comat_ps1;
+/// const PS1: &str = comat_ps1!("{bold_green}\\u{reset}:{blue}\\w{reset}$ ");
+/// assert_eq!(PS1, "\\[\x1b[1;34;32m\\]\\u\\[\x1b[0m\\]:\\[\x1b[0;34;34m\\]\\w\\[\x1b[0m\\]$ ");
+/// ```
+pub fn comat_ps1(input: TokenStream) -> TokenStream {
+    let str = parse_macro_input!(input as CFStr);
+    match str.plain(proc_macro2::Span::call_site()) {
+        Ok(mut str) => {
+            str.guard("\\[", "\\]");
+            str.to_token_stream().into()
+        }
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+/// Like [`comat!`], but with every escape sequence wrapped in `%{` and `%}`, so zsh knows how wide a prompt is.
+/// ```
+/// # use comat::comat_zsh;
+/// const PROMPT: &str = comat_zsh!("{bold_green}%n{reset}:{blue}%~{reset}%# ");
+/// assert_eq!(PROMPT, "%{\x1b[1;34;32m%}%n%{\x1b[0m%}:%{\x1b[0;34;34m%}%~%{\x1b[0m%}%# ");
+/// ```
+pub fn comat_zsh(input: TokenStream) -> TokenStream {
+    let str = parse_macro_input!(input as CFStr);
+    match str.plain(proc_macro2::Span::call_site()) {
+        Ok(mut str) => {
+            str.guard("%{", "%}");
+            str.to_token_stream().into()
+        }
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+/// Turn on escape sequences in the Windows console (`ENABLE_VIRTUAL_TERMINAL_PROCESSING`), for stdout and stderr.
+///
+/// Gives whether they work on each stream (a [`Consoles`](https://docs.rs/comat/latest/comat/struct.Consoles.html)), which is always `true` on other platforms.
+/// Where they dont, the printing macros to that stream print [plain](https://docs.rs/comat/latest/comat/fn.set_plain.html) text, rather than escapes the console would show as garbage.
+/// Call it once, at the start of `main`. It calls [`comat::enable_windows_ansi`](https://docs.rs/comat/latest/comat/fn.enable_windows_ansi.html).
+/// ```
+/// # use comat::*;
+/// if !enable_windows_ansi!().stdout {
+///     eprintln!("this console does not support colors");
+/// }
+/// cprintln!("{green}colors!{reset}");
+/// ```
+pub fn enable_windows_ansi(input: TokenStream) -> TokenStream {
+    if let Some(arg) = proc_macro2::TokenStream::from(input).into_iter().next() {
+        return syn::Error::new(arg.span(), "enable_windows_ansi! takes no arguments")
+            .into_compile_error()
+            .into();
+    }
+    quote! { ::comat::enable_windows_ansi() }.into()
+}
+
+#[proc_macro]
+/// The escape sequence for some colors, as a `&'static str`.
+/// ```
+/// # use comat::comat_code;
+/// const WARN: &str = comat_code!(bold_red);
+/// assert_eq!(WARN, "\x1b[1;34;31m");
+/// # #[cfg(not(any(feature = "ansi256", feature = "ansi16")))] // which downgrade it
+/// assert_eq!(comat_code!(italic, #ff8800), "\x1b[3m\x1b[38;2;255;136;0m");
+/// ```
+pub fn comat_code(input: TokenStream) -> TokenStream {
+    match code(&input.into()) {
+        Ok((code, track)) => quote! {{ #track #code }}.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+/// A [`Style`](https://docs.rs/comat/latest/comat/struct.Style.html) from some colors, usable in `const`s.
+/// ```
+/// # use comat::*;
+/// const ERROR: Style = cstyle!(bold_red);
+/// assert_eq!(ERROR, Style::new().bold().fg(Color::Basic(1)));
+/// # #[cfg(not(any(feature = "ansi256", feature = "ansi16")))] // which downgrade it
+/// assert_eq!(cstyle!(italic, #ff8800), Style::new().italic().fg(Color::Rgb(255, 136, 0)));
+/// ```
+pub fn cstyle(input: TokenStream) -> TokenStream {
+    match code(&input.into()).and_then(|(code, track)| {
+        let style =
+            sgr::style(&code).map_err(|e| syn::Error::new(proc_macro2::Span::call_site(), e))?;
+        Ok(quote! {{ #track #style }})
+    }) {
+        Ok(style) => style.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+/// Wrap a value so that it shows in some colors, and resets after, for when the rest of the string cannot be comat syntax.
+///
+/// The wrapper owns the value (pass a reference to keep it), and its format spec only pads the value.
+/// ```
+/// # use comat::paint;
+/// let name = "comat";
+/// let line = format!("hello {:>6}!", paint!(name, bold_red));
+/// assert_eq!(line, "hello \x1b[1;34;31m comat\x1b[0m!");
+/// ```
+pub fn paint(input: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let value = input.parse::<Expr>()?;
+        input.parse::<Token![,]>()?;
+        Ok((value, input.parse::<proc_macro2::TokenStream>()?))
+    };
+    let (value, colors) = parse_macro_input!(input with parser);
+    match code(&colors) {
+        Ok((code, track)) => quote! {{ #track ::comat::format::Paint(#value, #code) }}.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+/// Define a colored template once, to use in place of the string in the macros that format, like [`cprintln!`].
+///
+/// The template is translated here, so it can take options (`ctemplate!(HELP = dedent, "..")`), but not formatters, scales or `[style]`s.
+/// Its arguments have to be given where it is used, as they cannot be captured from there.
+/// Like `macro_rules!`, it can only be used after it is defined, and `pub(crate)` makes it usable by path.
+/// ```
+/// # use comat::*;
+/// ctemplate!(GREETING = "hello {name:bold}!");
+/// let user = "bendn";
+/// cprintln!(GREETING, name = user);
+/// assert_eq!(cformat!(GREETING, name = user), cformat!("hello {user:bold}!"));
+/// ```
+pub fn ctemplate(input: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let vis = input.parse::<syn::Visibility>()?;
+        let name = input.parse::<syn::Ident>()?;
+        input.parse::<Token![=]>()?;
+        let cfstr = input.parse::<CFStr>()?.template(name.span())?;
+        Ok((vis, name, cfstr))
+    };
+    let (vis, name, cfstr) = parse_macro_input!(input with parser);
+    let track = cfstr.track();
+    let export = (!matches!(vis, syn::Visibility::Inherited)).then(|| quote! { #vis use #name; });
+    quote! {
+        #track
+        macro_rules! #name {
+            () => { #cfstr };
+        }
+        #export
+    }
+    .into()
+}
+
+/// The escape sequence for some colors, and the theme files to track.
+fn code(input: &proc_macro2::TokenStream) -> Result<(String, proc_macro2::TokenStream)> {
+    let spec = input.to_string().replace(' ', "");
+    let theme = comat_parser::Theme::load()
+        .map_err(|e| syn::Error::new(proc_macro2::Span::call_site(), e))?;
+    let mut code = String::new();
+    // nothing for no colors
+    for token in comat_parser::split(&spec)
+        .into_iter()
+        .filter(|t| !t.is_empty())
+    {
+        let ansi = theme.get(token).ok_or_else(|| {
+            syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!("unknown color {token:?}"),
+            )
+        })?;
+        code.push_str(&ansi);
+    }
+    let track = theme
+        .files
+        .iter()
+        .filter_map(|path| path.to_str())
+        .map(|path| quote! { const _: &[u8] = include_bytes!(#path); });
+    Ok((code, quote! { #(#track)* }))
+}
+
+#[proc_macro]
+/// Like [`comat!`], but gives a `&'static [u8]`.
+/// ```
+/// # use comat::comat_bytes;
+/// const PROMPT: &[u8] = comat_bytes!("{bold_green}>{reset} ");
+/// assert_eq!(PROMPT, b"\x1b[1;34;32m>\x1b[0m ");
+/// ```
+pub fn comat_bytes(input: TokenStream) -> TokenStream {
+    let str = parse_macro_input!(input as CFStr);
+    match str.plain(proc_macro2::Span::call_site()) {
+        Ok(str) => str.bytes().into_token_stream().into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+/// Like [`comat!`], but with the escape characters written out as text, like `\x1b[1m`,
+/// to see what a string turns into, or for snapshots that can be read.
+/// ```
+/// # use comat::comat_debug;
+/// assert_eq!(comat_debug!("{bold}hi{reset}"), r"\x1b[1mhi\x1b[0m");
+/// ```
+pub fn comat_debug(input: TokenStream) -> TokenStream {
+    let str = parse_macro_input!(input as CFStr);
+    match str.plain(proc_macro2::Span::call_site()) {
+        Ok(mut str) => {
+            str.show_escapes();
+            str.to_token_stream().into()
+        }
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+/// Read a file (relative to the current file, like [`include_str`]) at compile time, and color it.
+///
+/// Gives a `&'static str`. The crate rebuilds when the file changes.
+/// ```
+/// # use comat::cinclude_str;
+/// const README: &str = cinclude_str!("../../README.md");
+/// assert!(README.starts_with("# comat"));
+/// ```
+pub fn cinclude_str(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as syn::LitStr);
+    let str = match syn::parse2::<CFStr>(quote! { include_str!(#path) })
+        .and_then(|str| str.plain(path.span()))
+    {
+        Ok(str) => str,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let track = str.track();
+    quote! {{ #track #str }}.into()
+}
+
+#[proc_macro]
+/// Concatenate string literals (run through comat) and `&'static str` constants into one `&'static str`, at compile time.
+/// ```
+/// # use comat::cconcat;
+/// const NAME: &str = "comat";
+/// const HEADER: &str = cconcat!("{bold}", NAME, "{reset} v", "0.1");
+/// const LINE: &str = cconcat!(HEADER, "{dim}: a better coloring crate{reset}");
+/// assert_eq!(LINE, "\x1b[1mcomat\x1b[0m v0.1\x1b[2m: a better coloring crate\x1b[0m");
+/// ```
+pub fn cconcat(input: TokenStream) -> TokenStream {
+    let parts = parse_macro_input!(input with Punctuated::<Expr, Token![,]>::parse_terminated);
+    let mut literal = true;
+    let mut out = vec![];
+    for part in parts {
+        match part {
+            Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit),
+                ..
+            }) => match syn::parse2::<CFStr>(lit.to_token_stream())
+                .and_then(|cfstr| cfstr.plain(lit.span()))
+            {
+                Ok(cfstr) => out.push(cfstr.into_token_stream()),
+                Err(e) => return e.into_compile_error().into(),
+            },
+            part => {
+                literal = false;
+                out.push(part.into_token_stream());
+            }
+        }
+    }
+    if literal {
+        return quote! { concat!(#(#out),*) }.into();
+    }
+    quote! {{
+        const PARTS: &[&str] = &[#(#out),*];
+        const LEN: usize = {
+            let (mut len, mut i) = (0, 0);
+            while i < PARTS.len() {
+                len += PARTS[i].len();
+                i += 1;
+            }
+            len
+        };
+        const BYTES: [u8; LEN] = {
+            let mut out = [0; LEN];
+            let (mut at, mut i) = (0, 0);
+            while i < PARTS.len() {
+                let part = PARTS[i].as_bytes();
+                let mut j = 0;
+                while j < part.len() {
+                    out[at] = part[j];
+                    at += 1;
+                    j += 1;
+                }
+                i += 1;
+            }
+            out
+        };
+        const STR: &str = match ::core::str::from_utf8(&BYTES) {
+            Ok(s) => s,
+            Err(_) => unreachable!(),
+        };
+        STR
+    }}
+    .into()
+}
+
+#[proc_macro]
+/// Define a macro that gives the comat macro call it wraps some custom tokens.
+///
+/// Each alias is either a single token or a string of comma separated tokens / SGR parameters, like the `[tokens]` of a `comat.toml`.
+/// ```
+/// # use comat::*;
+/// comat_alias! { macro colors { err = bold_red, ok = green, path = "cyan,underline" } }
+/// let file = "Cargo.toml";
+/// assert_eq!(colors!(cformat!("{err}no{reset} {file:path}")), cformat!("{bold_red}no{reset} {file:cyan,underline}"));
+/// ```
+///
+/// It is a `macro_rules!`, so it can be used after it in the module (and the modules in it), or anywhere in the crate with `pub(crate) macro colors { .. }`.
+/// It cannot be plain `pub`, as it is not exported from the crate:
+/// ```compile_fail
+/// # use comat::*;
+/// comat_alias! { pub macro colors { err = bold_red } }
+/// ```
+///
+/// Only the call it wraps gets the aliases, not comat macros in its arguments, nor the comat strings after it.
+/// Names that every later string in the crate knows would need the macros to share state between calls, which rustc does not promise to expand in order (and rust-analyzer expands on their own),
+/// so tokens for the whole crate go in the [`comat.toml`](https://docs.rs/comat/latest/comat/#comattoml) instead.
+pub fn comat_alias(input: TokenStream) -> TokenStream {
+    let alias::Define { vis, name, aliases } = parse_macro_input!(input as alias::Define);
+    let mut theme = match comat_parser::Theme::load() {
+        Ok(theme) => theme,
+        Err(e) => {
+            return syn::Error::new(proc_macro2::Span::call_site(), e)
+                .into_compile_error()
+                .into()
+        }
+    };
+    let mut given = vec![];
+    for alias::Alias { name, spec } in aliases {
+        let value = spec.value();
+        theme.set(name.to_string(), &*value);
+        if theme.get(&name.to_string()).is_none() {
+            return syn::Error::new(spec.span(), format!("unknown token in {value:?}"))
+                .into_compile_error()
+                .into();
+        }
+        given.push(quote! { #name = #spec });
+    }
+    let export = (!matches!(vis, syn::Visibility::Inherited)).then(|| {
+        quote! {
+            #[allow(unused_imports)]
+            #vis use #name;
+        }
+    });
+    quote! {
+        macro_rules! #name {
+            ($name:ident ! $args:tt) => {
+                ::comat::comat_aliased!({ #(#given),* } $name $args)
+            };
+        }
+        #export
+    }
+    .into()
+}
+
+#[proc_macro]
+#[doc(hidden)]
+/// Expand the comat macro `name` with the aliases [`comat_alias!`] defined, from `{ alias, .. } name (args)`.
+pub fn comat_aliased(input: TokenStream) -> TokenStream {
+    let alias::Aliased {
+        aliases,
+        name,
+        args,
+    } = parse_macro_input!(input as alias::Aliased);
+    let aliases = aliases
+        .into_iter()
+        .map(|alias::Alias { name, spec }| (name.to_string(), spec.value()))
+        .collect();
+    let out = comat_parser::alias::with(aliases, || aliased(&name, args.into()));
+    // the call site is the `macro_rules!`, so names it makes, like captured arguments, would be looked for in there
+    resolved_at(out.into(), name.span()).into()
+}
+
+/// `tokens`, with their names resolved at `span`, as if they were written there.
+fn resolved_at(
+    tokens: proc_macro2::TokenStream,
+    span: proc_macro2::Span,
+) -> proc_macro2::TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let proc_macro2::TokenTree::Group(group) = &token {
+                let mut new =
+                    proc_macro2::Group::new(group.delimiter(), resolved_at(group.stream(), span));
+                new.set_span(group.span());
+                token = new.into();
+            }
+            token.set_span(token.span().resolved_at(span));
+            token
+        })
+        .collect()
+}
+
+/// Call the macro `$f` named `name` with `input`, for each of the macros [`comat_alias!`] can wrap (behind their features).
+macro_rules! dispatch {
+    ($name:ident, $input:ident: $($(#[$cfg:meta])* $f:ident),* $(,)?) => {
+        match &*$name.to_string() {
+            $($(#[$cfg])* stringify!($f) => $f($input),)*
+            other => syn::Error::new(
+                $name.span(),
+                format!("{other}! is not a comat macro comat_alias! can give its tokens"),
+            )
+            .into_compile_error()
+            .into(),
+        }
+    };
+}
+
+/// Expand the comat macro `name` with `input`, for [`comat_aliased!`].
+fn aliased(name: &syn::Ident, input: TokenStream) -> TokenStream {
+    dispatch! {
+        name, input:
+        comat, cwidth, cspinner, comat_ps1, comat_zsh, cstyle, paint, ctemplate,
+        comat_bytes, comat_debug, cinclude_str, cconcat, cformat_args, cformat_args_nl, cpanic,
+        cwrite, cwriteln, cwrite_ok, cwriteln_ok, cwrite_fmt, cwriteln_fmt, cformat_into,
+        #[cfg(feature = "std")] cwrap,
+        #[cfg(feature = "std")] cprefixed,
+        #[cfg(feature = "std")] cbox,
+        #[cfg(feature = "std")] ccolumns,
+        #[cfg(feature = "std")] cprintln,
+        #[cfg(feature = "std")] cprint,
+        #[cfg(feature = "std")] cprint_flush,
+        #[cfg(feature = "std")] cprint_batch,
+        #[cfg(feature = "std")] ceprint_batch,
+        #[cfg(feature = "std")] cstatus_set,
+        #[cfg(feature = "std")] cask,
+        #[cfg(feature = "std")] cconfirm,
+        #[cfg(feature = "std")] cpassword,
+        #[cfg(feature = "std")] cprintln_if,
+        #[cfg(feature = "std")] cprint_if,
+        #[cfg(feature = "std")] cprintln_once,
+        #[cfg(feature = "std")] cprintln_throttled,
+        #[cfg(feature = "std")] ctprintln,
+        #[cfg(feature = "std")] cok,
+        #[cfg(feature = "std")] cwarn_msg,
+        #[cfg(feature = "std")] cerr_msg,
+        #[cfg(feature = "std")] cformat,
+        #[cfg(any(feature = "tokio", feature = "async-std"))] cawrite,
+        #[cfg(any(feature = "tokio", feature = "async-std"))] cawriteln,
+        #[cfg(any(feature = "anyhow", feature = "eyre"))] ccontext,
+        #[cfg(any(feature = "anyhow", feature = "eyre"))] cbail,
+        #[cfg(any(feature = "anyhow", feature = "eyre"))] censure,
+    }
+}
+
+#[cfg(feature = "std")]
+/// Parse some colors in parentheses, like `(bold_blue)`, into their escapes. `()` is no colors.
+fn style(input: syn::parse::ParseStream) -> Result<CFStr> {
+    let content;
+    let paren = syn::parenthesized!(content in input);
+    let spec = content
+        .parse::<proc_macro2::TokenStream>()?
+        .to_string()
+        .replace(' ', "");
+    // nothing for no colors
+    let spec = if spec.is_empty() {
+        String::new()
+    } else {
+        format!("{{{spec}}}")
+    };
+    let spec = syn::LitStr::new(&spec, paren.span.join());
+    syn::parse2::<CFStr>(spec.into_token_stream())
+}
+
+struct One {
+    cfstr: CFStr,
+    args: Punctuated<Expr, Token![,]>,
+}
+
+impl Parse for One {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let cfstr = input.parse::<CFStr>()?;
+        let _ = input.parse::<Token![,]>();
+        Ok(Self {
+            cfstr,
+            args: Punctuated::<Expr, Token![,]>::parse_terminated(input)?,
+        })
+    }
+}
+
+impl ToTokens for One {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(self.cfstr.with_args(&self.args));
+    }
+}
+
+// NOTE: many of these can be made as decl macros, but decl macros can't be exported from proc macro crates yet.
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Print text, colorfully, to stdout, with a newline.
+///
+/// See also [`println`].
+/// ```
+/// # use comat::*;
+/// let magic = 4;
+/// cprintln!("{red}look its red{reset}! {bold_blue}{magic}{reset} is the magic number!");
+/// ```
+pub fn cprintln(input: TokenStream) -> TokenStream {
+    let One { cfstr, args } = parse_macro_input!(input as One);
+    let track = cfstr.track();
+    let print = variants(&cfstr, "Stdout", |cfstr| {
+        let f = render(cfstr, "Stdout", &cfstr.with_args(&args));
+        quote! { println!(#f) }
+    });
+    quote! {{ #track #print }}.into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Print text, colorfully, to stdout, without a newline.
+///
+/// See also [`print`].
+/// ```
+/// # use comat::*;
+/// cprint!("{yellow}i am a warning. {reset}why do you dislike me?");
+/// ```
+pub fn cprint(input: TokenStream) -> TokenStream {
+    let One { cfstr, args } = parse_macro_input!(input as One);
+    let track = cfstr.track();
+    let print = variants(&cfstr, "Stdout", |cfstr| {
+        let f = render(cfstr, "Stdout", &cfstr.with_args(&args));
+        quote! { print!(#f) }
+    });
+    quote! {{ #track #print }}.into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// [`cprint!`], then flush stdout, so text without a newline (like progress) shows up now, rather than when the next line does.
+/// ```
+/// # use comat::*;
+/// for step in 1..=3 {
+///     cprint_flush!("\r{dim}step{reset} {step:bold}/3");
+/// }
+/// ```
+pub fn cprint_flush(input: TokenStream) -> TokenStream {
+    let One { cfstr, args } = parse_macro_input!(input as One);
+    let track = cfstr.track();
+    let print = variants(&cfstr, "Stdout", |cfstr| {
+        let f = render(cfstr, "Stdout", &cfstr.with_args(&args));
+        quote! { print!(#f) }
+    });
+    quote! {{
+        #track
+        #print;
+        let _ = ::std::io::Write::flush(&mut ::std::io::stdout());
+    }}
+    .into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Print some lines, colorfully, to stdout, holding its lock for all of them, so other threads cannot print in between.
+///
+/// The lines are separated by `;`s, and each is a format string with its arguments, like [`cprintln!`] takes.
+/// ```
+/// # use comat::*;
+/// let (file, line) = ("src/main.rs", 12);
+/// cprint_batch! {
+///     "{bold}compiling{reset} {file}";
+///     "{dim}at line{reset} {}", line;
+/// }
+/// ```
+pub fn cprint_batch(input: TokenStream) -> TokenStream {
+    batch(input, "stdout")
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Print some lines, colorfully, to stderr, holding its lock for all of them, like [`cprint_batch!`] does for stdout.
+///
+/// For errors that take more than a line, so another thread's output cannot end up in the middle of one.
+/// ```
+/// # use comat::*;
+/// let name = "colour";
+/// ceprint_batch! {
+///     "{error}error{reset}: unknown option {name:bold}";
+///     "  {dim}= {reset}{hint}help{reset}: did you mean {:bold}?", "color";
+/// }
+/// ```
+pub fn ceprint_batch(input: TokenStream) -> TokenStream {
+    batch(input, "stderr")
+}
+
+#[cfg(feature = "std")]
+/// Write `;` separated format strings as lines to `stream` (`stdout` or `stderr`), holding its lock.
+fn batch(input: TokenStream, stream: &str) -> TokenStream {
+    let mut lines = vec![];
+    let mut line = proc_macro2::TokenStream::new();
+    for token in proc_macro2::TokenStream::from(input) {
+        match token {
+            proc_macro2::TokenTree::Punct(p) if p.as_char() == ';' => {
+                lines.push(std::mem::take(&mut line));
+            }
+            token => line.extend([token]),
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    let lines = match lines
+        .into_iter()
+        .map(syn::parse2::<One>)
+        .collect::<Result<Vec<_>>>()
+    {
+        Ok(lines) => lines,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let track = lines.iter().map(|line| line.cfstr.track());
+    let name = format!("{}{}", stream[..1].to_uppercase(), &stream[1..]);
+    let lines = lines.iter().map(|line| {
+        variants(&line.cfstr, &name, |cfstr| {
+            let f = render(cfstr, &name, &cfstr.with_args(&line.args));
+            quote! { writeln!(out, #f) }
+        })
+    });
+    let failed = format!("failed printing to {stream}");
+    let stream = syn::Ident::new(stream, proc_macro2::Span::call_site());
+    quote! {{
+        #(#track)*
+        use ::std::io::Write as _;
+        let mut out = ::std::io::#stream().lock();
+        #((#lines).expect(#failed);)*
+    }}
+    .into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Format text, colorfully, and show it on a [status line](https://docs.rs/comat/latest/comat/fn.set_status.html) at the bottom of the terminal,
+/// which stays there while the output of [`cprintln!`] (and the rest) scrolls above it, until [`cstatus_clear!`].
+/// ```
+/// # use comat::*;
+/// let total = 3;
+/// for done in 1..=total {
+///     cstatus_set!("{bold}building{reset} {done}/{total}");
+///     cprintln!("{green}compiled{reset} crate {done}");
+/// }
+/// cstatus_clear!();
+/// ```
+pub fn cstatus_set(input: TokenStream) -> TokenStream {
+    let One { cfstr, args } = parse_macro_input!(input as One);
+    let track = cfstr.track();
+    let set = variants(&cfstr, "Stdout", |cfstr| {
+        let f = cfstr.with_args(&args);
+        quote! { ::comat::set_status(&format!(#f)) }
+    });
+    quote! {{ #track #set }}.into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Take away the status line [`cstatus_set!`] put up. Call it before exiting, as the terminal keeps the space it made.
+/// ```
+/// # use comat::*;
+/// cstatus_set!("{dim}waiting{reset}");
+/// cstatus_clear!();
+/// ```
+pub fn cstatus_clear(input: TokenStream) -> TokenStream {
+    no_arguments(input, "cstatus_clear!")
+        .unwrap_or_else(|| quote! { ::comat::clear_status() }.into())
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Clear the terminal, and put the cursor at the top left, unless the output is plain.
+/// ```
+/// # use comat::*;
+/// cclear!();
+/// cprintln!("{bold}dashboard{reset}");
+/// ```
+pub fn cclear(input: TokenStream) -> TokenStream {
+    no_arguments(input, "cclear!").unwrap_or_else(|| quote! { ::comat::clear_screen() }.into())
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Clear the line the cursor is on, and put it at the start, unless the output is plain.
+/// ```
+/// # use comat::*;
+/// cprint!("{dim}working{reset}");
+/// cclear_line!();
+/// cprintln!("{green}done{reset}");
+/// ```
+pub fn cclear_line(input: TokenStream) -> TokenStream {
+    no_arguments(input, "cclear_line!").unwrap_or_else(|| quote! { ::comat::clear_line() }.into())
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Put the cursor at a row and column (any expressions), counting from 1 at the top left, unless the output is plain.
+/// ```
+/// # use comat::*;
+/// let (cpu, memory) = (12, 48);
+/// cclear!();
+/// cmoveto!(1, 1);
+/// cprint!("{bold}cpu{reset} {cpu}%");
+/// cmoveto!(2, 1);
+/// cprint!("{bold}memory{reset} {memory}%");
+/// ```
+pub fn cmoveto(input: TokenStream) -> TokenStream {
+    let parse = Punctuated::<Expr, Token![,]>::parse_terminated;
+    let args = parse_macro_input!(input with parse);
+    let [row, column] = &args.iter().collect::<Vec<_>>()[..] else {
+        return syn::Error::new_spanned(args, "cmoveto! takes a row and a column")
+            .into_compile_error()
+            .into();
+    };
+    quote! { ::comat::move_to(#row, #column) }.into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Put the terminal back the way it started, with a soft reset and a reset of the colors, unless the output is plain.
+///
+/// This recovers a terminal left with some colors, a hidden cursor, or a [`cstatus_set!`] line, like after a panic.
+/// ```
+/// # use comat::*;
+/// std::panic::set_hook(Box::new(|info| {
+///     cterm_reset!();
+///     eprintln!("{info}");
+/// }));
+/// # let _ = std::panic::take_hook();
+/// ```
+pub fn cterm_reset(input: TokenStream) -> TokenStream {
+    no_arguments(input, "cterm_reset!")
+        .unwrap_or_else(|| quote! { ::comat::reset_terminal() }.into())
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Show a prompt, colorfully, and read a line from stdin, trimmed, as a [`std::io::Result<String>`].
+/// ```no_run
+/// # use comat::*;
+/// let name = cask!("{bold}project name{reset}: ")?;
+/// let license = cask!("{bold}license{reset} {dim}(for {name}){reset}: ")?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn cask(input: TokenStream) -> TokenStream {
+    let One { cfstr, args } = parse_macro_input!(input as One);
+    let track = cfstr.track();
+    let ask = variants(&cfstr, "Stdout", |cfstr| {
+        let f = cfstr.with_args(&args);
+        quote! { ::comat::ask(&format!(#f)) }
+    });
+    quote! {{ #track #ask }}.into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Show a prompt, colorfully, and read a yes or no from stdin, as a `bool`, asking again for anything else.
+///
+/// An empty answer is the default, when the prompt has `[Y/n]` (yes) or `[y/N]` (no). See [`confirm`](https://docs.rs/comat/latest/comat/fn.confirm.html).
+/// ```no_run
+/// # use comat::*;
+/// let path = "config.toml";
+/// if cconfirm!("{yellow}overwrite {path}?{reset} [y/N] ") {
+///     cprintln!("{green}overwrote{reset} {path}");
+/// }
+/// ```
+pub fn cconfirm(input: TokenStream) -> TokenStream {
+    let One { cfstr, args } = parse_macro_input!(input as One);
+    let track = cfstr.track();
+    let confirm = variants(&cfstr, "Stdout", |cfstr| {
+        let f = cfstr.with_args(&args);
+        quote! { ::comat::confirm(&format!(#f)) }
+    });
+    quote! {{ #track #confirm }}.into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Show a prompt, colorfully, and read a line from stdin without showing what is typed, as a [`std::io::Result<String>`].
+///
+/// See [`password`](https://docs.rs/comat/latest/comat/fn.password.html) for when it cannot hide the input.
+/// ```no_run
+/// # use comat::*;
+/// let host = "crates.io";
+/// let token = cpassword!("{dim}token for {host}:{reset} ")?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn cpassword(input: TokenStream) -> TokenStream {
+    let One { cfstr, args } = parse_macro_input!(input as One);
+    let track = cfstr.track();
+    let password = variants(&cfstr, "Stdout", |cfstr| {
+        let f = cfstr.with_args(&args);
+        quote! { ::comat::password(&format!(#f)) }
+    });
+    quote! {{ #track #password }}.into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Print text, colorfully, to stdout, with a newline, only if a condition holds.
+///
+/// The arguments are not evaluated (or formatted) when it doesnt.
+/// ```
+/// # use comat::*;
+/// let verbose = std::env::args().any(|arg| arg == "-v");
+/// let path = "target/debug";
+/// cprintln_if!(verbose, "{dim}cleaning{reset} {path:bold}");
+/// ```
+pub fn cprintln_if(input: TokenStream) -> TokenStream {
+    let Two { a, cfstr, args } = parse_macro_input!(input as Two);
+    let track = cfstr.track();
+    let print = variants(&cfstr, "Stdout", |cfstr| {
+        let f = render(cfstr, "Stdout", &cfstr.with_args(&args));
+        quote! { println!(#f) }
+    });
+    quote! {{ #track if #a { #print } }}.into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Print text, colorfully, to stdout, without a newline, only if a condition holds.
+///
+/// See [`cprintln_if!`].
+/// ```
+/// # use comat::*;
+/// let color = true;
+/// cprint_if!(color, "{green}*{reset} ");
+/// ```
+pub fn cprint_if(input: TokenStream) -> TokenStream {
+    let Two { a, cfstr, args } = parse_macro_input!(input as Two);
+    let track = cfstr.track();
+    let print = variants(&cfstr, "Stdout", |cfstr| {
+        let f = render(cfstr, "Stdout", &cfstr.with_args(&args));
+        quote! { print!(#f) }
+    });
+    quote! {{ #track if #a { #print } }}.into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Print text, colorfully, to stdout, with a newline, only the first time this is reached.
+///
+/// Each call site has its own [`Once`](std::sync::Once), so the same warning in two places prints twice.
+/// ```
+/// # use comat::*;
+/// for retry in 0..3 {
+///     cprintln_once!("{warn}warning{reset}: the server is slow, retrying ({retry})");
+/// }
+/// ```
+pub fn cprintln_once(input: TokenStream) -> TokenStream {
+    let One { cfstr, args } = parse_macro_input!(input as One);
+    let track = cfstr.track();
+    let print = variants(&cfstr, "Stdout", |cfstr| {
+        let f = render(cfstr, "Stdout", &cfstr.with_args(&args));
+        quote! { println!(#f) }
+    });
+    quote! {{
+        #track
+        static ONCE: ::std::sync::Once = ::std::sync::Once::new();
+        ONCE.call_once(|| #print);
+    }}
+    .into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Print text, colorfully, to stdout, with a newline, unless this printed within a [`Duration`](std::time::Duration).
+///
+/// Each call site has its own [`Throttle`](https://docs.rs/comat/latest/comat/struct.Throttle.html). The arguments are not evaluated when it doesnt print.
+/// ```
+/// # use comat::*;
+/// use std::time::Duration;
+/// for attempt in 0..100 {
+///     cprintln_throttled!(Duration::from_secs(5), "{dim}retrying ({attempt}){reset}");
+/// }
+/// ```
+pub fn cprintln_throttled(input: TokenStream) -> TokenStream {
+    let Two { a, cfstr, args } = parse_macro_input!(input as Two);
+    let track = cfstr.track();
+    let print = variants(&cfstr, "Stdout", |cfstr| {
+        let f = render(cfstr, "Stdout", &cfstr.with_args(&args));
+        quote! { println!(#f) }
+    });
+    quote! {{
+        #track
+        static THROTTLE: ::comat::Throttle = ::comat::Throttle::new();
+        if THROTTLE.ready(#a) {
+            #print
+        }
+    }}
+    .into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Print text, colorfully, to stdout, with a newline, after a [timestamp](https://docs.rs/comat/latest/comat/enum.Timestamp.html).
+///
+/// The stamp is the time in UTC, or with `elapsed,` before the string, the time since the first elapsed stamp.
+/// It is styled by the `timestamp` token, so a theme can change it.
+/// ```
+/// # use comat::*;
+/// let file = "Cargo.toml";
+/// ctprintln!("reading {file:bold}"); // 2024-05-01T13:37:00.123Z reading Cargo.toml
+/// ctprintln!(elapsed, "{success}done{reset}"); //    0.000s done
+/// ```
+pub fn ctprintln(input: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let elapsed = input.peek(syn::Ident)
+            && input.peek2(Token![,])
+            && input.fork().parse::<syn::Ident>()? == "elapsed";
+        if elapsed {
+            input.parse::<syn::Ident>()?;
+            input.parse::<Token![,]>()?;
+        }
+        let stamp = syn::parse2::<CFStr>(quote! { "{timestamp}{}{reset} {}" })?;
+        Ok((elapsed, stamp, input.parse::<One>()?))
+    };
+    let (elapsed, stamp, f) = match syn::parse::Parser::parse(parser, input) {
+        Ok(x) => x,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let track = stamp.track();
+    let ftrack = f.cfstr.track();
+    let kind = if elapsed {
+        quote! { Elapsed }
+    } else {
+        quote! { Utc }
+    };
+    let print = variants(&f.cfstr, "Stdout", |cfstr| {
+        let f = cfstr.with_args(&f.args);
+        let args = render(
+            cfstr,
+            "Stdout",
+            &quote! { #stamp, ::comat::Timestamp::#kind, format_args!(#f) },
+        );
+        quote! { println!(#args) }
+    });
+    quote! {{
+        #track
+        #ftrack
+        #print
+    }}
+    .into()
+}
+
+#[cfg(feature = "std")]
+/// An error for `name`, when it was given any arguments.
+fn no_arguments(input: TokenStream, name: &str) -> Option<TokenStream> {
+    let arg = proc_macro2::TokenStream::from(input).into_iter().next()?;
+    Some(
+        syn::Error::new(arg.span(), format!("{name} takes no arguments"))
+            .into_compile_error()
+            .into(),
+    )
+}
+
+#[cfg(feature = "std")]
+/// Format arguments for the printing macros to `stream` (`Stdout` or `Stderr`), going through [`Render`](https://docs.rs/comat/latest/comat/struct.Render.html),
+/// unless `cfstr` was [forced](cfstr::Force) to show some way.
+fn render(cfstr: &CFStr, stream: &str, args: &impl ToTokens) -> proc_macro2::TokenStream {
+    let stream = syn::Ident::new(stream, proc_macro2::Span::call_site());
+    match cfstr.force() {
+        None => quote! { "{}", ::comat::Render(format_args!(#args), ::comat::Stream::#stream) },
+        // the formatters and styles of the arguments still color them
+        Some(cfstr::Force::Plain) => quote! { "{}", ::comat::strip(&format!(#args)) },
+        Some(cfstr::Force::Depth(_)) => args.to_token_stream(),
+    }
+}
+
+#[cfg(feature = "std")]
+/// `print` of `cfstr`, [merged](CFStr::merged) with its [variants](CFStr::variants), so the one shown is chosen at runtime
+/// with [`set_markers`](https://docs.rs/comat/latest/comat/fn.set_markers.html), [`set_variant`](https://docs.rs/comat/latest/comat/fn.set_variant.html),
+/// or by the [`capabilities`](https://docs.rs/comat/latest/comat/fn.stream_capabilities.html) of the terminal `stream` (`Stdout` or `Stderr`) goes to.
+fn variants(
+    cfstr: &CFStr,
+    stream: &str,
+    print: impl Fn(&CFStr) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if cfstr.variants().is_empty() {
+        return print(cfstr);
+    }
+    let (merged, variants) = cfstr.merged();
+    let stream = syn::Ident::new(stream, proc_macro2::Span::call_site());
+    // the index of each variant, after the string itself
+    let index = |wanted: &dyn Fn(cfstr::Variant) -> bool| {
+        variants
+            .iter()
+            .position(|&variant| wanted(variant))
+            .map(|i| i + 1)
+    };
+    let truecolor = quote! {
+        ::comat::stream_capabilities(::comat::Stream::#stream).colors == ::comat::Colors::Truecolor
+    };
+    let mut select = quote! { 0 };
+    if let Some(ansi256) = index(&|variant| variant == cfstr::Variant::Ansi256) {
+        select = quote! { if #truecolor { #select } else { #ansi256 } };
+    }
+    let themes = variants
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &variant)| match variant {
+            cfstr::Variant::Theme(n) => {
+                let i = i + 1;
+                let ansi256 = index(&|variant| variant == cfstr::Variant::ThemeAnsi256(n));
+                Some(if let Some(ansi256) = ansi256 {
+                    quote! { #n => if #truecolor { #i } else { #ansi256 }, }
+                } else {
+                    quote! { #n => #i, }
+                })
+            }
+            cfstr::Variant::Markers | cfstr::Variant::Ansi256 | cfstr::Variant::ThemeAnsi256(_) => {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    if !themes.is_empty() {
+        select = quote! { match ::comat::variant() { #(#themes)* _ => #select } };
+    }
+    if let Some(markers) = index(&|variant| variant == cfstr::Variant::Markers) {
+        select = quote! { if ::comat::markers() { #markers } else { #select } };
+    }
+    let print = print(&merged);
+    quote! {{
+        let __comat_variant: usize = #select;
+        #print
+    }}
+}
+
+#[cfg(feature = "std")]
+/// Print a `tag` (comat syntax), then the text, with `print` (`println` or `eprintln`).
+fn tagged(tag: &str, print: &str, input: TokenStream) -> TokenStream {
+    let f = parse_macro_input!(input as One);
+    let tag = syn::LitStr::new(&format!("{tag} {{}}"), proc_macro2::Span::call_site());
+    let tag = match syn::parse2::<CFStr>(tag.into_token_stream()) {
+        Ok(tag) => tag,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let (track, ftrack) = (tag.track(), f.cfstr.track());
+    let stream = if print == "eprintln" {
+        "Stderr"
+    } else {
+        "Stdout"
+    };
+    let print = syn::Ident::new(print, proc_macro2::Span::call_site());
+    let print = variants(&f.cfstr, stream, |cfstr| {
+        let f = cfstr.with_args(&f.args);
+        let args = render(cfstr, stream, &quote! { #tag, format_args!(#f) });
+        quote! { #print!(#args) }
+    });
+    quote! {{ #track #ftrack #print }}.into()
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Print text, colorfully, to stdout, with a newline, after a `[ OK ]` tag in the `success` style.
+/// ```
+/// # use comat::*;
+/// let crate_name = "comat";
+/// cok!("built {crate_name:bold}"); // [ OK ] built comat
+/// ```
+pub fn cok(input: TokenStream) -> TokenStream {
+    tagged("{success}[ OK ]{reset}", "println", input)
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Print text, colorfully, to stderr, with a newline, after a `[WARN]` tag in the `warn` style.
+/// ```
+/// # use comat::*;
+/// let field = "edition";
+/// cwarn_msg!("{field:bold} is not set"); // [WARN] edition is not set
+/// ```
+pub fn cwarn_msg(input: TokenStream) -> TokenStream {
+    tagged("{warn}[WARN]{reset}", "eprintln", input)
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Print text, colorfully, to stderr, with a newline, after a `[FAIL]` tag in the `error` style.
+/// ```
+/// # use comat::*;
+/// let test = "parse";
+/// cerr_msg!("{test:bold} panicked"); // [FAIL] parse panicked
+/// ```
+pub fn cerr_msg(input: TokenStream) -> TokenStream {
+    tagged("{error}[FAIL]{reset}", "eprintln", input)
+}
+
+#[cfg(feature = "std")]
+#[proc_macro]
+/// Format text, colorfully.
+///
+/// See also [`format`].
+/// ```
+/// # use comat::*;
+/// let favorite_thing = "teddy bears";
+/// let message = cformat!("the {red}bogeymen{reset} will get your {favorite_thing:underline}");
+/// # assert_eq!(message, "the \x1b[0;34;31mbogeymen\x1b[0m will get your \x1b[24mteddy bears\x1b[0m");
+/// ```
+pub fn cformat(input: TokenStream) -> TokenStream {
+    let f = parse_macro_input!(input as One);
+    let track = f.cfstr.track();
+    quote! {{ #track format!(#f) }}.into()
+}
+
+#[proc_macro]
+/// Produce [`fmt::Arguments`](std::fmt::Arguments). Sometimes functions take these.
+///
+/// See also [`format_args`].
+/// ```
+/// # use comat::*;
+/// let args = cformat_args!("{bold_red}fatal error. {reset}killing {blue}everything{reset}");
+/// // NOTE: do not do this. instead use cprintln.
+/// println!("{}", args);
+pub fn cformat_args(input: TokenStream) -> TokenStream {
+    let f = parse_macro_input!(input as One);
+    quote! { format_args!(#f) }.into()
+}
+
+#[proc_macro]
+/// [`cformat_args!`], with a newline, like the `format_args_nl!` behind [`println`].
+///
+/// For functions that take [`fmt::Arguments`](std::fmt::Arguments) and print them as they are.
+/// ```
+/// # use comat::*;
+/// fn log(args: std::fmt::Arguments) -> String {
+///     args.to_string()
+/// }
+/// let done = 3;
+/// assert_eq!(log(cformat_args_nl!("{done:green} done")), cformat!("{done:green} done\n"));
+/// ```
+pub fn cformat_args_nl(input: TokenStream) -> TokenStream {
+    let mut f = parse_macro_input!(input as One);
+    f.cfstr.newline();
+    quote! { format_args!(#f) }.into()
+}
+/// Colorfully panic.
+///
+/// See also [`panic`].
+/// ```should_panic
+/// # use comat::cpanic;
+/// cpanic!("why is the bound {red}bad");
+/// ```
+#[proc_macro]
+pub fn cpanic(input: TokenStream) -> TokenStream {
+    let f = parse_macro_input!(input as One);
+    let track = f.cfstr.track();
+    quote! {{ #track panic!(#f) }}.into()
+}
+
+struct Two {
+    a: Expr,
+    cfstr: CFStr,
+    args: Punctuated<Expr, Token![,]>,
+}
+
+impl Parse for Two {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let a = input.parse::<Expr>()?;
+        input.parse::<Token![,]>()?;
+        let cfstr = input.parse::<CFStr>()?;
+        let _ = input.parse::<Token![,]>();
+        Ok(Self {
+            a,
+            cfstr,
+            args: Punctuated::<Expr, Token![,]>::parse_terminated(input)?,
+        })
+    }
+}
+
+impl ToTokens for Two {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        self.a.to_tokens(tokens);
+        tokens.append(proc_macro2::Punct::new(',', proc_macro2::Spacing::Alone));
+        tokens.extend(self.cfstr.with_args(&self.args));
+    }
+}
+
+#[proc_macro]
+/// Write to a buffer colorfully, with no newline.
+///
+/// Like [`write`], this works with anything that has a `write_fmt` method: [`io::Write`](std::io::Write)rs, [`fmt::Write`](std::fmt::Write)rs, and [`Formatter`](std::fmt::Formatter)s.
+/// See [`cwrite_fmt!`] for a version that doesn't need a trait import for `fmt::Write`rs.
+/// ```
+/// # use comat::cwrite;
+/// use std::io::Write;
+/// let mut buf = vec![];
+/// cwrite!(buf, "{green}omg there's going to be ansi sequences in a {black}Vec<u8>{reset}!");
+/// # assert_eq!(buf, [27, 91, 48, 59, 51, 52, 59, 51, 50, 109, 111, 109, 103, 32, 116, 104, 101, 114, 101, 39, 115, 32, 103, 111, 105, 110, 103, 32, 116, 111, 32, 98, 101, 32, 97, 110, 115, 105, 32, 115, 101, 113, 117, 101, 110, 99, 101, 115, 32, 105, 110, 32, 97, 32, 27, 91, 48, 59, 51, 52, 59, 51, 48, 109, 86, 101, 99, 60, 117, 56, 62, 27, 91, 48, 109, 33]);
+/// ```
+/// In a [`Display`](std::fmt::Display) impl:
+/// ```
+/// # use comat::cwrite;
+/// struct User { name: &'static str }
+/// impl std::fmt::Display for User {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         let name = self.name;
+///         cwrite!(f, "{name:bold} ({green}online{reset})")
+///     }
+/// }
+/// assert_eq!(User { name: "bendn" }.to_string(), "\x1b[0m\x1b[1mbendn\x1b[0m (\x1b[0;34;32monline\x1b[0m)");
+/// ```
+pub fn cwrite(input: TokenStream) -> TokenStream {
+    let f = parse_macro_input!(input as Two);
+    let track = f.cfstr.track();
+    quote! {{ #track write!(#f) }}.into()
+}
+
+#[proc_macro]
+/// Write to a buffer colorfully, with newline.
+///
+/// See also [`writeln`], [`cwrite!`]
+/// ```
+/// # use comat::cwriteln;
+/// use std::io::Write;
+/// let mut buf = vec![];
+/// cwriteln!(buf, "hey look: {strike}strike'd text{reset}!");
+/// # assert_eq!(buf, [104, 101, 121, 32, 108, 111, 111, 107, 58, 32, 27, 91, 57, 109, 115, 116, 114, 105, 107, 101, 39, 100, 32, 116, 101, 120, 116, 27, 91, 48, 109, 33, 10]);
+/// ```
+pub fn cwriteln(input: TokenStream) -> TokenStream {
+    let f = parse_macro_input!(input as Two);
+    let track = f.cfstr.track();
+    quote! {{ #track writeln!(#f) }}.into()
+}
+
+#[proc_macro]
+/// [`cwrite!`], panicking if the write fails, like [`print`] does, for when there is nothing to do about it.
+/// ```
+/// # use comat::cwrite_ok;
+/// use std::io::Write;
+/// let mut buf = vec![];
+/// cwrite_ok!(buf, "{bold}no result{reset}");
+/// assert_eq!(buf, b"\x1b[1mno result\x1b[0m");
+/// ```
+pub fn cwrite_ok(input: TokenStream) -> TokenStream {
+    let f = parse_macro_input!(input as Two);
+    let track = f.cfstr.track();
+    quote! {{ #track write!(#f).expect("failed writing") }}.into()
+}
+
+#[proc_macro]
+/// [`cwriteln!`], panicking if the write fails.
+///
+/// See [`cwrite_ok!`].
+/// ```
+/// # use comat::cwriteln_ok;
+/// use std::fmt::Write;
+/// let mut s = String::new();
+/// cwriteln_ok!(s, "{green}ok{reset}");
+/// assert_eq!(s, "\x1b[0;34;32mok\x1b[0m\n");
+/// ```
+pub fn cwriteln_ok(input: TokenStream) -> TokenStream {
+    let f = parse_macro_input!(input as Two);
+    let track = f.cfstr.track();
+    quote! {{ #track writeln!(#f).expect("failed writing") }}.into()
+}
+
+/// `dst.write_fmt(format_args!(..))` with `fmt::Write` in scope, optionally with a newline.
+fn write_fmt(Two { a, mut cfstr, args }: Two, newline: bool) -> proc_macro2::TokenStream {
+    let track = cfstr.track();
+    if newline {
+        cfstr.newline();
+    }
+    if cfstr.is_literal() && args.is_empty() {
+        return quote! {{ #track use ::core::fmt::Write as _; #a.write_str(#cfstr) }};
+    }
+    let args = cfstr.with_args(&args);
+    quote! {{ #track use ::core::fmt::Write as _; #a.write_fmt(format_args!(#args)) }}
+}
+
+#[proc_macro]
+/// Write to a [`fmt::Write`](std::fmt::Write)r (such as a [`String`] or [`Formatter`](std::fmt::Formatter)) colorfully, with no newline.
+///
+/// Unlike [`cwrite!`], this does not need [`fmt::Write`](std::fmt::Write) to be imported.
+/// ```
+/// # use comat::cwrite_fmt;
+/// struct Status(bool);
+/// impl std::fmt::Display for Status {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         match self.0 {
+///             true => cwrite_fmt!(f, "{green}ok{reset}"),
+///             false => cwrite_fmt!(f, "{red}failed{reset}"),
+///         }
+///     }
+/// }
+/// let mut s = String::new();
+/// cwrite_fmt!(s, "status: {}", Status(true)).unwrap();
+/// assert_eq!(s, "status: \x1b[0;34;32mok\x1b[0m");
+/// ```
+pub fn cwrite_fmt(input: TokenStream) -> TokenStream {
+    write_fmt(parse_macro_input!(input as Two), false).into()
+}
+
+#[proc_macro]
+/// Write to a [`fmt::Write`](std::fmt::Write)r colorfully, with newline.
+///
+/// See [`cwrite_fmt!`].
+/// ```
+/// # use comat::cwriteln_fmt;
+/// let mut s = String::new();
+/// cwriteln_fmt!(s, "{bold}done{reset}").unwrap();
+/// assert_eq!(s, "\x1b[1mdone\x1b[0m\n");
+/// ```
+pub fn cwriteln_fmt(input: TokenStream) -> TokenStream {
+    write_fmt(parse_macro_input!(input as Two), true).into()
+}
+
+#[proc_macro]
+/// Append colorful text to an existing [`String`], instead of allocating a new one like [`cformat!`].
+///
+/// Strings without any arguments are simply pushed.
+/// ```
+/// # use comat::cformat_into;
+/// let mut log = String::new();
+/// for level in ["info", "warn"] {
+///     cformat_into!(log, "{dim}[{reset}");
+///     cformat_into!(log, "{level:bold}{dim}]{reset} ");
+/// }
+/// # assert_eq!(log, "\x1b[2m[\x1b[0m\x1b[0m\x1b[1minfo\x1b[0m\x1b[2m]\x1b[0m \x1b[2m[\x1b[0m\x1b[0m\x1b[1mwarn\x1b[0m\x1b[2m]\x1b[0m ");
+/// ```
+pub fn cformat_into(input: TokenStream) -> TokenStream {
+    let write = write_fmt(parse_macro_input!(input as Two), false);
+    quote! {{ let _ = #write; }}.into()
+}
+
+/// `path;`, naming the crate (or trait) a macro that can use more than one should use, before the rest of its arguments.
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "anyhow",
+    feature = "eyre"
+))]
+struct Using<T> {
+    path: Option<syn::Path>,
+    rest: T,
+}
+
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "anyhow",
+    feature = "eyre"
+))]
+impl<T: Parse> Parse for Using<T> {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let fork = input.fork();
+        let path = if fork.parse::<syn::Path>().is_ok() && fork.peek(Token![;]) {
+            let path = input.parse()?;
+            input.parse::<Token![;]>()?;
+            Some(path)
+        } else {
+            None
+        };
+        Ok(Self {
+            path,
+            rest: input.parse()?,
+        })
+    }
+}
+
+/// `write_all` of the formatted bytes, through the trait `path` names, or the enabled async runtime's extension trait (tokio's, if both are).
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+fn async_write(
+    Using {
+        path,
+        rest: Two { a, mut cfstr, args },
+    }: Using<Two>,
+    newline: bool,
+) -> proc_macro2::TokenStream {
+    let track = cfstr.track();
+    if newline {
+        cfstr.newline();
+    }
+    let ext = match path {
+        Some(path) => path.into_token_stream(),
+        None if cfg!(feature = "tokio") => quote! { ::tokio::io::AsyncWriteExt },
+        None => quote! { ::async_std::io::WriteExt },
+    };
+    let bytes = if cfstr.is_literal() && args.is_empty() {
+        quote! { ::std::borrow::Cow::Borrowed(#cfstr) }
+    } else {
+        let args = cfstr.with_args(&args);
+        quote! { ::std::borrow::Cow::<str>::Owned(format!(#args)) }
+    };
+    quote! {{
+        #track
+        let bytes = #bytes;
+        let writer = &mut #a;
+        async move { #ext::write_all(writer, bytes.as_bytes()).await }
+    }}
+}
+
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+#[proc_macro]
+/// Write to an async writer colorfully, with no newline. Requires the `tokio` or `async-std` feature.
+///
+/// The text is formatted once, up front, and written with `write_all`.
+/// ```
+/// # use comat::cawrite;
+/// async fn greet(stream: &mut Vec<u8>, user: &str) -> std::io::Result<()> {
+///     cawrite!(*stream, "{green}welcome{reset}, {user:bold}! ").await
+/// }
+/// ```
+///
+/// The trait to write with can be named first, which is tokio's `AsyncWriteExt` or async-std's `WriteExt`, as the features choose, when it is not.
+/// That is tokio's when both are on, so a dependency that turns on the other feature does not change what a call makes.
+/// ```
+/// # use comat::cawrite;
+/// async fn greet(stream: &mut Vec<u8>, user: &str) -> std::io::Result<()> {
+///     cawrite!(async_std::io::WriteExt; *stream, "{green}welcome{reset}, {user:bold}! ").await
+/// }
+/// ```
+pub fn cawrite(input: TokenStream) -> TokenStream {
+    async_write(parse_macro_input!(input as Using<Two>), false).into()
+}
+
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+#[proc_macro]
+/// Write to an async writer colorfully, with newline.
+///
+/// See [`cawrite!`].
+/// ```
+/// # use comat::cawriteln;
+/// async fn prompt(stream: &mut Vec<u8>) -> std::io::Result<()> {
+///     cawriteln!(*stream, "{bold}>{reset} ").await
+/// }
+/// ```
+pub fn cawriteln(input: TokenStream) -> TokenStream {
+    async_write(parse_macro_input!(input as Using<Two>), true).into()
+}
+
+/// The error crate to use: `path`, or the enabled one (anyhow, if both are), and whether it is anyhow rather than eyre.
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+fn error_crate(path: Option<syn::Path>) -> (proc_macro2::TokenStream, bool) {
+    match path {
+        Some(path) => {
+            let anyhow = path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "anyhow");
+            (path.into_token_stream(), anyhow)
+        }
+        None if cfg!(feature = "anyhow") => (quote! { ::anyhow }, true),
+        None => (quote! { ::eyre }, false),
+    }
+}
+
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+#[proc_macro]
+/// Add colorful context to an error. Requires the `anyhow` or `eyre` feature.
+///
+/// With anyhow it can add context to a `None` too, but eyre only wraps a `Result`.
+/// The message is only formatted if there is an error.
+/// ```
+/// # use comat::ccontext;
+/// # #[cfg(feature = "anyhow")] use anyhow::Result;
+/// # #[cfg(not(feature = "anyhow"))] use eyre::Result;
+/// fn config(path: &str) -> Result<String> {
+///     ccontext!(std::fs::read_to_string(path), "couldnt read {path:bold}")
+/// }
+/// # assert!(config("nowhere.toml").is_err());
+/// ```
+///
+/// The error crate can be named first, and a path that ends in `anyhow` uses its `Context`, while any other (like `color_eyre::eyre`) uses eyre's `WrapErr`.
+/// The features only choose the crate when it is not named, which is anyhow when both are on,
+/// so a dependency that turns on the other feature does not change what a call makes.
+/// ```
+/// # use comat::ccontext;
+/// fn config(path: &str) -> eyre::Result<String> {
+///     ccontext!(eyre; std::fs::read_to_string(path), "couldnt read {path:bold}")
+/// }
+/// # assert!(config("nowhere.toml").is_err());
+/// ```
+pub fn ccontext(input: TokenStream) -> TokenStream {
+    let Using {
+        path,
+        rest: Two { a, cfstr, args },
+    } = parse_macro_input!(input as Using<Two>);
+    let track = cfstr.track();
+    let args = cfstr.with_args(&args);
+    let context = match error_crate(path) {
+        (krate, true) => quote! { #krate::Context::with_context },
+        (krate, false) => quote! { #krate::WrapErr::wrap_err_with },
+    };
+    quote! {{ #track #context(#a, || format!(#args)) }}.into()
+}
+
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+#[proc_macro]
+/// Return early with a colorful error. Requires the `anyhow` or `eyre` feature.
+///
+/// The error crate can be named first, like `cbail!(eyre; "..")`, as with [`ccontext!`].
+/// ```
+/// # use comat::cbail;
+/// # #[cfg(feature = "anyhow")] use anyhow::Result;
+/// # #[cfg(not(feature = "anyhow"))] use eyre::Result;
+/// fn listen(port: u16) -> Result<()> {
+///     if port == 0 {
+///         cbail!("{error}invalid port{reset} {port:bold}");
+///     }
+///     Ok(())
+/// }
+/// # assert!(listen(0).is_err());
+/// ```
+pub fn cbail(input: TokenStream) -> TokenStream {
+    let Using { path, rest: f } = parse_macro_input!(input as Using<One>);
+    let track = f.cfstr.track();
+    let (krate, _) = error_crate(path);
+    quote! {{ #track #krate::bail!(#f) }}.into()
+}
+
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+#[proc_macro]
+/// Return early with a colorful error if a condition doesnt hold. Requires the `anyhow` or `eyre` feature.
+///
+/// The error crate can be named first, like `censure!(eyre; ok, "..")`, as with [`ccontext!`].
+/// ```
+/// # use comat::censure;
+/// # #[cfg(feature = "anyhow")] use anyhow::Result;
+/// # #[cfg(not(feature = "anyhow"))] use eyre::Result;
+/// fn take(got: usize, max: usize) -> Result<()> {
+///     censure!(got <= max, "{got:bold} is more than {max:bold}");
+///     Ok(())
+/// }
+/// # assert!(take(12, 8).is_err());
+/// ```
+pub fn censure(input: TokenStream) -> TokenStream {
+    let Using {
+        path,
+        rest: Two { a, cfstr, args },
+    } = parse_macro_input!(input as Using<Two>);
+    let track = cfstr.track();
+    let args = cfstr.with_args(&args);
+    let (krate, _) = error_crate(path);
+    quote! {{ #track #krate::ensure!(#a, #args) }}.into()
+}
@@ -0,0 +1,87 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// The attributes a `comat::Style` has builders for, by SGR parameter.
+const ATTRIBUTES: &[(u8, &str)] = &[
+    (1, "bold"),
+    (2, "dim"),
+    (3, "italic"),
+    (4, "underline"),
+    (5, "blink"),
+    (7, "reverse"),
+    (8, "hidden"),
+    (9, "strike"),
+];
+
+/// Build a `comat::Style` that does what the SGR escapes in `code` do.
+pub fn style(code: &str) -> Result<TokenStream, String> {
+    let mut attributes = [false; 10];
+    let (mut fg, mut bg) = (None, None);
+    for sequence in code.split('\x1b').filter(|s| !s.is_empty()) {
+        let params = sequence
+            .strip_prefix('[')
+            .and_then(|s| s.strip_suffix('m'))
+            .ok_or("cstyle! only takes colors and attributes")?;
+        let mut params = params.split(';').map(|p| {
+            if p.is_empty() {
+                Some(0)
+            } else {
+                p.parse::<u8>().ok()
+            }
+        });
+        while let Some(param) = params.next() {
+            let param = param.ok_or("invalid SGR parameter")?;
+            match param {
+                0 => (attributes, fg, bg) = ([false; 10], None, None),
+                1..=9 if param != 6 => attributes[param as usize] = true,
+                22 => (attributes[1], attributes[2]) = (false, false),
+                23..=29 if param != 26 => attributes[param as usize - 20] = false,
+                30..=37 => fg = Some(basic(param - 30)),
+                90..=97 => fg = Some(basic(param - 90 + 8)),
+                39 => fg = None,
+                40..=47 => bg = Some(basic(param - 40)),
+                100..=107 => bg = Some(basic(param - 100 + 8)),
+                49 => bg = None,
+                38 | 48 => {
+                    let mut next = || params.next().flatten().ok_or("invalid SGR color");
+                    let color = match next()? {
+                        5 => {
+                            let n = next()?;
+                            quote! { ::comat::Color::Fixed(#n) }
+                        }
+                        2 => {
+                            let (r, g, b) = (next()?, next()?, next()?);
+                            quote! { ::comat::Color::Rgb(#r, #g, #b) }
+                        }
+                        _ => return Err("invalid SGR color".into()),
+                    };
+                    if param == 38 {
+                        fg = Some(color);
+                    } else {
+                        bg = Some(color);
+                    }
+                }
+                _ => {
+                    return Err(format!(
+                        "cstyle! does not support the SGR parameter {param}"
+                    ))
+                }
+            }
+        }
+    }
+    let attributes = ATTRIBUTES
+        .iter()
+        .filter(|&&(param, _)| attributes[param as usize])
+        .map(|&(_, name)| {
+            let name = proc_macro2::Ident::new(name, proc_macro2::Span::call_site());
+            quote! { .#name() }
+        });
+    let fg = fg.map(|fg| quote! { .fg(#fg) });
+    let bg = bg.map(|bg| quote! { .on(#bg) });
+    Ok(quote! { ::comat::Style::new() #(#attributes)* #fg #bg })
+}
+
+/// One of the 16 basic colors.
+fn basic(n: u8) -> TokenStream {
+    quote! { ::comat::Color::Basic(#n) }
+}
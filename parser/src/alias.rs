@@ -0,0 +1,32 @@
+//! The tokens of a `comat_alias!`, while a macro it wraps is expanded.
+use std::cell::RefCell;
+
+thread_local! {
+    /// The aliases of the macro being expanded, as `(name, spec)`.
+    static ALIASES: RefCell<Vec<(String, String)>> = const { RefCell::new(vec![]) };
+}
+
+/// The aliases of the macro being expanded, when it is in [`with`].
+#[must_use]
+pub fn aliases() -> Vec<(String, String)> {
+    ALIASES.with_borrow(Clone::clone)
+}
+
+/// Call `f` with `aliases` (as `(name, spec)`, where `spec` is like the `[tokens]` of a `comat.toml`) in every [`Theme`](crate::Theme) it loads.
+/// ```
+/// use comat_parser::{alias, Theme};
+/// let theme = alias::with(vec![("err".into(), "bold_red".into())], || Theme::load().unwrap());
+/// assert_eq!(theme.get("err"), Theme::default().get("bold_red"));
+/// assert_eq!(Theme::load().unwrap().get("err"), None);
+/// ```
+pub fn with<T>(aliases: Vec<(String, String)>, f: impl FnOnce() -> T) -> T {
+    /// Puts back the aliases from before, even if `f` panics.
+    struct Restore(Vec<(String, String)>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            ALIASES.set(std::mem::take(&mut self.0));
+        }
+    }
+    let _restore = Restore(ALIASES.replace(aliases));
+    f()
+}
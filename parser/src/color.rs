@@ -0,0 +1,239 @@
+//! Color spaces, and getting them onto the terminal.
+
+/// The levels of each channel in the xterm 6x6x6 color cube.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Squared distance between two colors.
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter()
+        .zip(b)
+        .map(|(&a, b)| u32::from(a.abs_diff(b)).pow(2))
+        .sum()
+}
+
+/// The nearest xterm 256 color, from the color cube or the grayscale ramp.
+pub fn to_256(rgb: [u8; 3]) -> u8 {
+    let level = |c: u8| {
+        (0..6)
+            .min_by_key(|&i| CUBE[usize::from(i)].abs_diff(c))
+            .unwrap_or_default()
+    };
+    let [r, g, b] = rgb.map(level);
+    let cube = [r, g, b].map(|i| CUBE[usize::from(i)]);
+    let avg = u8::try_from(rgb.iter().map(|&c| u32::from(c)).sum::<u32>() / 3).unwrap_or(u8::MAX);
+    let gray = (avg.saturating_sub(3) / 10).min(23);
+    let level = 8 + gray * 10;
+    if distance(rgb, [level; 3]) < distance(rgb, cube) {
+        232 + gray
+    } else {
+        16 + 36 * r + 6 * g + b
+    }
+}
+
+/// The standard values of the first 16 colors.
+const SYSTEM: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [128, 0, 0],
+    [0, 128, 0],
+    [128, 128, 0],
+    [0, 0, 128],
+    [128, 0, 128],
+    [0, 128, 128],
+    [192, 192, 192],
+    [128, 128, 128],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [0, 0, 255],
+    [255, 0, 255],
+    [0, 255, 255],
+    [255, 255, 255],
+];
+
+/// The (usual) value of a xterm 256 color.
+pub fn from_256(i: u8) -> [u8; 3] {
+    match i {
+        0..16 => SYSTEM[usize::from(i)],
+        16..232 => {
+            let i = i - 16;
+            [i / 36, i / 6 % 6, i % 6].map(|c| CUBE[usize::from(c)])
+        }
+        232.. => [8 + (i - 232) * 10; 3],
+    }
+}
+
+/// Parse `rrggbb` or `rgb`.
+pub fn hex(hex: &str) -> Option<[u8; 3]> {
+    let digit =
+        |i: usize, len: usize| u8::from_str_radix(hex.get(i * len..(i + 1) * len)?, 16).ok();
+    match hex.len() {
+        6 => Some([digit(0, 2)?, digit(1, 2)?, digit(2, 2)?]),
+        3 => Some([digit(0, 1)? * 17, digit(1, 1)? * 17, digit(2, 1)? * 17]),
+        _ => None,
+    }
+}
+
+/// Black or white, whichever is more readable on top of `rgb`.
+pub fn contrast(rgb: [u8; 3]) -> [u8; 3] {
+    // https://www.w3.org/TR/WCAG21/#dfn-relative-luminance
+    let [r, g, b] = rgb.map(|c| {
+        let c = f64::from(c) / 255.0;
+        if c <= 0.039_28 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    });
+    let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    // where the contrast ratio against black and white is equal
+    if luminance > 0.179 {
+        [0; 3]
+    } else {
+        [255; 3]
+    }
+}
+
+/// Convert hue (degrees), saturation and lightness (percentages) to rgb.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn hsl(hue: f64, saturation: f64, lightness: f64) -> Option<[u8; 3]> {
+    if !(0.0..=100.0).contains(&saturation)
+        || !(0.0..=100.0).contains(&lightness)
+        || !hue.is_finite()
+    {
+        return None;
+    }
+    let (saturation, lightness) = (saturation / 100.0, lightness / 100.0);
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let second = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let rgb = match sector as u8 {
+        0 => [chroma, second, 0.0],
+        1 => [second, chroma, 0.0],
+        2 => [0.0, chroma, second],
+        3 => [0.0, second, chroma],
+        4 => [second, 0.0, chroma],
+        _ => [chroma, 0.0, second],
+    };
+    let m = lightness - chroma / 2.0;
+    Some(rgb.map(|v| ((v + m) * 255.0).round() as u8))
+}
+
+/// Linearly interpolate between evenly spaced `stops`, at `t` (0..=1).
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+pub fn gradient(stops: &[[u8; 3]], t: f64) -> [u8; 3] {
+    let t = t.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+    let i = (t as usize).min(stops.len() - 2);
+    let (a, b, t) = (stops[i], stops[i + 1], t - i as f64);
+    std::array::from_fn(|c| {
+        (f64::from(a[c]) + (f64::from(b[c]) - f64::from(a[c])) * t).round() as u8
+    })
+}
+
+/// The escape for a xterm 256 foreground (or background).
+pub fn xterm(i: u8, background: bool) -> String {
+    format!("\x1b[{};5;{i}m", if background { 48 } else { 38 })
+}
+
+/// The escape for a truecolor foreground (or background).
+pub fn rgb(rgb: [u8; 3], background: bool) -> String {
+    let [r, g, b] = rgb;
+    format!("\x1b[{};2;{r};{g};{b}m", if background { 48 } else { 38 })
+}
+
+/// How many colors the terminal has.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Depth {
+    /// The 16 basic colors.
+    Ansi16,
+    /// The xterm 256 color palette.
+    Ansi256,
+    /// 24 bit color.
+    Truecolor,
+}
+
+impl Depth {
+    /// The depth selected with the `ansi16` / `ansi256` features.
+    pub const FEATURE: Self = if cfg!(feature = "ansi16") {
+        Self::Ansi16
+    } else if cfg!(feature = "ansi256") {
+        Self::Ansi256
+    } else {
+        Self::Truecolor
+    };
+}
+
+/// The nearest of the first 16 colors.
+fn to_16(rgb: [u8; 3]) -> u8 {
+    (0..16)
+        .min_by_key(|&i| distance(SYSTEM[usize::from(i)], rgb))
+        .unwrap_or_default()
+}
+
+/// Rewrite the 256 and truecolor colors in `ansi` to fit in `depth`.
+pub fn downgrade(ansi: &str, depth: Depth) -> String {
+    if depth == Depth::Truecolor {
+        return ansi.to_string();
+    }
+    let mut out = String::with_capacity(ansi.len());
+    let mut rest = ansi;
+    while let Some(start) = rest.find("\x1b[") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start + 2..];
+        let Some(end) = rest
+            .find(|c: char| !c.is_ascii_digit() && c != ';')
+            .filter(|&end| rest[end..].starts_with('m'))
+        else {
+            // not SGR
+            out.push_str("\x1b[");
+            continue;
+        };
+        out.push_str("\x1b[");
+        out.push_str(&sgr(&rest[..end], depth));
+        out.push('m');
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Rewrite SGR parameters.
+fn sgr(params: &str, depth: Depth) -> String {
+    let params = params.split(';').collect::<Vec<_>>();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < params.len() {
+        let n = |j: usize| params.get(i + j)?.parse::<u8>().ok();
+        let color = match (params[i], params.get(i + 1).copied()) {
+            (layer @ ("38" | "48"), Some("5")) => n(2).map(|c| (layer, 3, None, c)),
+            (layer @ ("38" | "48"), Some("2")) => {
+                (|| Some([n(2)?, n(3)?, n(4)?]))().map(|rgb| (layer, 5, Some(rgb), to_256(rgb)))
+            }
+            _ => None,
+        };
+        let Some((layer, len, rgb, index)) = color else {
+            out.push(params[i].to_string());
+            i += 1;
+            continue;
+        };
+        i += len;
+        out.push(match depth {
+            Depth::Ansi256 | Depth::Truecolor => format!("{layer};5;{index}"),
+            Depth::Ansi16 => {
+                let c = match index {
+                    0..16 => index,
+                    _ => to_16(rgb.unwrap_or_else(|| from_256(index))),
+                };
+                let base = if layer == "48" { 40 } else { 30 };
+                match c {
+                    0..8 => format!("{}", base + c),
+                    _ => format!("{}", base + 60 + c - 8),
+                }
+            }
+        });
+    }
+    out.join(";")
+}
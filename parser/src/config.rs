@@ -0,0 +1,275 @@
+use std::path::{Path, PathBuf};
+
+/// The crate's `comat.toml`, found by walking up from the crate root.
+///
+/// ```toml
+/// theme = "solarized"
+/// close = "default"
+/// variants = ["high_contrast", "deuteranopia"]
+///
+/// [tokens]
+/// err = "red,bold"
+/// path = "cyan,underline"
+/// orange = "38;5;208"
+/// ```
+#[derive(Default)]
+pub struct Config {
+    pub path: Option<PathBuf>,
+    pub theme: Option<String>,
+    pub close: crate::Close,
+    pub variants: Vec<String>,
+    pub tokens: Vec<(String, String)>,
+}
+
+impl Config {
+    pub fn load() -> Result<Self, String> {
+        let Some(path) = Self::find() else {
+            return Ok(Self::default());
+        };
+        let mut config = Self::default();
+        let err = |e: String| format!("{}: {e}", path.display());
+        for (key, value) in read(&path)? {
+            match key.split_once('.') {
+                None if key == "theme" => config.theme = Some(value.string(&key).map_err(err)?),
+                None if key == "close" => {
+                    config.close = value.string(&key).map_err(err)?.parse().map_err(err)?;
+                }
+                None if key == "variants" => match value {
+                    Value::Array(names) => config.variants = names,
+                    Value::String(_) => return Err(err(
+                        "expected an array of themes for \"variants\", like [\"high_contrast\"]"
+                            .into(),
+                    )),
+                },
+                Some(("tokens", token)) => config
+                    .tokens
+                    .push((token.to_string(), value.string(&key).map_err(err)?)),
+                _ => return Err(err(format!("unknown key {key:?}"))),
+            }
+        }
+        config.path = Some(path);
+        Ok(config)
+    }
+
+    /// Where the `comat.toml` is, if there is one.
+    pub fn find() -> Option<PathBuf> {
+        let dir = std::env::var_os("CARGO_MANIFEST_DIR")?;
+        Path::new(&dir)
+            .ancestors()
+            .map(|dir| dir.join("comat.toml"))
+            .find(|path| path.is_file())
+    }
+
+    /// Resolve a path relative to the `comat.toml`.
+    pub fn relative(&self, path: &str) -> PathBuf {
+        match self.path.as_deref().and_then(Path::parent) {
+            Some(dir) => dir.join(path),
+            None => PathBuf::from(path),
+        }
+    }
+}
+
+/// Read and [`parse`] a file.
+pub fn read(path: &Path) -> Result<Vec<(String, Value)>, String> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| format!("couldnt read {}: {e}", path.display()))?;
+    parse(&source).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// A value in a file.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Value {
+    String(String),
+    Array(Vec<String>),
+}
+
+impl Value {
+    /// The string this is, or an error naming `key`.
+    pub fn string(self, key: &str) -> Result<String, String> {
+        match self {
+            Self::String(s) => Ok(s),
+            Self::Array(_) => Err(format!("expected a string for {key:?}, not an array")),
+        }
+    }
+}
+
+/// Parse the subset of toml that comat's files use: `key = value` pairs, under `[table]`s, and `#` comments.
+/// A value is a string, `"basic"` (with escapes, like `\n` and `\u00e9`) or `'literal'`, or an array of strings, which can span lines.
+/// Keys can be bare or quoted. Keys under a `[table]` are returned as `table.key`.
+///
+/// Numbers, booleans, inline tables, multi-line strings and dotted keys are not in the subset.
+pub fn parse(source: &str) -> Result<Vec<(String, Value)>, String> {
+    let mut reader = Reader {
+        rest: source,
+        line: 1,
+    };
+    reader
+        .pairs()
+        .map_err(|e| format!("line {}: {e}", reader.line))
+}
+
+/// Where [`parse`] is in the file.
+struct Reader<'a> {
+    rest: &'a str,
+    line: usize,
+}
+
+impl Reader<'_> {
+    fn pairs(&mut self) -> Result<Vec<(String, Value)>, String> {
+        let mut out = vec![];
+        let mut table = None;
+        loop {
+            self.blank(true);
+            if self.rest.is_empty() {
+                return Ok(out);
+            }
+            if self.eat('[') {
+                self.blank(false);
+                let name = self.key()?;
+                self.blank(false);
+                self.expect(']')?;
+                table = Some(name);
+            } else {
+                let key = self.key()?;
+                self.blank(false);
+                self.expect('=')?;
+                self.blank(false);
+                let value = self.value()?;
+                out.push((
+                    match &table {
+                        Some(table) => format!("{table}.{key}"),
+                        None => key,
+                    },
+                    value,
+                ));
+            }
+            self.blank(false);
+            self.eat('\r');
+            if !self.rest.is_empty() && !self.eat('\n') {
+                return Err("expected the end of the line".into());
+            }
+        }
+    }
+
+    /// Skip spaces and comments, and newlines too if `newlines`.
+    fn blank(&mut self, newlines: bool) {
+        loop {
+            self.rest = self.rest.trim_start_matches([' ', '\t']);
+            if self.rest.starts_with('#') {
+                self.rest = &self.rest[self.rest.find('\n').unwrap_or(self.rest.len())..];
+            } else if !(newlines && (self.eat('\r') || self.eat('\n'))) {
+                return;
+            }
+        }
+    }
+
+    /// Take `c`, if it is next, giving whether it was.
+    fn eat(&mut self, c: char) -> bool {
+        let Some(rest) = self.rest.strip_prefix(c) else {
+            return false;
+        };
+        self.rest = rest;
+        self.line += usize::from(c == '\n');
+        true
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(format!("expected `{c}`"))
+        }
+    }
+
+    /// A bare or quoted key.
+    fn key(&mut self) -> Result<String, String> {
+        if self.rest.starts_with(['"', '\'']) {
+            return self.string();
+        }
+        let end = self
+            .rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(self.rest.len());
+        if end == 0 {
+            return Err("expected a key".into());
+        }
+        let key = self.rest[..end].to_string();
+        self.rest = &self.rest[end..];
+        Ok(key)
+    }
+
+    /// A string, or an array of them.
+    fn value(&mut self) -> Result<Value, String> {
+        if !self.eat('[') {
+            return self.string().map(Value::String);
+        }
+        let mut items = vec![];
+        loop {
+            self.blank(true);
+            if self.eat(']') {
+                return Ok(Value::Array(items));
+            }
+            items.push(self.string()?);
+            self.blank(true);
+            if !self.eat(',') {
+                self.expect(']')?;
+                return Ok(Value::Array(items));
+            }
+        }
+    }
+
+    /// A `"basic"` or `'literal'` string.
+    fn string(&mut self) -> Result<String, String> {
+        if self.rest.starts_with("\"\"\"") || self.rest.starts_with("'''") {
+            return Err("multi-line strings are not supported".into());
+        }
+        if self.eat('\'') {
+            let end = self
+                .rest
+                .find(['\'', '\n'])
+                .filter(|&end| self.rest[end..].starts_with('\''))
+                .ok_or("expected `'`")?;
+            let s = self.rest[..end].to_string();
+            self.rest = &self.rest[end + 1..];
+            return Ok(s);
+        }
+        if !self.eat('"') {
+            return Err("expected a string".into());
+        }
+        let mut out = String::new();
+        let mut chars = self.rest.chars();
+        loop {
+            let c = match chars.next() {
+                None | Some('\n') => return Err("expected `\"`".into()),
+                Some('"') => break,
+                Some('\\') => match chars.next() {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('b') => '\u{8}',
+                    Some('f') => '\u{c}',
+                    Some('e') => '\x1b',
+                    Some(c @ ('"' | '\\')) => c,
+                    Some(u @ ('u' | 'U')) => {
+                        let len = if u == 'u' { 4 } else { 8 };
+                        let hex = chars
+                            .as_str()
+                            .get(..len)
+                            .ok_or("expected a unicode escape")?;
+                        let c = u32::from_str_radix(hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| format!("invalid unicode escape \\{u}{hex}"))?;
+                        chars = chars.as_str()[len..].chars();
+                        c
+                    }
+                    _ => return Err("invalid escape".into()),
+                },
+                Some(c) => c,
+            };
+            out.push(c);
+        }
+        self.rest = chars.as_str();
+        Ok(out)
+    }
+}
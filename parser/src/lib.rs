@@ -0,0 +1,1091 @@
+//! The parser behind [comat](https://docs.rs/comat)'s macros, which turns comat syntax into a format string with escapes.
+//!
+//! Use it from build scripts, or other proc macros:
+//! ```
+//! let s = comat_parser::translate("{bold}comat{reset}: {version:green}").unwrap();
+//! assert_eq!(s, "\x1b[1mcomat\x1b[0m: \x1b[0;34;32m{version}\x1b[0m");
+//! ```
+//! [`parse`] gives more control.
+#![forbid(unsafe_code)]
+#![warn(clippy::pedantic, clippy::dbg_macro, missing_docs)]
+use std::fmt::Write;
+
+pub mod alias;
+mod color;
+mod config;
+mod css;
+mod theme;
+mod xterm;
+
+pub use color::Depth;
+pub use theme::Theme;
+
+/// Translate `input` to a format string, with the theme comat's macros would use (see [`Theme::load`]).
+///
+/// # Errors
+///
+/// when the syntax is invalid, the theme cannot be loaded, or `input` uses formatters, scales or styles, which need a [`parse`]r that can give them arguments.
+pub fn translate(input: &str) -> Result<String, String> {
+    let parsed = parse(input, &Theme::load()?, &Options::default())?;
+    if parsed
+        .made
+        .iter()
+        .all(|(_, made)| matches!(made, Made::Literal(_)))
+    {
+        Ok(inline(&parsed.text, &parsed.made, true))
+    } else {
+        Err(
+            "formatters, scales and [style]s only work in the macros that format, like cformat!"
+                .into(),
+        )
+    }
+}
+
+/// Put the text of the `{!token}` blocks ([`Made::Literal`]s) into `text`, with their braces doubled if it is a format string.
+#[must_use]
+pub fn inline(text: &str, made: &[(String, Made)], format: bool) -> String {
+    let mut text = text.to_string();
+    for (i, (_, made)) in made.iter().enumerate() {
+        if let Made::Literal(literal) = made {
+            let literal = if format {
+                literal.replace('{', "{{").replace('}', "}}")
+            } else {
+                literal.clone()
+            };
+            text = text.replace(&format!("{{__comat_{i}}}"), &literal);
+        }
+    }
+    text
+}
+
+/// Check that `text` is a format string `format!` would accept, so the error can point at what was written, rather than what it was translated to.
+/// ```
+/// assert!(comat_parser::check("{0} {name:>8.2} {{").is_ok());
+/// assert!(comat_parser::check("{ñ:>ñ$}").is_ok());
+/// assert_eq!(comat_parser::check("{0} {name:>>>}").unwrap_err().at, Some(4..14));
+/// ```
+///
+/// # Errors
+///
+/// when a `{` is not closed, a `}` is not opened, or a block is not a valid argument and spec.
+pub fn check(text: &str) -> Result<(), Error> {
+    let mut rest = text;
+    while let Some(at) = rest.find(['{', '}']) {
+        let start = text.len() - rest.len() + at;
+        let (brace, after) = (&rest[at..=at], &rest[at + 1..]);
+        if after.starts_with(brace) {
+            rest = &after[1..];
+            continue;
+        }
+        let error = |message: String, end: usize| Error {
+            message,
+            at: Some(start..end),
+        };
+        if brace == "}" {
+            return Err(error(
+                "unmatched `}` in format string, use `}}` for a `}`".into(),
+                start + 1,
+            ));
+        }
+        let end = after
+            .find(['{', '}'])
+            .filter(|&end| after[end..].starts_with('}'))
+            .ok_or_else(|| {
+                error(
+                    "unclosed `{` in format string, use `{{` for a `{`".into(),
+                    text.len(),
+                )
+            })?;
+        let block = &after[..end];
+        let (arg, spec) = block.split_once(':').unwrap_or((block, ""));
+        let end = start + end + 2;
+        if !(arg.is_empty() || arg.bytes().all(|b| b.is_ascii_digit()) || ident(arg)) {
+            return Err(error(
+                format!("invalid argument {arg:?} in {{{block}}}"),
+                end,
+            ));
+        }
+        if !valid_spec(spec) {
+            return Err(error(
+                format!("invalid format spec {spec:?} in {{{block}}}"),
+                end,
+            ));
+        }
+        rest = &after[end - start - 1..];
+    }
+    Ok(())
+}
+
+/// Whether `spec` is `[[fill]align][sign]['#']['0'][width]['.' precision][type]`.
+fn valid_spec(spec: &str) -> bool {
+    /// An integer, or an argument followed by a `$`.
+    fn count(s: &str) -> &str {
+        let end = s
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(s.len());
+        let (count, rest) = s.split_at(end);
+        match rest.strip_prefix('$') {
+            Some(rest) if count.bytes().all(|b| b.is_ascii_digit()) || ident(count) => rest,
+            _ => {
+                let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+                &s[digits..]
+            }
+        }
+    }
+    let mut s = spec;
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(fill), Some('<' | '^' | '>')) => s = &s[fill.len_utf8() + 1..],
+        (Some('<' | '^' | '>'), _) => s = &s[1..],
+        _ => {}
+    }
+    s = s.strip_prefix(['+', '-']).unwrap_or(s);
+    s = s.strip_prefix('#').unwrap_or(s);
+    // `0$` is a width, not the `0` flag
+    if !s.starts_with("0$") {
+        s = s.strip_prefix('0').unwrap_or(s);
+    }
+    s = count(s);
+    if let Some(precision) = s.strip_prefix('.') {
+        s = precision
+            .strip_prefix('*')
+            .unwrap_or_else(|| count(precision));
+    }
+    matches!(s, "" | "?" | "x?" | "X?") || ident(s)
+}
+
+/// Settings for [`parse`].
+#[derive(Clone, Debug, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Options {
+    /// Styles applied to the whole string, restored after every reset.
+    pub base: Vec<String>,
+    /// Strip the common indentation of the lines.
+    pub dedent: bool,
+    /// Pass the escapes tmux does not understand (like links and titles) through to the terminal.
+    pub tmux: bool,
+    /// How to end `{arg:colors}` blocks, over the [theme's](Theme::close).
+    pub close: Option<Close>,
+    /// Use `<token>` and `</>` (for a reset) in place of `{token}`, leaving the braces alone.
+    pub tags: bool,
+    /// Leave out the escapes, putting text markers (like `[error] `) where the semantic tokens were, for screen readers.
+    pub markers: bool,
+    /// Leave out the escapes.
+    pub plain: bool,
+}
+
+/// How a `{arg:colors}` block ends.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Close {
+    /// Reset, and go back to the style before the block.
+    #[default]
+    Reset,
+    /// Go back to the default colors (`\x1b[39;49m`), keeping attributes like bold.
+    Default,
+    /// Leave the block's style on.
+    None,
+}
+
+impl std::str::FromStr for Close {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "reset" => Ok(Self::Reset),
+            "default" => Ok(Self::Default),
+            "none" => Ok(Self::None),
+            _ => Err(format!(
+                "unknown close {s:?}. expected one of \"reset\", \"default\" or \"none\""
+            )),
+        }
+    }
+}
+
+/// A [`parse`]d string.
+pub struct Parsed {
+    /// The format string.
+    pub text: String,
+    /// The arguments the blocks made, by the name (or, for a positional argument, the index, or `""` for the next) of the argument they were made from. The `n`th is named `__comat_{n}` in [`text`](Parsed::text).
+    pub made: Vec<(String, Made)>,
+    /// The tokens the string used, in order, like `bold` or `gradient(red,blue)`.
+    pub tokens: Vec<String>,
+}
+
+/// Why [`parse`] or [`check`] failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    /// What went wrong.
+    pub message: String,
+    /// The bytes of the input that are wrong, if known.
+    /// This is `None` when the input was changed before parsing, as by [`Options::dedent`].
+    pub at: Option<std::ops::Range<usize>>,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Self { message, at: None }
+    }
+}
+
+impl From<Error> for String {
+    fn from(error: Error) -> Self {
+        error.message
+    }
+}
+
+/// Parse `input`, coloring it with `theme`.
+/// ```
+/// # use comat_parser::*;
+/// let error = parse("{bold}comat{reset", &Theme::default(), &Options::default()).err().unwrap();
+/// assert_eq!((&*error.message, error.at), ("unexpected eof", Some(11..17)));
+/// ```
+///
+/// # Errors
+///
+/// when the syntax is invalid, or a style in `options` is unknown.
+pub fn parse(input: &str, theme: &Theme, options: &Options) -> Result<Parsed, Error> {
+    let mut base = String::new();
+    for style in &options.base {
+        base.push_str(
+            &theme
+                .get(style)
+                .ok_or_else(|| format!("unknown style {style:?}"))?,
+        );
+    }
+    let mut out = Out {
+        s: base.clone(),
+        theme,
+        base,
+        formatted: vec![],
+        tokens: vec![],
+        active: String::new(),
+        close: options.close.unwrap_or(theme.close),
+        markers: options.markers,
+    };
+    let mut input = std::borrow::Cow::Borrowed(input);
+    if options.dedent {
+        input = dedent(&input).into();
+    }
+    if options.tags {
+        input = tags(&input, theme).into();
+    }
+    out.parse(&input).map_err(|error| match input {
+        std::borrow::Cow::Borrowed(_) => error,
+        std::borrow::Cow::Owned(_) => Error { at: None, ..error },
+    })?;
+    if !out.base.is_empty() {
+        out.s.push_str(RESET);
+    }
+    let Out {
+        mut s,
+        formatted,
+        tokens,
+        ..
+    } = out;
+    s = squeeze(&s);
+    if options.markers || options.plain {
+        s = strip(&s);
+    }
+    if options.tmux {
+        s = tmux(&s);
+    }
+    Ok(Parsed {
+        text: s,
+        made: formatted,
+        tokens,
+    })
+}
+
+/// Put `open` and `close` around every run of escape sequences in `s`, for shell prompts.
+/// ```
+/// assert_eq!(comat_parser::guard("\x1b[1m\x1b[2m$\x1b[0m ", "\\[", "\\]"), "\\[\x1b[1m\x1b[2m\\]$\\[\x1b[0m\\] ");
+/// ```
+#[must_use]
+pub fn guard(s: &str, open: &str, close: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('\x1b') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let mut end = 0;
+        while rest[end..].starts_with('\x1b') {
+            end += escape_len(&rest[end..]);
+        }
+        out.push_str(open);
+        out.push_str(&rest[..end]);
+        out.push_str(close);
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The basic colors and effects.
+fn name2ansi(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "black" => "\x1b[0;34;30m",
+        "red" => "\x1b[0;34;31m",
+        "green" => "\x1b[0;34;32m",
+        "yellow" => "\x1b[0;34;33m",
+        "blue" => "\x1b[0;34;34m",
+        "magenta" => "\x1b[0;34;35m",
+        "cyan" => "\x1b[0;34;36m",
+        "white" => "\x1b[0;34;37m",
+        "default" => "\x1b[0;34;39m",
+
+        "bold_black" => "\x1b[1;34;30m",
+        "bold_red" => "\x1b[1;34;31m",
+        "bold_green" => "\x1b[1;34;32m",
+        "bold_yellow" => "\x1b[1;34;33m",
+        "bold_blue" => "\x1b[1;34;34m",
+        "bold_magenta" => "\x1b[1;34;35m",
+        "bold_cyan" => "\x1b[1;34;36m",
+        "bold_white" => "\x1b[1;34;37m",
+        "bold_default" => "\x1b[1;34;39m",
+
+        "on_black_bold" => "\x1b[1;34;40m",
+        "on_red_bold" => "\x1b[1;34;41m",
+        "on_green_bold" => "\x1b[1;34;42m",
+        "on_yellow_bold" => "\x1b[1;34;43m",
+        "on_blue_bold" => "\x1b[1;34;44m",
+        "on_magenta_bold" => "\x1b[1;44;35m",
+        "on_cyan_bold" => "\x1b[1;34;46m",
+        "on_white_bold" => "\x1b[1;34;47m",
+        "on_default_bold" => "\x1b[1;34;49m",
+
+        "on_black" => "\x1b[0;34;40m",
+        "on_red" => "\x1b[0;34;41m",
+        "on_green" => "\x1b[0;34;42m",
+        "on_yellow" => "\x1b[0;34;43m",
+        "on_blue" => "\x1b[0;34;44m",
+        "on_magenta" => "\x1b[0;44;35m",
+        "on_cyan" => "\x1b[0;34;46m",
+        "on_white" => "\x1b[0;34;47m",
+        "on_default" => "\x1b[0;34;49m",
+
+        "reset" => "\x1b[0m",
+        "dim" => "\x1b[2m",
+        "italic" => "\x1b[3m",
+        "underline" => "\x1b[24m",
+        "blinking" => "\x1b[5m",
+        "hide" => "\x1b[8m",
+        "strike" => "\x1b[9m",
+        "bold" => "\x1b[1m",
+        _ => return None,
+    })
+}
+
+/// Every token comat knows about, before theming.
+fn builtin(name: &str) -> Option<String> {
+    #[cfg(feature = "aliases")]
+    if let Some(name) = short(name) {
+        return builtin(name);
+    }
+    if let Some(ansi) = name2ansi(name) {
+        return Some(ansi.to_string());
+    }
+    if let Some(color) = name.strip_prefix("auto_on_") {
+        let (background, rgb) = if let Some(i) = index(color) {
+            (color::xterm(i, true), color::from_256(i))
+        } else {
+            let rgb = rgb(color)?;
+            (color::rgb(rgb, true), rgb)
+        };
+        return Some(background + &color::rgb(color::contrast(rgb), false));
+    }
+    let (color, background) = match name.strip_prefix("on_") {
+        Some(color) => (color, true),
+        None => (name, false),
+    };
+    match index(color) {
+        Some(i) => Some(color::xterm(i, background)),
+        None => rgb(color).map(|rgb| color::rgb(rgb, background)),
+    }
+}
+
+/// The long name of a single letter alias.
+#[cfg(feature = "aliases")]
+fn short(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "r" => "red",
+        "g" => "green",
+        "b" => "blue",
+        "y" => "yellow",
+        "c" => "cyan",
+        "m" => "magenta",
+        "w" => "white",
+        "k" => "black",
+        "*" => "bold",
+        _ => return None,
+    })
+}
+
+/// A 256 color: a xterm name, or `gray(0..24)`.
+/// A name without a number that is also a CSS name is the CSS color, so only numbered names (like `orange1`) are xterm's then.
+fn index(color: &str) -> Option<u8> {
+    if let Some([n]) = call(color, "gray").or_else(|| call(color, "grey")) {
+        return n.parse::<u8>().ok().filter(|&n| n < 24).map(|n| 232 + n);
+    }
+    if !color.contains(|c: char| c.is_ascii_digit()) && css::rgb(color).is_some() {
+        return None;
+    }
+    xterm::index(color)
+}
+
+/// A truecolor: `#rrggbb`, `hsl(..)`, or a CSS name.
+fn rgb(color: &str) -> Option<[u8; 3]> {
+    if let Some(hex) = color.strip_prefix('#') {
+        return color::hex(hex);
+    }
+    if let Some([h, s, l]) = call(color, "hsl") {
+        let percent = |x: &str| x.strip_suffix('%').unwrap_or(x).parse::<f64>().ok();
+        return color::hsl(h.parse().ok()?, percent(s)?, percent(l)?);
+    }
+    css::rgb(color.strip_prefix("css_").unwrap_or(color))
+}
+
+/// The arguments of `name(a, b, ..)`.
+fn call<'a, const N: usize>(token: &'a str, name: &str) -> Option<[&'a str; N]> {
+    call_n(token, name)?.try_into().ok()
+}
+
+/// The arguments of `name(a, b, ..)`, however many.
+fn call_n<'a>(token: &'a str, name: &str) -> Option<Vec<&'a str>> {
+    let args = token
+        .strip_prefix(name)?
+        .strip_prefix('(')?
+        .strip_suffix(')')?;
+    Some(split(args).into_iter().map(str::trim).collect())
+}
+
+/// Any color with a known value.
+fn any_rgb(color: &str) -> Option<[u8; 3]> {
+    index(color).map(color::from_256).or_else(|| rgb(color))
+}
+
+/// Read literal text up to `{/region}`. Any other blocks are an error.
+fn literal(chars: &mut std::str::Chars, region: &str) -> std::result::Result<String, String> {
+    let mut text = String::new();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' => match chars.next() {
+                Some('{') => text.push('{'),
+                Some(ch) => {
+                    let mut block = ch.to_string();
+                    for ch in chars.by_ref() {
+                        if ch == '}' {
+                            break;
+                        }
+                        block.push(ch);
+                    }
+                    if block.strip_prefix('/') == Some(region) {
+                        return Ok(text);
+                    }
+                    return Err(format!("only text can be inside a {region}"));
+                }
+                None => break,
+            },
+            '}' => match chars.next() {
+                Some('}') => text.push('}'),
+                _ => return Err("unexpected text".into()),
+            },
+            ch => text.push(ch),
+        }
+    }
+    Err(format!("unclosed {region}"))
+}
+
+/// Split a list of tokens on the commas that are not inside parentheses (or brackets).
+#[must_use]
+pub fn split(tokens: &str) -> Vec<&str> {
+    let mut out = vec![];
+    let (mut depth, mut start) = (0_usize, 0);
+    for (i, c) in tokens.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                out.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    out.push(&tokens[start..]);
+    out
+}
+
+/// The expression in a `[style]` token.
+fn style(token: &str) -> Option<&str> {
+    token
+        .trim()
+        .strip_prefix('[')?
+        .strip_suffix(']')
+        .map(str::trim)
+        .filter(|style| !style.is_empty())
+}
+
+/// What the semantic token `token` means, as text, for [`Options::markers`].
+fn marker(token: &str) -> Option<&'static str> {
+    Some(match token {
+        "error" => "[error] ",
+        "warn" => "[warning] ",
+        "info" => "[info] ",
+        "success" => "[ok] ",
+        "hint" => "[hint] ",
+        _ => return None,
+    })
+}
+
+/// `s`, without its escape sequences.
+fn strip(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(at) = rest.find('\x1b') {
+        out.push_str(&rest[..at]);
+        rest = &rest[at + escape_len(&rest[at..])..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The length of the escape sequence `s` starts with.
+fn escape_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let end = match bytes.get(1) {
+        // CSI: parameters, then a final byte in @..=~
+        Some(b'[') => bytes[2..]
+            .iter()
+            .position(|b| matches!(b, 0x40..=0x7e))
+            .map(|i| i + 3),
+        // OSC (and the other strings): until BEL or ST (ESC \)
+        Some(b']' | b'P' | b'_' | b'^') => (2..bytes.len()).find_map(|i| match bytes[i] {
+            0x07 => Some(i + 1),
+            0x1b if bytes.get(i + 1) == Some(&b'\\') => Some(i + 2),
+            _ => None,
+        }),
+        Some(_) => Some(2),
+        None => None,
+    };
+    end.unwrap_or(bytes.len())
+}
+
+/// The escapes of `{esc(..)}`: `\e` for `ESC`, `\a` for `BEL`, and `\\` for a `\`.
+fn unescape(sequence: &str) -> std::result::Result<String, String> {
+    let mut out = String::with_capacity(sequence.len());
+    let mut chars = sequence.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some('e') => out.push('\x1b'),
+                Some('a') => out.push('\x07'),
+                Some('\\') => out.push('\\'),
+                other => {
+                    return Err(format!(
+                        "unknown escape \\{} in esc(..). expected \\e, \\a or \\\\",
+                        other.map(String::from).unwrap_or_default()
+                    ))
+                }
+            },
+            '{' => return Err("esc(..) cannot contain a `{`".into()),
+            ch => out.push(ch),
+        }
+    }
+    Ok(out)
+}
+
+/// Drop the resets that do nothing: those right after another reset, those right before a color that resets anyway (like `\x1b[0;34;31m`),
+/// and those where nothing can be styled, after an earlier reset.
+///
+/// What comes before the string is not known, so it may be styled, as may the arguments.
+fn squeeze(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    // whether something may be styled, and whether a reset is owed before the next text
+    let (mut styled, mut owed) = (true, false);
+    let mut rest = s;
+    while let Some(ch) = rest.chars().next() {
+        let len = match ch {
+            '\x1b' => escape_len(rest),
+            '{' if rest.starts_with("{{") => 2,
+            '{' => rest.find('}').map_or(1, |end| end + 1),
+            _ => ch.len_utf8(),
+        };
+        let (piece, after) = rest.split_at(len);
+        rest = after;
+        let sgr = piece
+            .strip_prefix("\x1b[")
+            .and_then(|params| params.strip_suffix('m'));
+        match sgr {
+            Some("0" | "") => owed = styled,
+            Some(params) => {
+                if owed && !params.starts_with("0;") {
+                    out.push_str(RESET);
+                }
+                out.push_str(piece);
+                (styled, owed) = (true, false);
+            }
+            None => {
+                if owed {
+                    out.push_str(RESET);
+                    (styled, owed) = (false, false);
+                }
+                out.push_str(piece);
+                // an argument
+                styled |= piece.len() > 1 && piece.starts_with('{') && !piece.starts_with("{{");
+            }
+        }
+    }
+    if owed {
+        out.push_str(RESET);
+    }
+    out
+}
+
+/// Wrap the escape sequences that are not CSI (like OSC 8 links) in tmux's passthrough, doubling their `ESC`s.
+fn tmux(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('\x1b') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let (escape, after) = rest.split_at(escape_len(rest));
+        if escape.starts_with("\x1b[") {
+            out.push_str(escape);
+        } else {
+            out.push_str("\x1bPtmux;");
+            out.push_str(&escape.replace('\x1b', "\x1b\x1b"));
+            out.push_str("\x1b\\");
+        }
+        rest = after;
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Whether `s` is an identifier, like a named argument, which can be any letters, like `ñ`.
+fn ident(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Turn `<token>`s into `{token}`s and `</>` into `{reset}`, doubling the braces.
+/// `<<` is a `<`, and tags that are not tokens are left alone, like `a < b`.
+fn tags(input: &str, theme: &Theme) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(at) = rest.find(['<', '{', '}']) {
+        out.push_str(&rest[..at]);
+        rest = &rest[at..];
+        let tag = rest[1..].find('>').map(|end| &rest[1..=end]);
+        match tag {
+            _ if rest.starts_with("<<") => {
+                out.push('<');
+                rest = &rest[2..];
+                continue;
+            }
+            _ if !rest.starts_with('<') => {
+                out.push_str(&rest[..1].repeat(2));
+                rest = &rest[1..];
+                continue;
+            }
+            Some("/") => out.push_str("{reset}"),
+            Some(tokens)
+                if !tokens.is_empty()
+                    && split(tokens)
+                        .iter()
+                        .all(|token| theme.get(token.trim()).is_some()) =>
+            {
+                for token in split(tokens) {
+                    out.push('{');
+                    out.push_str(token.trim());
+                    out.push('}');
+                }
+            }
+            _ => {
+                out.push('<');
+                rest = &rest[1..];
+                continue;
+            }
+        }
+        rest = &rest[tag.map_or(0, str::len) + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Remove the leading newline, and the indentation common to every line that isnt blank.
+fn dedent(text: &str) -> String {
+    let text = text
+        .strip_prefix('\n')
+        .or_else(|| text.strip_prefix("\r\n"))
+        .unwrap_or(text);
+    let indent = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    text.split('\n')
+        .map(|line| line.get(indent..).unwrap_or_else(|| line.trim_start()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// An argument made from an argument by a block, as `__comat_{n}`.
+#[derive(Clone)]
+pub enum Made {
+    /// `{arg::formatter}` (see [`Out::formatter`]): the function to format it with.
+    Formatter(String),
+    /// `{arg:scale(..)}` (see [`Out::scale`]): the `(threshold, escape)`s, and the escape for values past them.
+    Scale(Vec<(String, String)>, String),
+    /// `{arg:[style]}`: the expression for the `comat::Style` to show it in.
+    Style(String),
+    /// `{!token}`: the text `{token}`, to put in with [`inline`]. It is not made from an argument.
+    Literal(String),
+    /// `{src(path, line)}`: the expressions for the path and line to link to. It is not made from an argument either.
+    Source(String, String),
+    /// `{notify(title, body)}`: the expressions for the title and body of a desktop notification. Also not made from an argument.
+    Notify(String, String),
+    /// `{image(path, width)}`, with the `image` feature: the expressions for the path of an image, and its width in cells. Not made from an argument.
+    Image(String, String),
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// The formatters in `comat::format`, which can be used without colors.
+pub const FORMATTERS: &[&str] = &["human_bytes", "human_duration"];
+
+struct Out<'a> {
+    s: String,
+    theme: &'a Theme,
+    base: String,
+    /// the argument each block made, and what it was made from
+    formatted: Vec<(String, Made)>,
+    /// the tokens used
+    tokens: Vec<String>,
+    /// the escapes in effect since the last reset (besides the base), to go back to after a block
+    active: String,
+    close: Close,
+    /// whether to put in the [`marker`]s
+    markers: bool,
+}
+
+impl Out<'_> {
+    fn parse(&mut self, input: &str) -> std::result::Result<(), Error> {
+        let mut chars = input.chars();
+        while let Some(ch) = chars.next() {
+            let at = input.len() - chars.as_str().len() - ch.len_utf8();
+            match ch {
+                '{' => self.braced(&mut chars).map_err(|message| Error {
+                    message,
+                    at: Some(at..input.len() - chars.as_str().len()),
+                })?,
+                '}' => match chars.next() {
+                    Some('}') => self.s.push('}'),
+                    _ => {
+                        return Err(Error {
+                            message: "unexpected text".into(),
+                            at: Some(at..at + 1),
+                        })
+                    }
+                },
+                c => self.s.push(c),
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle what comes after a `{`.
+    fn braced(&mut self, chars: &mut std::str::Chars) -> std::result::Result<(), String> {
+        let mut block = String::new();
+        match chars.next() {
+            Some('{') => {
+                self.s.push('{');
+                return Ok(());
+            }
+            Some('}') => {
+                self.s.push_str("{}");
+                return Ok(());
+            }
+            Some(ch) => block.push(ch),
+            None => return Err("unexpected eof".into()),
+        }
+        loop {
+            match chars.next() {
+                Some('}') => break,
+                Some(ch) => block.push(ch),
+                None => return Err("unexpected eof".into()),
+            }
+        }
+        if let Some(token) = block.strip_prefix('!') {
+            self.formatted
+                .push((String::new(), Made::Literal(format!("{{{token}}}"))));
+            let _ = write!(self.s, "{{__comat_{}}}", self.formatted.len() - 1);
+        } else if let Some(stops) = call_n(&block, "gradient") {
+            let stops = stops
+                .into_iter()
+                .map(any_rgb)
+                .collect::<Option<Vec<_>>>()
+                .filter(|stops| stops.len() > 1)
+                .ok_or("gradient takes two or more colors")?;
+            let text = literal(chars, "gradient")?;
+            self.tokens.push(block);
+            self.paint(&text, |t| color::gradient(&stops, t));
+        } else if block == "rainbow" {
+            let text = literal(chars, "rainbow")?;
+            self.tokens.push(block);
+            // stop at violet, rather than going back round to red
+            self.paint(&text, |t| {
+                color::hsl(t * 300.0, 100.0, 50.0).unwrap_or_default()
+            });
+        } else {
+            self.block(&block)?;
+        }
+        Ok(())
+    }
+
+    /// Handle the inside of a `{}`.
+    fn block(&mut self, block: &str) -> std::result::Result<(), String> {
+        if let Some(sequence) = block
+            .strip_prefix("esc(")
+            .and_then(|sequence| sequence.strip_suffix(')'))
+        {
+            self.tokens.push(block.to_string());
+            self.s.push('\x1b');
+            self.s.push_str(&unescape(sequence)?);
+            return Ok(());
+        }
+        if self.expressions(block)? {
+            return Ok(());
+        }
+        if let Some(ansi) = self.theme.get(block) {
+            self.tokens.push(block.to_string());
+            self.push(&ansi);
+            self.mark(block);
+            return Ok(());
+        }
+        if let Some(block) = self.formatter(block)? {
+            return self.block(&block);
+        }
+        // `{arg:colors}` or `{arg:spec:colors}`
+        if let Some((mut arg, colors)) = block.rsplit_once(':') {
+            let colors = split(colors);
+            if colors.iter().all(|&color| {
+                call_n(color, "scale").is_some()
+                    || style(color).is_some()
+                    || self.theme.get(color).is_some()
+            }) {
+                let mut ansi = vec![];
+                let mut name = None;
+                // whether the made argument resets before showing the value, like a scale of colors does
+                let mut resets = false;
+                let mut marked = vec![];
+                for color in colors {
+                    let made = match (call_n(color, "scale"), style(color)) {
+                        (Some(steps), _) => self.scale(&steps)?,
+                        (_, Some(style)) => Made::Style(style.to_string()),
+                        _ => {
+                            self.tokens.push(color.trim().to_string());
+                            ansi.extend(self.theme.get(color));
+                            marked.push(color.trim());
+                            continue;
+                        }
+                    };
+                    if name.is_some() {
+                        return Err("a block can only have one scale or [style]".into());
+                    }
+                    resets = matches!(&made, Made::Scale(steps, past)
+                        if steps.iter().map(|(_, ansi)| ansi).chain([past]).all(|ansi| ansi.starts_with("\x1b[0;")));
+                    name = Some(self.make(arg, made)?);
+                }
+                if let Some(name) = &name {
+                    arg = name;
+                }
+                let outer = self.active.clone();
+                if ansi.first().map(String::as_str) != Some(RESET) && !(resets && ansi.is_empty()) {
+                    self.reset();
+                }
+                for ansi in ansi {
+                    self.push(&ansi);
+                }
+                for color in marked {
+                    self.mark(color);
+                }
+                self.s.push('{');
+                self.s.push_str(arg);
+                self.s.push('}');
+                match self.close {
+                    Close::Reset => {
+                        self.reset();
+                        self.push(&outer);
+                    }
+                    Close::Default => self.push("\x1b[39;49m"),
+                    Close::None => {}
+                }
+                return Ok(());
+            }
+        }
+        // not ours
+        self.s.push('{');
+        self.s.push_str(block);
+        self.s.push('}');
+        Ok(())
+    }
+
+    /// `{src(path, line)}`, `{notify(title, body)}` and `{image(path, width)}`, which are made at runtime from two expressions.
+    /// Whether `block` was one of them.
+    fn expressions(&mut self, block: &str) -> std::result::Result<bool, String> {
+        for (name, made, usage) in [
+            (
+                "src",
+                Made::Source as fn(_, _) -> _,
+                "src takes a path and a line, like {src(file, 12)}",
+            ),
+            (
+                "notify",
+                Made::Notify,
+                "notify takes a title and a body, like {notify(title, body)}",
+            ),
+            (
+                "image",
+                Made::Image,
+                "image takes a path and a width, like {image(path, 20)}",
+            ),
+        ] {
+            if name == "image" && !cfg!(feature = "image") {
+                continue;
+            }
+            if let Some(args) = call_n(block, name) {
+                let [a, b] = args[..] else {
+                    return Err(usage.into());
+                };
+                self.tokens.push(block.to_string());
+                self.formatted
+                    .push((String::new(), made(a.to_string(), b.to_string())));
+                let _ = write!(self.s, "{{__comat_{}}}", self.formatted.len() - 1);
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// `{arg:colors:formatter}` (or `{arg:spec:colors:formatter}`) formats `arg` with the function `formatter`.
+    /// This replaces the argument with a new one, giving the rest of the block to handle as usual.
+    ///
+    /// The builtin formatters also work without colors, like `{arg:human_bytes}`.
+    /// Other functions need the colors (which can be empty, like `{arg::formatter}`), so a misspelled color is not taken for a function.
+    fn formatter(&mut self, block: &str) -> std::result::Result<Option<String>, String> {
+        let Some((rest, formatter)) = block.rsplit_once(':') else {
+            return Ok(None);
+        };
+        if !ident(formatter)
+            || self.theme.get(formatter).is_some()
+            || !(FORMATTERS.contains(&formatter) || rest.contains(':'))
+        {
+            return Ok(None);
+        }
+        self.make(rest, Made::Formatter(formatter.to_string()))
+            .map(Some)
+    }
+
+    /// `scale(50=green, 200=yellow, _=red)` colors a value by the first threshold it is below, and `_` past all of them.
+    /// The thresholds are Rust expressions, compared to the value with `<`, so they can be constants in scope.
+    fn scale(&mut self, steps: &[&str]) -> std::result::Result<Made, String> {
+        let mut thresholds = vec![];
+        let mut past = String::new();
+        for step in steps {
+            let (threshold, color) = step
+                .split_once('=')
+                .ok_or_else(|| format!("expected `threshold=color` in scale, not {step:?}"))?;
+            let (threshold, color) = (threshold.trim(), color.trim());
+            let ansi = self
+                .theme
+                .get(color)
+                .ok_or_else(|| format!("unknown color {color:?} in scale"))?;
+            self.tokens.push(color.to_string());
+            if threshold == "_" {
+                past = ansi;
+            } else {
+                thresholds.push((threshold.to_string(), ansi));
+            }
+        }
+        Ok(Made::Scale(thresholds, past))
+    }
+
+    /// Replace the argument of `arg` (or `arg:spec`) with a new one, made from it.
+    fn make(&mut self, arg: &str, made: Made) -> std::result::Result<String, String> {
+        let (name, spec) = arg
+            .split_once(':')
+            .map_or((arg, ""), |(name, _)| (name, &arg[name.len()..]));
+        // a positional argument is `""` or its index
+        if !(name.is_empty() || name.bytes().all(|b| b.is_ascii_digit()) || ident(name)) {
+            return Err(match made {
+                Made::Formatter(formatter) => {
+                    format!("{formatter} needs an argument, like {{bytes:cyan:{formatter}}}")
+                }
+                Made::Scale(..) => {
+                    "scale needs an argument, like {ms:scale(50=green,_=red)}".into()
+                }
+                Made::Style(..) => "[style] needs an argument, like {msg:[style]}".into(),
+                Made::Literal(..) | Made::Source(..) | Made::Notify(..) | Made::Image(..) => {
+                    unreachable!("these are not made from an argument")
+                }
+            });
+        }
+        self.formatted.push((name.to_string(), made));
+        Ok(format!("__comat_{}{spec}", self.formatted.len() - 1))
+    }
+
+    /// Color each character of `text` with `color(position)`, position going from 0 to 1.
+    fn paint(&mut self, text: &str, color: impl Fn(f64) -> [u8; 3]) {
+        let n = text.chars().count().saturating_sub(1).max(1);
+        let mut last = String::new();
+        for (i, ch) in text.chars().enumerate() {
+            // whitespace has no color to show
+            if !ch.is_whitespace() {
+                #[allow(clippy::cast_precision_loss)]
+                let ansi = color::downgrade(
+                    &color::rgb(color(i as f64 / n as f64), false),
+                    self.theme.depth,
+                );
+                if ansi != last {
+                    self.s.push_str(&ansi);
+                    last = ansi;
+                }
+            }
+            self.s.push(ch);
+        }
+        self.reset();
+    }
+
+    /// Push an escape, restoring the base style when it resets.
+    fn push(&mut self, ansi: &str) {
+        self.s.push_str(ansi);
+        if ansi == RESET {
+            self.s.push_str(&self.base);
+            self.active.clear();
+        } else if ansi.starts_with("\x1b[0;") {
+            self.active = ansi.to_string();
+        } else {
+            self.active.push_str(ansi);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.push(RESET);
+    }
+
+    /// Put in the [`marker`] of `token`, if there are markers.
+    fn mark(&mut self, token: &str) {
+        if let Some(marker) = marker(token).filter(|_| self.markers) {
+            self.s.push_str(marker);
+        }
+    }
+}
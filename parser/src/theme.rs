@@ -0,0 +1,319 @@
+use std::{
+    cell::RefCell,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{
+    builtin,
+    color::{self, Depth},
+    config::Config,
+    split, Close,
+};
+
+/// Maps tokens onto escapes. Tokens not in the theme fall through to the base palette.
+#[derive(Clone)]
+pub struct Theme {
+    /// `(token, spec)` pairs. later entries win.
+    ///
+    /// `spec` is either raw SGR parameters (`1;38;5;208`) or a comma separated list of tokens (`red,bold`).
+    entries: Vec<(String, String)>,
+    /// Files the theme was read from, that should trigger a rebuild when changed.
+    pub files: Vec<PathBuf>,
+    /// How `{arg:colors}` blocks end, from the `comat.toml`'s `close`.
+    pub close: Close,
+    /// Other themes to make versions of every string with, to switch to at runtime, from the `comat.toml`'s `variants`.
+    /// See [`load_variant`](Self::load_variant).
+    pub variants: Vec<String>,
+    /// How many colors to use, [`Depth::FEATURE`] unless changed.
+    pub depth: Depth,
+}
+
+/// The semantic tokens, and what they default to.
+const SEMANTIC: &[(&str, &str)] = &[
+    ("error", "bold_red"),
+    ("warn", "bold_yellow"),
+    ("info", "bold_blue"),
+    ("success", "bold_green"),
+    ("hint", "cyan"),
+    ("timestamp", "dim"),
+];
+
+/// `(color, rgb)` pairs, replacing the base palette.
+type Palette = &'static [(&'static str, [u8; 3])];
+
+/// Truecolor palettes, selectable with `COMAT_THEME=<name>`.
+const PRESETS: &[(&str, Palette)] = &[(
+    "solarized",
+    &[
+        ("black", [7, 54, 66]),
+        ("red", [220, 50, 47]),
+        ("green", [133, 153, 0]),
+        ("yellow", [181, 137, 0]),
+        ("blue", [38, 139, 210]),
+        ("magenta", [211, 54, 130]),
+        ("cyan", [42, 161, 152]),
+        ("white", [238, 232, 213]),
+    ],
+)];
+
+/// Semantic tokens for red-green color blindness (deuteranopia and protanopia), from the [Okabe-Ito](https://jfly.uni-koeln.de/color/) palette:
+/// `error` is vermillion and `success` blue, rather than red and green.
+const RED_GREEN: &[(&str, &str)] = &[
+    ("error", "1;38;2;213;94;0"),
+    ("warn", "1;38;2;240;228;66"),
+    ("info", "1;38;2;86;180;233"),
+    ("success", "1;38;2;0;114;178"),
+    ("hint", "38;2;204;121;167"),
+];
+
+/// Semantic tokens for blue-yellow color blindness (tritanopia), also from Okabe-Ito: nothing blue, and `warn` orange.
+const BLUE_YELLOW: &[(&str, &str)] = &[
+    ("error", "1;38;2;213;94;0"),
+    ("warn", "1;38;2;230;159;0"),
+    ("info", "1"),
+    ("success", "1;38;2;0;158;115"),
+    ("hint", "38;2;204;121;167"),
+];
+
+/// The basic colors, in the order of their SGR codes.
+const COLORS: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// Semantic tokens that can be told apart with color blindness, selectable like the [`PRESETS`].
+const ACCESSIBLE: &[(&str, &[(&str, &str)])] = &[
+    ("deuteranopia", RED_GREEN),
+    ("protanopia", RED_GREEN),
+    ("tritanopia", BLUE_YELLOW),
+];
+
+impl Theme {
+    /// The theme selected by `COMAT_THEME` or the `comat.toml`, with the `comat.toml`s tokens on top.
+    /// ```
+    /// # use comat_parser::Theme;
+    /// std::env::set_var("COMAT_THEME", "deuteranopia");
+    /// let theme = Theme::load().unwrap();
+    /// # #[cfg(not(any(feature = "ansi256", feature = "ansi16")))] // which downgrade it
+    /// assert_eq!(theme.get("success").unwrap(), "\x1b[1;38;2;0;114;178m");
+    /// std::env::set_var("COMAT_THEME", "high_contrast");
+    /// let theme = Theme::load().unwrap();
+    /// assert_eq!(theme.get("error").unwrap(), "\x1b[1;91m");
+    /// assert_eq!(theme.get("on_yellow").unwrap(), "\x1b[0;1;30;103m");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// when the `comat.toml` or a theme file is unreadable or invalid.
+    pub fn load() -> Result<Self, String> {
+        Self::load_as(None)
+    }
+
+    /// [`load`](Self::load), with the theme `name` in place of the one `COMAT_THEME` or the `comat.toml` selects.
+    ///
+    /// # Errors
+    ///
+    /// when the `comat.toml` or a theme file is unreadable or invalid.
+    pub fn load_variant(name: &str) -> Result<Self, String> {
+        Self::load_as(Some(name))
+    }
+
+    fn load_as(variant: Option<&str>) -> Result<Self, String> {
+        let mut theme = Self::cached(variant)?;
+        for (token, spec) in crate::alias::aliases() {
+            theme.set(token, spec);
+        }
+        Ok(theme)
+    }
+
+    /// [`read`](Self::read), kept until the files it was read from change, as every macro call loads the theme.
+    fn cached(variant: Option<&str>) -> Result<Self, String> {
+        /// What the theme was read for: the crate, `COMAT_THEME`, the variant and the `comat.toml`.
+        type Key = (
+            Option<OsString>,
+            Option<OsString>,
+            Option<String>,
+            Option<PathBuf>,
+        );
+        /// When each file the theme was read from was last changed.
+        type Stamps = Vec<Option<SystemTime>>;
+        thread_local! {
+            static CACHE: RefCell<Vec<(Key, Stamps, Theme)>> = const { RefCell::new(vec![]) };
+        }
+        let stamps = |files: &[PathBuf]| {
+            files
+                .iter()
+                .map(|file| std::fs::metadata(file).and_then(|m| m.modified()).ok())
+                .collect::<Stamps>()
+        };
+        let key = (
+            std::env::var_os("CARGO_MANIFEST_DIR"),
+            std::env::var_os("COMAT_THEME"),
+            variant.map(String::from),
+            Config::find(),
+        );
+        let cached = CACHE.with_borrow(|cache| {
+            cache
+                .iter()
+                .find(|(k, old, theme)| *k == key && *old == stamps(&theme.files))
+                .map(|(.., theme)| theme.clone())
+        });
+        if let Some(theme) = cached {
+            return Ok(theme);
+        }
+        let theme = Self::read(variant)?;
+        let entry = (key, stamps(&theme.files), theme.clone());
+        CACHE.with_borrow_mut(|cache| {
+            cache.retain(|(k, ..)| *k != entry.0);
+            cache.push(entry);
+        });
+        Ok(theme)
+    }
+
+    /// Read the theme from the `comat.toml` and the theme files.
+    fn read(variant: Option<&str>) -> Result<Self, String> {
+        let mut theme = Self::default();
+        let config = Config::load()?;
+        match (variant, std::env::var("COMAT_THEME")) {
+            (Some(name), _) => theme.apply(name, |path| config.relative(path))?,
+            (None, Ok(name)) if !name.is_empty() => {
+                let dir = std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+                theme.apply(&name, |path| Path::new(&dir).join(path))?;
+            }
+            _ => {
+                if let Some(name) = &config.theme {
+                    theme.apply(name, |path| config.relative(path))?;
+                }
+            }
+        }
+        for (token, spec) in config.tokens {
+            theme.set(token, spec);
+        }
+        theme.close = config.close;
+        theme.variants = config.variants;
+        theme.files.extend(config.path);
+        Ok(theme)
+    }
+
+    /// Apply a preset by name, or a theme file by path.
+    fn apply(&mut self, name: &str, relative: impl Fn(&str) -> PathBuf) -> Result<(), String> {
+        if name == "default" {
+            return Ok(());
+        }
+        if let Some((_, palette)) = PRESETS.iter().find(|&&(n, _)| n == name) {
+            for &(color, [r, g, b]) in *palette {
+                self.set(color, format!("0;38;2;{r};{g};{b}"));
+                self.set(format!("bold_{color}"), format!("1;38;2;{r};{g};{b}"));
+                self.set(format!("on_{color}"), format!("0;48;2;{r};{g};{b}"));
+                self.set(format!("on_{color}_bold"), format!("1;48;2;{r};{g};{b}"));
+            }
+            return Ok(());
+        }
+        if name == "high_contrast" {
+            self.high_contrast();
+            return Ok(());
+        }
+        if let Some((_, tokens)) = ACCESSIBLE.iter().find(|&&(n, _)| n == name) {
+            for &(token, spec) in *tokens {
+                self.set(token, spec);
+            }
+            return Ok(());
+        }
+        if Path::new(name).extension().is_none() && !name.contains(['/', '\\']) {
+            return Err(format!(
+                "unknown theme {name:?}. expected one of {:?} or a path to a theme file",
+                ["default", "high_contrast"]
+                    .into_iter()
+                    .chain(PRESETS.iter().map(|&(n, _)| n))
+                    .chain(ACCESSIBLE.iter().map(|&(n, _)| n))
+                    .collect::<Vec<_>>()
+            ));
+        }
+        let path = relative(name);
+        for (key, value) in crate::config::read(&path)? {
+            let value = value
+                .string(&key)
+                .map_err(|e| format!("{}: {e}", path.display()))?;
+            self.set(key, value);
+        }
+        self.files.push(path);
+        Ok(())
+    }
+
+    /// Only bold and bright colors, for low vision or washed out screens:
+    /// colors are bright and bold, `dim` is bold instead, and backgrounds get black or white text, whichever is clearer.
+    fn high_contrast(&mut self) {
+        for (i, color) in COLORS.into_iter().enumerate() {
+            self.set(color, format!("0;1;9{i}"));
+            self.set(format!("bold_{color}"), format!("1;9{i}"));
+            // black text on the light backgrounds, white on the dark ones
+            let text = if matches!(color, "green" | "yellow" | "cyan" | "white") {
+                30
+            } else {
+                97
+            };
+            self.set(format!("on_{color}"), format!("0;1;{text};10{i}"));
+            self.set(format!("on_{color}_bold"), format!("1;{text};10{i}"));
+        }
+        self.set("dim", "1");
+    }
+
+    /// Define `token` as `spec`, over any earlier definition.
+    pub fn set(&mut self, token: impl Into<String>, spec: impl Into<String>) {
+        self.entries.push((token.into(), spec.into()));
+    }
+
+    /// Resolve a token to its escape sequence, downgraded to the theme's [`depth`](Self::depth) (except for `sgr(..)`).
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<String> {
+        // passed through as is, for what comat does not know about
+        if let Some(params) = name
+            .strip_prefix("sgr(")
+            .and_then(|params| params.strip_suffix(')'))
+            .filter(|params| {
+                params
+                    .bytes()
+                    .all(|b| b.is_ascii_digit() || b == b';' || b == b':')
+            })
+        {
+            return Some(format!("\x1b[{params}m"));
+        }
+        self.resolve(name, &mut vec![])
+            .map(|ansi| color::downgrade(&ansi, self.depth))
+    }
+
+    fn resolve<'a>(&'a self, name: &'a str, seen: &mut Vec<&'a str>) -> Option<String> {
+        let spec = match self.entries.iter().rfind(|(token, _)| token == name) {
+            // a token refering to itself (`red = "red,bold"`) means the base palette one
+            Some((_, spec)) if !seen.contains(&name) => spec,
+            _ => return builtin(name),
+        };
+        if spec.bytes().all(|b| b.is_ascii_digit() || b == b';') {
+            return Some(format!("\x1b[{spec}m"));
+        }
+        seen.push(name);
+        let mut out = String::new();
+        for token in split(spec) {
+            out.push_str(&self.resolve(token.trim(), seen)?);
+        }
+        seen.pop();
+        Some(out)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let mut theme = Self {
+            entries: vec![],
+            files: vec![],
+            close: Close::default(),
+            variants: vec![],
+            depth: Depth::FEATURE,
+        };
+        for &(token, color) in SEMANTIC {
+            theme.set(token, color);
+        }
+        theme
+    }
+}
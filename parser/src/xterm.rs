@@ -0,0 +1,271 @@
+/// The xterm names of the 256 colors. Names that appear more than once refer to their first index.
+const NAMES: [&str; 256] = [
+    "black",
+    "maroon",
+    "green",
+    "olive",
+    "navy",
+    "purple",
+    "teal",
+    "silver",
+    "grey",
+    "red",
+    "lime",
+    "yellow",
+    "blue",
+    "fuchsia",
+    "aqua",
+    "white",
+    "grey0",
+    "navyblue",
+    "darkblue",
+    "blue3",
+    "blue3",
+    "blue1",
+    "darkgreen",
+    "deepskyblue4",
+    "deepskyblue4",
+    "deepskyblue4",
+    "dodgerblue3",
+    "dodgerblue2",
+    "green4",
+    "springgreen4",
+    "turquoise4",
+    "deepskyblue3",
+    "deepskyblue3",
+    "dodgerblue1",
+    "green3",
+    "springgreen3",
+    "darkcyan",
+    "lightseagreen",
+    "deepskyblue2",
+    "deepskyblue1",
+    "green3",
+    "springgreen3",
+    "springgreen2",
+    "cyan3",
+    "darkturquoise",
+    "turquoise2",
+    "green1",
+    "springgreen2",
+    "springgreen1",
+    "mediumspringgreen",
+    "cyan2",
+    "cyan1",
+    "darkred",
+    "deeppink4",
+    "purple4",
+    "purple4",
+    "purple3",
+    "blueviolet",
+    "orange4",
+    "grey37",
+    "mediumpurple4",
+    "slateblue3",
+    "slateblue3",
+    "royalblue1",
+    "chartreuse4",
+    "darkseagreen4",
+    "paleturquoise4",
+    "steelblue",
+    "steelblue3",
+    "cornflowerblue",
+    "chartreuse3",
+    "darkseagreen4",
+    "cadetblue",
+    "cadetblue",
+    "skyblue3",
+    "steelblue1",
+    "chartreuse3",
+    "palegreen3",
+    "seagreen3",
+    "aquamarine3",
+    "mediumturquoise",
+    "steelblue1",
+    "chartreuse2",
+    "seagreen2",
+    "seagreen1",
+    "seagreen1",
+    "aquamarine1",
+    "darkslategrey2",
+    "darkred",
+    "deeppink4",
+    "darkmagenta",
+    "darkmagenta",
+    "darkviolet",
+    "purple",
+    "orange4",
+    "lightpink4",
+    "plum4",
+    "mediumpurple3",
+    "mediumpurple3",
+    "slateblue1",
+    "yellow4",
+    "wheat4",
+    "grey53",
+    "lightslategrey",
+    "mediumpurple",
+    "lightslateblue",
+    "yellow4",
+    "darkolivegreen3",
+    "darkseagreen",
+    "lightskyblue3",
+    "lightskyblue3",
+    "skyblue2",
+    "chartreuse2",
+    "darkolivegreen3",
+    "palegreen3",
+    "darkseagreen3",
+    "darkslategrey3",
+    "skyblue1",
+    "chartreuse1",
+    "lightgreen",
+    "lightgreen",
+    "palegreen1",
+    "aquamarine1",
+    "darkslategrey1",
+    "red3",
+    "deeppink4",
+    "mediumvioletred",
+    "magenta3",
+    "darkviolet",
+    "purple",
+    "darkorange3",
+    "indianred",
+    "hotpink3",
+    "mediumorchid3",
+    "mediumorchid",
+    "mediumpurple2",
+    "darkgoldenrod",
+    "lightsalmon3",
+    "rosybrown",
+    "grey63",
+    "mediumpurple2",
+    "mediumpurple1",
+    "gold3",
+    "darkkhaki",
+    "navajowhite3",
+    "grey69",
+    "lightsteelblue3",
+    "lightsteelblue",
+    "yellow3",
+    "darkolivegreen3",
+    "darkseagreen3",
+    "darkseagreen2",
+    "lightcyan3",
+    "lightskyblue1",
+    "greenyellow",
+    "darkolivegreen2",
+    "palegreen1",
+    "darkseagreen2",
+    "darkseagreen1",
+    "paleturquoise1",
+    "red3",
+    "deeppink3",
+    "deeppink3",
+    "magenta3",
+    "magenta3",
+    "magenta2",
+    "darkorange3",
+    "indianred",
+    "hotpink3",
+    "hotpink2",
+    "orchid",
+    "mediumorchid1",
+    "orange3",
+    "lightsalmon3",
+    "lightpink3",
+    "pink3",
+    "plum3",
+    "violet",
+    "gold3",
+    "lightgoldenrod3",
+    "tan",
+    "mistyrose3",
+    "thistle3",
+    "plum2",
+    "yellow3",
+    "khaki3",
+    "lightgoldenrod2",
+    "lightyellow3",
+    "grey84",
+    "lightsteelblue1",
+    "yellow2",
+    "darkolivegreen1",
+    "darkolivegreen1",
+    "darkseagreen1",
+    "honeydew2",
+    "lightcyan1",
+    "red1",
+    "deeppink2",
+    "deeppink1",
+    "deeppink1",
+    "magenta2",
+    "magenta1",
+    "orangered1",
+    "indianred1",
+    "indianred1",
+    "hotpink",
+    "hotpink",
+    "mediumorchid1",
+    "darkorange",
+    "salmon1",
+    "lightcoral",
+    "palevioletred1",
+    "orchid2",
+    "orchid1",
+    "orange1",
+    "sandybrown",
+    "lightsalmon1",
+    "lightpink1",
+    "pink1",
+    "plum1",
+    "gold1",
+    "lightgoldenrod2",
+    "lightgoldenrod2",
+    "navajowhite1",
+    "mistyrose1",
+    "thistle1",
+    "yellow1",
+    "lightgoldenrod1",
+    "khaki1",
+    "wheat1",
+    "cornsilk1",
+    "grey100",
+    "grey3",
+    "grey7",
+    "grey11",
+    "grey15",
+    "grey19",
+    "grey23",
+    "grey27",
+    "grey30",
+    "grey35",
+    "grey39",
+    "grey42",
+    "grey46",
+    "grey50",
+    "grey54",
+    "grey58",
+    "grey62",
+    "grey66",
+    "grey70",
+    "grey74",
+    "grey78",
+    "grey82",
+    "grey85",
+    "grey89",
+    "grey93",
+];
+
+/// Find a color by name. `gray` is accepted for `grey`, and a name without a number means the `1` variant (`orange` is `orange1`).
+pub fn index(name: &str) -> Option<u8> {
+    let name = name.replace("gray", "grey");
+    let find = |name: &str| NAMES.iter().position(|&n| n == name);
+    let i = find(&name).or_else(|| {
+        name.ends_with(|c: char| c.is_ascii_alphabetic())
+            .then(|| find(&format!("{name}1")))
+            .flatten()
+    })?;
+    u8::try_from(i).ok()
+}
@@ -0,0 +1,39 @@
+use crate::wrap::paint;
+
+/// The blocks for the last, partly filled, cell of a [`bar`], by eighths.
+const PARTS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// A progress bar `width` cells wide, filled to `fraction` (from 0 to 1) to the eighth of a cell,
+/// with the filled part in the style `filled` and the rest in `empty` (some escapes, like from [`comat!`](crate::comat)).
+/// ```
+/// # use comat::*;
+/// assert_eq!(bar(0.5, 4, "", ""), "██░░");
+/// assert_eq!(bar(0.3, 4, "", ""), "█▎░░");
+/// assert_eq!(bar(1.0, 2, comat!("{green}"), comat!("{dim}")), "\x1b[0;34;32m██\x1b[0m");
+/// ```
+#[must_use]
+pub fn bar(fraction: f64, width: usize, filled: &str, empty: &str) -> String {
+    let fraction = if fraction.is_nan() {
+        0.0
+    } else {
+        fraction.clamp(0.0, 1.0)
+    };
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let eighths = (fraction * width as f64 * 8.0).round() as usize;
+    let (full, part) = (eighths / 8, eighths % 8);
+    let mut out = String::with_capacity(width * 3 + 16);
+    let mut bar: String = std::iter::repeat_n('█', full).collect();
+    bar.extend(part.checked_sub(1).map(|i| PARTS[i]));
+    if !bar.is_empty() {
+        paint(&mut out, filled, &bar);
+    }
+    let rest = width - full - usize::from(part > 0);
+    if rest > 0 {
+        paint(&mut out, empty, &"░".repeat(rest));
+    }
+    out
+}
@@ -0,0 +1,68 @@
+use crate::{
+    visible_width,
+    wrap::{paint, track},
+};
+
+/// The lines [`boxed`] draws with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Border {
+    /// `┌─┐`
+    #[default]
+    Single,
+    /// `╔═╗`
+    Double,
+    /// `╭─╮`
+    Rounded,
+    /// `┏━┓`
+    Heavy,
+}
+
+impl Border {
+    /// The corners (clockwise from the top left), the horizontal line and the vertical line.
+    const fn chars(self) -> [char; 6] {
+        match self {
+            Self::Single => ['┌', '┐', '┘', '└', '─', '│'],
+            Self::Double => ['╔', '╗', '╝', '╚', '═', '║'],
+            Self::Rounded => ['╭', '╮', '╯', '╰', '─', '│'],
+            Self::Heavy => ['┏', '┓', '┛', '┗', '━', '┃'],
+        }
+    }
+}
+
+/// Draw a box around `s`, in the style `border` (some escapes, like from [`comat!`](crate::comat)).
+///
+/// Lines are padded to the [`visible_width`] of the widest one, and they keep their colors.
+/// ```
+/// # use comat::*;
+/// let b = boxed("hello\nworld!", "", Border::Rounded);
+/// assert_eq!(b, "╭────────╮\n│ hello  │\n│ world! │\n╰────────╯");
+/// ```
+#[must_use]
+pub fn boxed(s: &str, border: &str, shape: Border) -> String {
+    let [tl, tr, br, bl, h, v] = shape.chars();
+    let width = s.lines().map(visible_width).max().unwrap_or(0);
+    let mut out = String::with_capacity(s.len() + (width + 8) * 3);
+    let line = |out: &mut String, l, r| {
+        let mut edge = String::from(l);
+        edge.extend(std::iter::repeat_n(h, width + 2));
+        edge.push(r);
+        paint(out, border, &edge);
+    };
+    line(&mut out, tl, tr);
+    let mut active = String::new();
+    for text in s.lines() {
+        out.push('\n');
+        paint(&mut out, border, &format!("{v} "));
+        out.push_str(&active);
+        out.push_str(text);
+        track(&mut active, text);
+        if !active.is_empty() {
+            out.push_str("\x1b[0m");
+        }
+        out.extend(std::iter::repeat_n(' ', width - visible_width(text)));
+        paint(&mut out, border, &format!(" {v}"));
+    }
+    out.push('\n');
+    line(&mut out, bl, br);
+    out
+}
@@ -0,0 +1,204 @@
+use std::{fmt, io::IsTerminal, sync::OnceLock};
+
+/// What the terminal can show, from [`capabilities`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Capabilities {
+    /// How many colors it has.
+    pub colors: Colors,
+    /// Whether it makes OSC 8 links clickable.
+    pub hyperlinks: bool,
+    /// Whether it shows titles set with OSC 0.
+    pub titles: bool,
+    /// How it shows desktop notifications, if it does.
+    pub notifications: Option<Notifications>,
+    /// How it shows images, if it does.
+    pub images: Option<Images>,
+}
+
+/// The escapes for images.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Images {
+    /// OSC 1337, from iTerm2, which shows any format macOS can.
+    Iterm,
+    /// kitty's graphics protocol, which shows PNGs.
+    Kitty,
+}
+
+/// The escapes for desktop notifications.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Notifications {
+    /// OSC 9, from iTerm2, which has only a body.
+    Osc9,
+    /// OSC 777, from rxvt-unicode, which has a title and a body.
+    Osc777,
+}
+
+/// How many colors a terminal has.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Colors {
+    /// None, as when the output is not a terminal, `NO_COLOR` is set, or `TERM` is `dumb` (or not set, outside of windows).
+    None,
+    /// The 16 basic colors.
+    Ansi16,
+    /// The xterm 256 color palette.
+    Ansi256,
+    /// 24 bit color.
+    Truecolor,
+}
+
+/// The terminals known to make links clickable, by their `TERM_PROGRAM`.
+const HYPERLINKS: &[&str] = &["iTerm.app", "WezTerm", "vscode", "ghostty", "Hyper"];
+
+/// The terminals known to show notifications, by their `TERM_PROGRAM`, and how.
+const NOTIFICATIONS: &[(&str, Notifications)] = &[
+    ("iTerm.app", Notifications::Osc9),
+    ("WezTerm", Notifications::Osc777),
+    ("ghostty", Notifications::Osc777),
+];
+
+/// The terminals known to show images, by their `TERM_PROGRAM`, and how.
+const IMAGES: &[(&str, Images)] = &[
+    ("iTerm.app", Images::Iterm),
+    ("WezTerm", Images::Iterm),
+    ("ghostty", Images::Kitty),
+];
+
+/// CI services whose logs show colors, though the output is not a terminal, by the variable they set, and the colors they show.
+const CI: &[(&str, Colors)] = &[
+    ("GITHUB_ACTIONS", Colors::Truecolor),
+    ("GITLAB_CI", Colors::Ansi256),
+    ("BUILDKITE", Colors::Ansi256),
+];
+
+/// The variable that turns the [`CI`] detection off, with `0`.
+const KEEP_CI: &str = "COMAT_CI";
+
+/// Where the printing macros print: stdout and stderr can each go to a terminal or not, like when only stdout is piped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stream {
+    /// Where [`cprintln!`](crate::cprintln) (and most of the rest) print.
+    Stdout,
+    /// Where [`ceprint_batch!`](crate::ceprint_batch), [`cwarn_msg!`](crate::cwarn_msg) and [`cerr_msg!`](crate::cerr_msg) print.
+    Stderr,
+}
+
+/// What the terminal stdout goes to can show. It is found once, from the environment, and kept,
+/// so the printing macros can ask on every call. [`stream_capabilities`] does the same for stderr.
+///
+/// When [`set_auto`](crate::set_auto) is on, the printing macros leave out the escapes when there are no [colors](Colors::None).
+/// On GitHub Actions, GitLab CI and Buildkite, which show colors in their logs, there are colors even though the output is not a terminal;
+/// set `COMAT_CI=0` to turn that off.
+/// ```
+/// # use comat::*;
+/// if capabilities().hyperlinks {
+///     cprintln!("see the \x1b]8;;https://docs.rs/comat\x07{bold}docs{reset}\x1b]8;;\x07");
+/// }
+/// ```
+#[must_use]
+pub fn capabilities() -> Capabilities {
+    stream_capabilities(Stream::Stdout)
+}
+
+/// What the terminal `stream` goes to can show, found once for each stream, like [`capabilities`].
+/// ```
+/// # use comat::*;
+/// if stream_capabilities(Stream::Stderr).colors == Colors::None {
+///     eprintln!("error: not found");
+/// }
+/// ```
+#[must_use]
+pub fn stream_capabilities(stream: Stream) -> Capabilities {
+    static STDOUT: OnceLock<Capabilities> = OnceLock::new();
+    static STDERR: OnceLock<Capabilities> = OnceLock::new();
+    let var = |name: &str| std::env::var(name).ok();
+    match stream {
+        Stream::Stdout => {
+            *STDOUT.get_or_init(|| Capabilities::detect(var, std::io::stdout().is_terminal()))
+        }
+        Stream::Stderr => {
+            *STDERR.get_or_init(|| Capabilities::detect(var, std::io::stderr().is_terminal()))
+        }
+    }
+}
+
+impl Capabilities {
+    /// Work out the capabilities from environment variables (got with `var`), and whether the output is a terminal.
+    fn detect(var: impl Fn(&str) -> Option<String>, terminal: bool) -> Self {
+        let term = var("TERM");
+        // like IDE run panes and emacs shells, which do not show escapes. windows consoles do not set it
+        let dumb = term
+            .as_deref()
+            .map_or(!cfg!(windows), |term| term == "dumb");
+        let term = term.unwrap_or_default();
+        let ci = CI
+            .iter()
+            .find(|(name, _)| var(name).is_some())
+            .filter(|_| var(KEEP_CI).as_deref() != Some("0"));
+        let colors = if var("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            Colors::None
+        } else if let Some(&(_, colors)) = ci {
+            colors
+        } else if !terminal || dumb {
+            Colors::None
+        } else if var("COLORTERM").is_some_and(|v| v == "truecolor" || v == "24bit") {
+            Colors::Truecolor
+        } else if term.contains("256color") {
+            Colors::Ansi256
+        } else {
+            Colors::Ansi16
+        };
+        let hyperlinks = match var("FORCE_HYPERLINK").as_deref() {
+            Some("0") => false,
+            Some(_) => true,
+            None => {
+                terminal
+                    && (var("TERM_PROGRAM").is_some_and(|p| HYPERLINKS.contains(&&*p))
+                        || var("KITTY_WINDOW_ID").is_some()
+                        || var("WT_SESSION").is_some()
+                        || term.starts_with("foot")
+                        // vte added them in 0.50
+                        || var("VTE_VERSION")
+                            .and_then(|v| v.parse::<u32>().ok())
+                            .is_some_and(|v| v >= 5000))
+            }
+        };
+        let notifications = var("TERM_PROGRAM")
+            .and_then(|p| NOTIFICATIONS.iter().find(|(name, _)| *name == p))
+            .map(|&(_, notifications)| notifications)
+            .or_else(|| {
+                (var("KITTY_WINDOW_ID").is_some()
+                    || term.starts_with("foot")
+                    || term.starts_with("rxvt-unicode"))
+                .then_some(Notifications::Osc777)
+            })
+            .filter(|_| terminal);
+        let images = var("TERM_PROGRAM")
+            .and_then(|p| IMAGES.iter().find(|(name, _)| *name == p))
+            .map(|&(_, images)| images)
+            .or_else(|| {
+                (var("KITTY_WINDOW_ID").is_some() || term == "xterm-kitty").then_some(Images::Kitty)
+            })
+            .filter(|_| terminal);
+        Self {
+            colors,
+            hyperlinks,
+            titles: terminal && !dumb && term != "linux",
+            notifications,
+            images,
+        }
+    }
+}
+
+/// Write the escape `escape` to `f`, in tmux's passthrough (doubling its `ESC`s) when `TMUX` is set, as tmux would swallow it otherwise.
+///
+/// This is the runtime side of the `tmux` option, for the escapes [`Location`](crate::Location),
+/// [`Notification`](crate::Notification) and [`Image`](crate::Image) make.
+pub(crate) fn write_escape(f: &mut fmt::Formatter<'_>, escape: fmt::Arguments<'_>) -> fmt::Result {
+    static TMUX: OnceLock<bool> = OnceLock::new();
+    if !*TMUX.get_or_init(|| std::env::var_os("TMUX").is_some_and(|v| !v.is_empty())) {
+        return f.write_fmt(escape);
+    }
+    f.write_str("\x1bPtmux;")?;
+    f.write_str(&escape.to_string().replace('\x1b', "\x1b\x1b"))?;
+    f.write_str("\x1b\\")
+}
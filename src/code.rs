@@ -0,0 +1,223 @@
+use crate::{comat, wrap::paint};
+
+/// The languages [`Code`] can highlight.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lang {
+    /// rust
+    Rust,
+    /// toml
+    Toml,
+    /// sql (keywords in any case)
+    Sql,
+    /// sh, bash and friends
+    Shell,
+    /// python
+    Python,
+    /// json
+    Json,
+}
+
+impl Lang {
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Self::Rust => &[
+                "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else",
+                "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match",
+                "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct",
+                "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+            ],
+            Self::Toml | Self::Json => &["true", "false", "null"],
+            Self::Sql => &[
+                "select",
+                "from",
+                "where",
+                "and",
+                "or",
+                "not",
+                "insert",
+                "into",
+                "values",
+                "update",
+                "set",
+                "delete",
+                "create",
+                "table",
+                "drop",
+                "alter",
+                "index",
+                "join",
+                "left",
+                "right",
+                "inner",
+                "outer",
+                "on",
+                "as",
+                "group",
+                "by",
+                "order",
+                "having",
+                "limit",
+                "offset",
+                "distinct",
+                "null",
+                "is",
+                "in",
+                "like",
+                "between",
+                "primary",
+                "key",
+                "references",
+                "default",
+                "union",
+                "case",
+                "when",
+                "then",
+                "else",
+                "end",
+                "exists",
+                "true",
+                "false",
+            ],
+            Self::Shell => &[
+                "if", "then", "else", "elif", "fi", "for", "while", "until", "do", "done", "case",
+                "esac", "in", "function", "return", "export", "local", "exit",
+            ],
+            Self::Python => &[
+                "and", "as", "assert", "async", "await", "break", "class", "continue", "def",
+                "del", "elif", "else", "except", "False", "finally", "for", "from", "global", "if",
+                "import", "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass", "raise",
+                "return", "True", "try", "while", "with", "yield",
+            ],
+        }
+    }
+
+    const fn line_comment(self) -> Option<&'static str> {
+        match self {
+            Self::Rust => Some("//"),
+            Self::Toml | Self::Shell | Self::Python => Some("#"),
+            Self::Sql => Some("--"),
+            Self::Json => None,
+        }
+    }
+
+    const fn block_comment(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Self::Rust | Self::Sql => Some(("/*", "*/")),
+            _ => None,
+        }
+    }
+
+    const fn quotes(self) -> &'static [u8] {
+        match self {
+            Self::Rust | Self::Json => b"\"",
+            Self::Sql => b"'",
+            Self::Toml | Self::Shell | Self::Python => b"\"'",
+        }
+    }
+
+    fn is_keyword(self, word: &str) -> bool {
+        let keywords = self.keywords();
+        if self == Self::Sql {
+            keywords.iter().any(|k| k.eq_ignore_ascii_case(word))
+        } else {
+            keywords.contains(&word)
+        }
+    }
+}
+
+/// The colors to highlight code with.
+///
+/// Each field is some escapes (like from [`comat!`]).
+/// ```
+/// # use comat::*;
+/// let style = Code { keyword: comat!("{bold}"), ..Code::DEFAULT };
+/// assert_eq!(style.highlight(Lang::Sql, "SELECT 1"), "\x1b[1mSELECT\x1b[0m \x1b[0;34;36m1\x1b[0m");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Code<'a> {
+    /// keywords, like `fn` or `select`
+    pub keyword: &'a str,
+    /// string literals
+    pub string: &'a str,
+    /// numbers
+    pub number: &'a str,
+    /// comments
+    pub comment: &'a str,
+}
+
+impl Code<'_> {
+    /// The default colors.
+    pub const DEFAULT: Self = Self {
+        keyword: comat!("{magenta}"),
+        string: comat!("{green}"),
+        number: comat!("{cyan}"),
+        comment: comat!("{dim}"),
+    };
+
+    /// Highlight `source`, as `lang`.
+    ///
+    /// This is a small highlighter, that only knows keywords, strings, numbers and comments.
+    #[must_use]
+    pub fn highlight(&self, lang: Lang, source: &str) -> String {
+        let mut out = String::with_capacity(source.len() * 2);
+        let bytes = source.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            let rest = &source[i..];
+            let start = i;
+            if let Some((open, close)) = lang
+                .block_comment()
+                .filter(|(open, _)| rest.starts_with(open))
+            {
+                i += rest[open.len()..]
+                    .find(close)
+                    .map_or(rest.len(), |end| open.len() + end + close.len());
+                paint(&mut out, self.comment, &source[start..i]);
+            } else if lang.line_comment().is_some_and(|c| rest.starts_with(c)) {
+                i += rest.find('\n').unwrap_or(rest.len());
+                paint(&mut out, self.comment, &source[start..i]);
+            } else if lang.quotes().contains(&bytes[i]) {
+                let quote = bytes[i];
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    // sql escapes quotes by doubling them, which works out as two strings
+                    i += if bytes[i] == b'\\' && lang != Lang::Sql {
+                        2
+                    } else {
+                        1
+                    };
+                }
+                i = (i + 1).min(bytes.len());
+                paint(&mut out, self.string, &source[start..i]);
+            } else if bytes[i].is_ascii_alphabetic() || bytes[i] == b'_' {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                let word = &source[start..i];
+                if lang.is_keyword(word) {
+                    paint(&mut out, self.keyword, word);
+                } else {
+                    out.push_str(word);
+                }
+            } else if bytes[i].is_ascii_digit() {
+                while i < bytes.len()
+                    && (bytes[i].is_ascii_alphanumeric() || matches!(bytes[i], b'_' | b'.'))
+                {
+                    i += 1;
+                }
+                paint(&mut out, self.number, &source[start..i]);
+            } else {
+                let c = rest.chars().next().map_or(1, char::len_utf8);
+                i += c;
+                out.push_str(&source[start..i]);
+            }
+        }
+        out
+    }
+}
+
+impl Default for Code<'_> {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
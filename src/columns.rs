@@ -0,0 +1,47 @@
+use crate::visible_width;
+
+/// Line up cells into columns, by their [`visible_width`], with two spaces between them.
+///
+/// The rows dont need to be the same length, and the last cell in a row isnt padded.
+/// ```
+/// # use comat::*;
+/// let rows = [
+///     [cformat!("{bold}name{reset}"), cformat!("{bold}size{reset}")],
+///     [cformat!("{:blue}", "src"), "4.0K".to_string()],
+/// ];
+/// assert_eq!(
+///     columns(&rows),
+///     "\x1b[1mname\x1b[0m  \x1b[1msize\x1b[0m\n\x1b[0;34;34msrc\x1b[0m   4.0K"
+/// );
+/// ```
+#[must_use]
+pub fn columns<R: AsRef<[C]>, C: AsRef<str>>(rows: &[R]) -> String {
+    let mut widths = Vec::<usize>::new();
+    for row in rows {
+        for (n, cell) in row.as_ref().iter().enumerate() {
+            let width = visible_width(cell.as_ref());
+            match widths.get_mut(n) {
+                Some(w) => *w = (*w).max(width),
+                None => widths.push(width),
+            }
+        }
+    }
+    let mut out = String::new();
+    for (n, row) in rows.iter().enumerate() {
+        if n != 0 {
+            out.push('\n');
+        }
+        let row = row.as_ref();
+        for (n, cell) in row.iter().enumerate() {
+            let cell = cell.as_ref();
+            out.push_str(cell);
+            if n + 1 != row.len() {
+                out.extend(std::iter::repeat_n(
+                    ' ',
+                    widths[n] - visible_width(cell) + 2,
+                ));
+            }
+        }
+    }
+    out
+}
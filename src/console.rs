@@ -0,0 +1,76 @@
+/// Which streams can show escape sequences, from [`enable_windows_ansi`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Consoles {
+    /// Whether stdout has them.
+    pub stdout: bool,
+    /// Whether stderr has them.
+    pub stderr: bool,
+}
+
+/// Turn on escape sequences in the Windows console (`ENABLE_VIRTUAL_TERMINAL_PROCESSING`), for stdout and stderr.
+///
+/// Gives whether they work on each stream, which is always `true` on other platforms.
+/// Each stream is set up on its own, so redirecting stdout to a file (which is no console) still gives stderr its colors.
+/// Where they dont work, the printing macros to that stream print [plain](crate::set_plain) text, rather than escapes the console would show as garbage,
+/// and the other stream keeps its colors.
+/// Call it once, at the start of `main`. [`enable_windows_ansi!`](macro@crate::enable_windows_ansi) does the same.
+/// ```
+/// # use comat::*;
+/// if !enable_windows_ansi().stdout {
+///     eprintln!("this console does not support colors");
+/// }
+/// cprintln!("{green}colors!{reset}");
+/// ```
+// it is fine to call it only to turn them on
+#[allow(clippy::must_use_candidate)]
+pub fn enable_windows_ansi() -> Consoles {
+    #[cfg(windows)]
+    let enabled = windows::enable();
+    #[cfg(not(windows))]
+    let enabled = Consoles {
+        stdout: true,
+        stderr: true,
+    };
+    crate::render::set_unsupported(enabled);
+    enabled
+}
+
+#[cfg(windows)]
+#[allow(unsafe_code)]
+mod windows {
+    type Handle = *mut core::ffi::c_void;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetStdHandle(std: u32) -> Handle;
+        fn GetConsoleMode(console: Handle, mode: *mut u32) -> i32;
+        fn SetConsoleMode(console: Handle, mode: u32) -> i32;
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    const STD_OUTPUT_HANDLE: u32 = -11_i32 as u32;
+    #[allow(clippy::cast_sign_loss)]
+    const STD_ERROR_HANDLE: u32 = -12_i32 as u32;
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+
+    /// Turn on escape sequences for stdout and stderr, each on its own.
+    pub fn enable() -> super::Consoles {
+        super::Consoles {
+            stdout: enable_on(STD_OUTPUT_HANDLE),
+            stderr: enable_on(STD_ERROR_HANDLE),
+        }
+    }
+
+    /// Turn on escape sequences for one std handle, giving whether it has them.
+    /// This fails when the handle is no console, as when it is redirected to a file or a pipe.
+    fn enable_on(std: u32) -> bool {
+        let mut mode = 0;
+        // SAFETY: the handle is only used if GetStdHandle gave one, which GetConsoleMode checks is a console
+        unsafe {
+            let console = GetStdHandle(std);
+            GetConsoleMode(console, &raw mut mode) != 0
+                && (mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0
+                    || SetConsoleMode(console, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0)
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use crate::comat;
+use std::fmt::Write;
+
+const REMOVED: &str = comat!("{red}");
+const ADDED: &str = comat!("{green}");
+const HUNK: &str = comat!("{cyan}");
+const RESET: &str = comat!("{reset}");
+/// reverse video, and back
+const MARK: (&str, &str) = ("\x1b[7m", "\x1b[27m");
+
+/// Lines of context around each change.
+const CONTEXT: usize = 3;
+
+#[derive(Clone, Copy)]
+enum Op {
+    /// a line in both, at these positions
+    Same(usize, usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// A unified diff between two texts, with removals in red and additions in green.
+///
+/// With `inline`, the parts of a changed line that changed are highlighted (in reverse video).
+/// Gives an empty string if there are no differences.
+/// ```
+/// # use comat::*;
+/// let d = diff("a\nb\nc\n", "a\nB\nc\n", false);
+/// assert_eq!(d, cformat!("{cyan}@@ -1,3 +1,3 @@{reset}\n a\n{red}-b{reset}\n{green}+B{reset}\n c\n"));
+/// ```
+#[must_use]
+pub fn diff(old: &str, new: &str, inline: bool) -> String {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+    let ops = ops(&old, &new);
+    let mut out = String::new();
+    let mut i = 0;
+    while let Some(start) = ops[i..].iter().position(|op| !matches!(op, Op::Same(..))) {
+        let start = (i + start).saturating_sub(CONTEXT);
+        // extend the hunk while the next change is close enough to share context
+        let mut end = start;
+        let mut same = 0;
+        for (n, op) in ops.iter().enumerate().skip(start) {
+            if let Op::Same(..) = op {
+                same += 1;
+                if same > CONTEXT * 2 {
+                    break;
+                }
+            } else {
+                same = 0;
+                end = n + 1;
+            }
+        }
+        let end = (end + CONTEXT).min(ops.len());
+        hunk(&mut out, &ops[start..end], &old, &new, inline);
+        i = end;
+    }
+    out
+}
+
+fn hunk(out: &mut String, ops: &[Op], old: &[&str], new: &[&str], inline: bool) {
+    // where the hunk starts, in both texts
+    let (mut o, mut n) = (usize::MAX, usize::MAX);
+    let (mut old_len, mut new_len) = (0, 0);
+    for op in ops {
+        match *op {
+            Op::Same(i, j) => {
+                o = o.min(i);
+                n = n.min(j);
+                old_len += 1;
+                new_len += 1;
+            }
+            Op::Removed(i) => {
+                o = o.min(i);
+                old_len += 1;
+            }
+            Op::Added(j) => {
+                n = n.min(j);
+                new_len += 1;
+            }
+        }
+    }
+    // a side with no lines can only be an empty text, which diff numbers as line 0
+    let position = |at: usize, len| if len == 0 { 0 } else { at + 1 };
+    let (so, sn) = (position(o, old_len), position(n, new_len));
+    let _ = writeln!(out, "{HUNK}@@ -{so},{old_len} +{sn},{new_len} @@{RESET}");
+    let mut i = 0;
+    while i < ops.len() {
+        if let Op::Same(at, _) = ops[i] {
+            out.push(' ');
+            out.push_str(old[at]);
+            out.push('\n');
+            i += 1;
+            continue;
+        }
+        let removed = ops[i..]
+            .iter()
+            .take_while(|op| matches!(op, Op::Removed(_)))
+            .count();
+        let added = ops[i + removed..]
+            .iter()
+            .take_while(|op| matches!(op, Op::Added(_)))
+            .count();
+        let line = |out: &mut String, op: Op, other: Option<Op>| {
+            let (style, sign, text, against) = match (op, other) {
+                (Op::Removed(at), Some(Op::Added(with))) => {
+                    (REMOVED, '-', old[at], Some(new[with]))
+                }
+                (Op::Added(at), Some(Op::Removed(with))) => (ADDED, '+', new[at], Some(old[with])),
+                (Op::Removed(at), _) => (REMOVED, '-', old[at], None),
+                (Op::Added(at) | Op::Same(_, at), _) => (ADDED, '+', new[at], None),
+            };
+            out.push_str(style);
+            out.push(sign);
+            match against.filter(|_| inline) {
+                Some(against) => {
+                    let (start, end) = changed(text, against);
+                    out.push_str(&text[..start]);
+                    out.push_str(MARK.0);
+                    out.push_str(&text[start..end]);
+                    out.push_str(MARK.1);
+                    out.push_str(&text[end..]);
+                }
+                None => out.push_str(text),
+            }
+            out.push_str(RESET);
+            out.push('\n');
+        };
+        for k in 0..removed {
+            line(out, ops[i + k], (k < added).then(|| ops[i + removed + k]));
+        }
+        for k in 0..added {
+            line(out, ops[i + removed + k], (k < removed).then(|| ops[i + k]));
+        }
+        i += removed + added;
+    }
+}
+
+/// The byte range of `text` that differs from `against` (what is left after the common prefix and suffix).
+fn changed(text: &str, against: &str) -> (usize, usize) {
+    let start = text
+        .char_indices()
+        .zip(against.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(text.len().min(against.len()), |((i, _), _)| i);
+    let suffix = text[start..]
+        .chars()
+        .rev()
+        .zip(against[start.min(against.len())..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum::<usize>();
+    (start, text.len() - suffix)
+}
+
+/// The shortest edit script from `old` to `new` (the longest common subsequence of their lines), as a list of operations,
+/// with the removals of each change before its additions.
+///
+/// This is Myers' algorithm, splitting at the middle of the script, so it takes space for only a few rows, rather than a table of every pair of lines.
+fn ops(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let mut ops = Vec::with_capacity(old.len().max(new.len()));
+    script(old, new, (0, 0), &mut ops);
+    // the halves of a change can come in either order
+    for change in ops.chunk_by_mut(|a, b| !matches!(a, Op::Same(..)) && !matches!(b, Op::Same(..)))
+    {
+        change.sort_by_key(|op| matches!(op, Op::Added(_)));
+    }
+    ops
+}
+
+/// Push the edit script from `old` to `new` onto `ops`, where they start at lines `at` of the whole texts.
+fn script(old: &[&str], new: &[&str], at: (usize, usize), ops: &mut Vec<Op>) {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    ops.extend((0..prefix).map(|k| Op::Same(at.0 + k, at.1 + k)));
+    let (old, new) = (&old[prefix..], &new[prefix..]);
+    let suffix = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old, new) = (&old[..old.len() - suffix], &new[..new.len() - suffix]);
+    let at = (at.0 + prefix, at.1 + prefix);
+    if old.is_empty() || new.is_empty() {
+        ops.extend((0..old.len()).map(|k| Op::Removed(at.0 + k)));
+        ops.extend((0..new.len()).map(|k| Op::Added(at.1 + k)));
+    } else {
+        let (x, y) = middle(old, new);
+        script(&old[..x], &new[..y], at, ops);
+        script(&old[x..], &new[y..], (at.0 + x, at.1 + y), ops);
+    }
+    let end = (at.0 + old.len(), at.1 + new.len());
+    ops.extend((0..suffix).map(|k| Op::Same(end.0 + k, end.1 + k)));
+}
+
+/// Where the shortest edit script from `old` to `new` (neither empty, with different first and last lines) can be split in two,
+/// found by going forward from the start and back from the end at once, until the paths meet.
+#[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+fn middle(old: &[&str], new: &[&str]) -> (usize, usize) {
+    let (old_len, new_len) = (old.len() as isize, new.len() as isize);
+    let max = (old_len + new_len + 1) / 2;
+    let delta = old_len - new_len;
+    // a path with an odd number of edits meets going forward, an even one going back
+    let front = delta % 2 != 0;
+    // the furthest x on each diagonal k (x - y), at offset max + k, going forward, and back from the end
+    let mut forward = vec![-1_isize; 2 * max as usize + 2];
+    let mut back = forward.clone();
+    forward[max as usize + 1] = 0;
+    back[max as usize + 1] = 0;
+    // the diagonals that went past the edges, which are skipped
+    let (mut forward_start, mut forward_end, mut back_start, mut back_end) = (0, 0, 0, 0);
+    let diagonal = |k: isize| (max + k) as usize;
+    for d in 0..max {
+        for k in (-d + forward_start..=d - forward_end).step_by(2) {
+            let mut x =
+                if k == -d || (k != d && forward[diagonal(k - 1)] < forward[diagonal(k + 1)]) {
+                    forward[diagonal(k + 1)]
+                } else {
+                    forward[diagonal(k - 1)] + 1
+                };
+            let mut y = x - k;
+            while x < old_len && y < new_len && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            forward[diagonal(k)] = x;
+            if x > old_len {
+                forward_end += 2;
+            } else if y > new_len {
+                forward_start += 2;
+            } else if front {
+                let other = delta - k;
+                if (-max..=max).contains(&other)
+                    && back[diagonal(other)] != -1
+                    && x >= old_len - back[diagonal(other)]
+                {
+                    return (x as usize, y as usize);
+                }
+            }
+        }
+        for k in (-d + back_start..=d - back_end).step_by(2) {
+            let mut x = if k == -d || (k != d && back[diagonal(k - 1)] < back[diagonal(k + 1)]) {
+                back[diagonal(k + 1)]
+            } else {
+                back[diagonal(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < old_len
+                && y < new_len
+                && old[(old_len - x - 1) as usize] == new[(new_len - y - 1) as usize]
+            {
+                x += 1;
+                y += 1;
+            }
+            back[diagonal(k)] = x;
+            if x > old_len {
+                back_end += 2;
+            } else if y > new_len {
+                back_start += 2;
+            } else if !front {
+                let other = delta - k;
+                if (-max..=max).contains(&other) && forward[diagonal(other)] != -1 {
+                    let forward_x = forward[diagonal(other)];
+                    if forward_x >= old_len - x {
+                        return (forward_x as usize, (forward_x - other) as usize);
+                    }
+                }
+            }
+        }
+    }
+    // nothing in common: remove all of old, then add all of new
+    (old.len(), 0)
+}
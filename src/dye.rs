@@ -0,0 +1,115 @@
+use crate::parser::{inline, parse, Made, Options, Theme};
+use std::fmt::{Display, Write};
+
+/// Color a template that is only known at runtime, like one from a config file, as [`cformat!`](crate::cformat) would.
+///
+/// The arguments are positional: `{}` takes the next one, and `{1}` the second.
+/// Tokens come from the builtin palette; use [`dye_with`] for another [`Theme`].
+/// ```
+/// # use comat::*;
+/// let template = "{bold}{}{reset}: {1:green}"; // from a config file
+/// assert_eq!(dye(template, &[&"comat", &1.0]).unwrap(), cformat!("{bold}{}{reset}: {1:green}", "comat", 1.0));
+/// ```
+///
+/// # Errors
+///
+/// when the template is invalid, uses more arguments than it was given, or uses names or format specs.
+pub fn dye(template: &str, args: &[&dyn Display]) -> Result<String, String> {
+    dye_with(template, &Theme::default(), args)
+}
+
+/// [`dye`], with the tokens of `theme`.
+///
+/// # Errors
+///
+/// when the template is invalid, uses more arguments than it was given, or uses names or format specs.
+pub fn dye_with(template: &str, theme: &Theme, args: &[&dyn Display]) -> Result<String, String> {
+    let parsed = parse(template, theme, &Options::default())?;
+    if !parsed
+        .made
+        .iter()
+        .all(|(_, made)| matches!(made, Made::Literal(_)))
+    {
+        return Err(
+            "formatters, scales and [style]s need arguments, which dye does not take".into(),
+        );
+    }
+    fill(&inline(&parsed.text, &parsed.made, true), args)
+}
+
+/// [`dye`] a translation of `source` (like one loaded from fluent or gettext), making sure it only uses the tokens `source` does,
+/// so that translators cannot change what the output looks like.
+/// ```
+/// # use comat::*;
+/// let source = "{error}error{reset}: cannot find {bold}{}{reset}";
+/// let german = "{error}Fehler{reset}: {bold}{}{reset} nicht gefunden";
+/// assert!(dye_translated(source, german, &[&"comat.toml"]).is_ok());
+/// assert!(dye_translated(source, "{blinking}Fehler{reset}", &[]).is_err());
+/// ```
+///
+/// # Errors
+///
+/// when either string is invalid, the translation uses a token that `source` does not, or [`dye`] fails.
+pub fn dye_translated(
+    source: &str,
+    translation: &str,
+    args: &[&dyn Display],
+) -> Result<String, String> {
+    let theme = Theme::default();
+    let source = parse(source, &theme, &Options::default())?;
+    let translated = parse(translation, &theme, &Options::default())?;
+    if let Some(token) = translated
+        .tokens
+        .iter()
+        .find(|&token| !source.tokens.contains(token))
+    {
+        return Err(format!(
+            "the translation uses {token:?}, which the source string does not"
+        ));
+    }
+    dye_with(translation, &theme, args)
+}
+
+/// Put the `args` into a format string.
+fn fill(format: &str, args: &[&dyn Display]) -> Result<String, String> {
+    let mut out = String::with_capacity(format.len());
+    let mut next = 0;
+    let mut rest = format;
+    while let Some(at) = rest.find(['{', '}']) {
+        out.push_str(&rest[..at]);
+        let brace = rest.as_bytes()[at];
+        rest = &rest[at + 1..];
+        if rest.as_bytes().first() == Some(&brace) {
+            out.push(brace as char);
+            rest = &rest[1..];
+            continue;
+        }
+        if brace == b'}' {
+            return Err("unmatched `}` in template".into());
+        }
+        let end = rest.find('}').ok_or("unclosed `{` in template")?;
+        let (position, spec) = rest[..end].split_once(':').unwrap_or((&rest[..end], ""));
+        rest = &rest[end + 1..];
+        if !spec.is_empty() {
+            return Err(format!("dye does not take format specs, like {{:{spec}}}"));
+        }
+        let index = if position.is_empty() {
+            next += 1;
+            next - 1
+        } else {
+            position
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("dye only takes positional arguments, not {position:?}"))?
+        };
+        let arg = args.get(index).ok_or_else(|| {
+            format!(
+                "the template uses argument {index}, but was given {}",
+                args.len()
+            )
+        })?;
+        write!(out, "{arg}").unwrap();
+    }
+    out.push_str(rest);
+    Ok(out)
+}
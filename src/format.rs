@@ -0,0 +1,150 @@
+//! Formatters, for `{arg:colors:formatter}` blocks.
+//!
+//! A formatter is any function `fn(&T, &mut Formatter) -> fmt::Result`.
+//! The ones here can be used by name; others need to be in scope, and take colors (which may be empty):
+//! ```
+//! # use comat::*;
+//! use std::fmt;
+//! fn percent(x: &f64, f: &mut fmt::Formatter) -> fmt::Result {
+//!     write!(f, "{:.0}%", x * 100.0)
+//! }
+//! let (done, size) = (0.25, 1536_u64);
+//! assert_eq!(cformat!("{done:green:percent} of {size::human_bytes}"), cformat!("{:green} of 1.5 KiB", "25%"));
+//! ```
+use core::fmt;
+
+/// A value, and the function to format it with.
+pub struct Formatted<'a, T: ?Sized>(
+    pub &'a T,
+    pub fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+);
+
+impl<T: ?Sized> fmt::Display for Formatted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.1)(self.0, f)
+    }
+}
+
+/// A number of bytes, in binary units, like `1.5 KiB`, or `-1.5 KiB` when it is negative.
+///
+/// The number is rounded to the tenth it shows before its unit is chosen, so 1048575 bytes are `1.0 MiB`, not `1024.0 KiB`.
+/// ```
+/// # use comat::*;
+/// let size = 3 * 1024 * 1024_usize;
+/// assert_eq!(cformat!("{size:>8:bold:human_bytes}"), cformat!("{:bold}", " 3.0 MiB"));
+/// ```
+/// # Errors
+///
+/// when the formatter does
+#[cfg(feature = "std")]
+pub fn human_bytes<T: Copy + TryInto<i128>>(bytes: &T, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    // only a u128 past i128 does not fit
+    let bytes = (*bytes).try_into().unwrap_or(i128::MAX);
+    let sign = if bytes < 0 { "-" } else { "" };
+    let bytes = bytes.unsigned_abs();
+    if bytes < 1024 {
+        return f.pad(&format!("{sign}{bytes} B"));
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let mut n = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    // rounded to tenths, as it is shown, so a number that rounds up to 1024 takes the next unit
+    while (n * 10.0).round() >= 10240.0 && unit + 1 < UNITS.len() {
+        n /= 1024.0;
+        unit += 1;
+    }
+    f.pad(&format!(
+        "{sign}{:.1} {}",
+        (n * 10.0).round() / 10.0,
+        UNITS[unit]
+    ))
+}
+
+/// A [`Duration`](core::time::Duration), in the biggest unit that fits, like `1.5s` or `2m 5s`.
+///
+/// It is rounded to what it shows before its unit is chosen, so 59.96s is `1m 0s`, not `60.0s`.
+/// ```
+/// # use comat::*;
+/// let took = std::time::Duration::from_millis(1500);
+/// assert_eq!(cformat!("{took:green:human_duration}"), cformat!("{:green}", "1.5s"));
+/// ```
+/// # Errors
+///
+/// when the formatter does
+#[cfg(feature = "std")]
+pub fn human_duration(duration: &core::time::Duration, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let nanos = duration.as_nanos();
+    // the duration in tenths of `unit` nanoseconds, rounded
+    let tenths = |unit: u128| (nanos * 10 + unit / 2) / unit;
+    let s = if tenths(1_000_000_000) >= 600 {
+        let secs = (nanos + 500_000_000) / 1_000_000_000;
+        if secs >= 3600 {
+            format!("{}h {}m", secs / 3600, secs % 3600 / 60)
+        } else {
+            format!("{}m {}s", secs / 60, secs % 60)
+        }
+    } else if tenths(1_000_000) >= 10_000 {
+        let t = tenths(1_000_000_000);
+        format!("{}.{}s", t / 10, t % 10)
+    } else if tenths(1_000) >= 10_000 {
+        let t = tenths(1_000_000);
+        format!("{}.{}ms", t / 10, t % 10)
+    } else if nanos >= 1_000 {
+        let t = tenths(1_000);
+        format!("{}.{}µs", t / 10, t % 10)
+    } else {
+        format!("{nanos}ns")
+    };
+    f.pad(&s)
+}
+
+/// A value, and the escapes to show it in, from `{arg:scale(..)}`.
+///
+/// The escapes are written before the value, so its format spec only pads the value.
+/// ```
+/// # use comat::*;
+/// let (fast, slow) = (12, 340);
+/// assert_eq!(
+///     cformat!("{fast:scale(50=green,200=yellow,_=red)} {slow:>4:scale(50=green,200=yellow,_=red)}"),
+///     cformat!("{fast:green} {slow:>4:red}"),
+/// );
+/// ```
+pub struct Painted<'a, T: ?Sized>(pub &'a T, pub &'static str);
+
+impl<'a, T: ?Sized> Painted<'a, T> {
+    /// `value`, in the escapes `scale` picks for it.
+    pub fn scaled(value: &'a T, scale: impl FnOnce(&T) -> &'static str) -> Self {
+        Self(value, scale(value))
+    }
+}
+
+impl<T: fmt::Display + ?Sized> fmt::Display for Painted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.1)?;
+        self.0.fmt(f)
+    }
+}
+
+/// A value, the escapes to show it in, and a reset after, from [`paint!`](crate::paint).
+pub struct Paint<T>(pub T, pub &'static str);
+
+impl<T: fmt::Display> fmt::Display for Paint<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.1)?;
+        self.0.fmt(f)?;
+        f.write_str("\x1b[0m")
+    }
+}
+
+/// A value, and the [`Style`](crate::Style) to show it in, from `{arg:[style]}`.
+///
+/// Like [`Painted`], the format spec only pads the value.
+pub struct Styled<'a, T: ?Sized>(pub &'a T, pub &'a crate::Style);
+
+impl<T: fmt::Display + ?Sized> fmt::Display for Styled<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.1)?;
+        self.0.fmt(f)
+    }
+}
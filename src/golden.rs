@@ -0,0 +1,71 @@
+//! Golden files: colored output checked in next to the tests, and compared against.
+//!
+//! The files keep the escapes written out as text (like [`comat_debug!`](crate::comat_debug)), so they can be read and diffed in review.
+//! Run the tests with `COMAT_GOLDEN=update` to write the files, instead of comparing with them.
+//! ```no_run
+//! # use comat::*;
+//! let out = cformat!("{bold}usage{reset}: app <file>");
+//! golden::check(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/usage.txt"), &out);
+//! ```
+use std::{fs, path::Path};
+
+use crate::{cformat, Render, Stream};
+
+/// The environment variable that makes [`check`] write the golden files, when it is `update`.
+pub const UPDATE: &str = "COMAT_GOLDEN";
+
+/// Compare `actual` against the golden file at `path`, or write it there, if the tests were run with [`UPDATE`]` = update`.
+///
+/// # Panics
+///
+/// when `actual` is not what the file has (showing a [`diff`](crate::diff)), or the file cannot be read or written.
+#[track_caller]
+pub fn check(path: impl AsRef<Path>, actual: &str) {
+    let path = path.as_ref();
+    let actual = escape(actual);
+    if std::env::var(UPDATE).is_ok_and(|v| v == "update") {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap_or_else(|e| {
+                panic!("couldnt create {}: {e}", parent.display());
+            });
+        }
+        fs::write(path, &actual).unwrap_or_else(|e| {
+            panic!("couldnt write {}: {e}", path.display());
+        });
+        return;
+    }
+    let expected = fs::read_to_string(path).unwrap_or_else(|e| {
+        panic!(
+            "{}",
+            Render(
+                cformat!(
+                    "couldnt read {:bold}: {e}\n(run with {UPDATE:bold}=update to write it)",
+                    path.display()
+                ),
+                Stream::Stderr
+            )
+        );
+    });
+    assert!(
+        expected == actual,
+        "{}",
+        Render(
+            cformat!(
+                "{:bold} is not the same:\n{}(run with {UPDATE:bold}=update to write it)",
+                path.display(),
+                crate::diff(&expected, &actual, true),
+            ),
+            Stream::Stderr
+        )
+    );
+}
+
+/// `s`, with the escape characters written out as text, like `\x1b[1m`: how [`check`] keeps it.
+/// ```
+/// # use comat::*;
+/// assert_eq!(golden::escape(&cformat!("{bold}hi{reset}")), comat_debug!("{bold}hi{reset}"));
+/// ```
+#[must_use]
+pub fn escape(s: &str) -> String {
+    s.replace('\x1b', "\\x1b").replace('\x07', "\\x07")
+}
@@ -0,0 +1,120 @@
+use crate::comat;
+
+/// The colors of a hexdump, in the layout of `hexdump -C`.
+///
+/// Each field is some escapes (like from [`comat!`]).
+/// ```
+/// # use comat::*;
+/// let style = Hexdump { zero: "", ..Hexdump::DEFAULT };
+/// println!("{}", style.dump(b"\0\x01 comat"));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hexdump<'a> {
+    /// the offset at the start of each line
+    pub offset: &'a str,
+    /// `0` bytes
+    pub zero: &'a str,
+    /// printable ascii (and space)
+    pub printable: &'a str,
+    /// every other byte
+    pub other: &'a str,
+}
+
+impl Hexdump<'_> {
+    /// The default colors.
+    pub const DEFAULT: Self = Self {
+        offset: comat!("{dim}"),
+        zero: comat!("{dim}"),
+        printable: comat!("{cyan}"),
+        other: comat!("{yellow}"),
+    };
+
+    fn style(&self, byte: u8) -> &str {
+        match byte {
+            0 => self.zero,
+            b' '..=b'~' => self.printable,
+            _ => self.other,
+        }
+    }
+
+    /// Dump `bytes`, 16 to a line.
+    #[must_use]
+    pub fn dump(&self, bytes: &[u8]) -> String {
+        use std::fmt::Write;
+        let mut out = String::with_capacity(bytes.len() * 8);
+        for (n, line) in bytes.chunks(16).enumerate() {
+            if n != 0 {
+                out.push('\n');
+            }
+            let _ = write!(out, "{}{:08x}", self.offset, n * 16);
+            reset(&mut out, self.offset);
+            out.push_str("  ");
+            let mut current = "";
+            for (i, &byte) in line.iter().enumerate() {
+                switch(&mut out, &mut current, self.style(byte));
+                let _ = write!(out, "{byte:02x} ");
+                if i == 7 {
+                    out.push(' ');
+                }
+            }
+            reset(&mut out, current);
+            // pad short lines, so the ascii lines up
+            let missing = 16 - line.len();
+            out.extend(std::iter::repeat_n(
+                ' ',
+                missing * 3 + usize::from(line.len() < 8),
+            ));
+            out.push(' ');
+            out.push('|');
+            let mut current = "";
+            for &byte in line {
+                switch(&mut out, &mut current, self.style(byte));
+                out.push(if matches!(byte, b' '..=b'~') {
+                    byte as char
+                } else {
+                    '.'
+                });
+            }
+            reset(&mut out, current);
+            out.push('|');
+        }
+        out
+    }
+}
+
+impl Default for Hexdump<'_> {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Change style, only if it changed.
+fn switch<'a>(out: &mut String, current: &mut &'a str, style: &'a str) {
+    if *current != style {
+        if !current.is_empty() {
+            out.push_str("\x1b[0m");
+        }
+        out.push_str(style);
+        *current = style;
+    }
+}
+
+fn reset(out: &mut String, style: &str) {
+    if !style.is_empty() {
+        out.push_str("\x1b[0m");
+    }
+}
+
+/// A colored hexdump of `bytes`, with the [default colors](Hexdump::DEFAULT).
+/// ```
+/// # use comat::*;
+/// let dump = hexdump(b"comat");
+/// assert_eq!(
+///     dump,
+///     cformat!("{dim}00000000{reset}  {cyan}63 6f 6d 61 74 {reset}                                   |{cyan}comat{reset}|")
+/// );
+/// ```
+#[must_use]
+pub fn hexdump(bytes: &[u8]) -> String {
+    Hexdump::DEFAULT.dump(bytes)
+}
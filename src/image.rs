@@ -0,0 +1,75 @@
+use std::{fmt, path::Path};
+
+use crate::{capabilities::write_escape, Images};
+
+/// The image at a path, some cells wide, for the terminals that [show them](crate::Capabilities::images).
+/// In the others, or when the file cannot be read, it shows nothing. Inside tmux (when `TMUX` is set), it is passed through to the terminal.
+///
+/// `{image(path, width)}` makes these.
+/// ```
+/// # use comat::*;
+/// print!("{}", Image("assets/logo.png", 8));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Image<P, W>(pub P, pub W);
+
+/// How much of the image kitty takes in one escape.
+const CHUNK: usize = 4096;
+
+impl<P: AsRef<Path>, W: fmt::Display> fmt::Display for Image<P, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(images) = crate::capabilities().images else {
+            return Ok(());
+        };
+        let Ok(bytes) = std::fs::read(&self.0) else {
+            return Ok(());
+        };
+        let data = base64(&bytes);
+        let width = &self.1;
+        match images {
+            Images::Iterm => write_escape(
+                f,
+                format_args!(
+                    "\x1b]1337;File=inline=1;size={};width={width};preserveAspectRatio=1:{data}\x07",
+                    bytes.len()
+                ),
+            ),
+            Images::Kitty => {
+                // base64 is ascii, so it can be cut anywhere
+                for start in (0..data.len()).step_by(CHUNK) {
+                    let chunk = &data[start..data.len().min(start + CHUNK)];
+                    let more = u8::from(start + CHUNK < data.len());
+                    if start == 0 {
+                        write_escape(
+                            f,
+                            format_args!("\x1b_Ga=T,f=100,c={width},m={more};{chunk}\x1b\\"),
+                        )?;
+                    } else {
+                        write_escape(f, format_args!("\x1b_Gm={more};{chunk}\x1b\\"))?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// `bytes` in standard base64, with padding.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for group in bytes.chunks(3) {
+        let n = group
+            .iter()
+            .enumerate()
+            .fold(0_u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            out.push(if i <= group.len() {
+                char::from(ALPHABET[(n >> (18 - 6 * i)) as usize & 63])
+            } else {
+                '='
+            });
+        }
+    }
+    out
+}
@@ -0,0 +1,242 @@
+use crate::{comat, wrap::paint};
+
+/// The colors to pretty print json with.
+///
+/// Each field is some escapes (like from [`comat!`]).
+/// ```
+/// # use comat::*;
+/// let style = Json { punctuation: "", ..Json::DEFAULT };
+/// assert_eq!(style.pretty("[]"), "[]");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Json<'a> {
+    /// the keys of objects
+    pub key: &'a str,
+    /// other strings
+    pub string: &'a str,
+    /// numbers
+    pub number: &'a str,
+    /// `true`, `false` and `null`
+    pub literal: &'a str,
+    /// `{}[]:,`
+    pub punctuation: &'a str,
+}
+
+impl Json<'_> {
+    /// The default colors.
+    pub const DEFAULT: Self = Self {
+        key: comat!("{blue}"),
+        string: comat!("{green}"),
+        number: comat!("{cyan}"),
+        literal: comat!("{magenta}"),
+        punctuation: comat!("{dim}"),
+    };
+
+    /// Pretty print `json`, indenting with two spaces.
+    ///
+    /// This only looks at the tokens, so it doesnt need valid json (but will not make it valid either).
+    /// With the `serde_json` feature, [`value`](Self::value) prints a `serde_json::Value` without writing it out and reading it again.
+    #[must_use]
+    pub fn pretty(&self, json: &str) -> String {
+        let mut out = String::with_capacity(json.len() * 2);
+        let bytes = json.as_bytes();
+        let mut depth = 0_usize;
+        let newline = |out: &mut String, depth: usize| {
+            out.push('\n');
+            out.extend(std::iter::repeat_n("  ", depth));
+        };
+        let next = |mut i: usize| {
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            bytes.get(i).map(|&b| (i, b))
+        };
+        let mut i = 0;
+        while let Some((at, byte)) = next(i) {
+            i = at + 1;
+            match byte {
+                b'{' | b'[' => {
+                    let close = if byte == b'{' { b'}' } else { b']' };
+                    paint(&mut out, self.punctuation, &json[at..i]);
+                    match next(i) {
+                        // keep empty ones on one line
+                        Some((end, b)) if b == close => {
+                            paint(&mut out, self.punctuation, &json[end..=end]);
+                            i = end + 1;
+                        }
+                        _ => {
+                            depth += 1;
+                            newline(&mut out, depth);
+                        }
+                    }
+                }
+                b'}' | b']' => {
+                    depth = depth.saturating_sub(1);
+                    newline(&mut out, depth);
+                    paint(&mut out, self.punctuation, &json[at..i]);
+                }
+                b',' => {
+                    paint(&mut out, self.punctuation, ",");
+                    newline(&mut out, depth);
+                }
+                b':' => {
+                    paint(&mut out, self.punctuation, ":");
+                    out.push(' ');
+                }
+                b'"' => {
+                    let mut escaped = false;
+                    while i < bytes.len() {
+                        let b = bytes[i];
+                        i += 1;
+                        match b {
+                            _ if escaped => escaped = false,
+                            b'\\' => escaped = true,
+                            b'"' => break,
+                            _ => {}
+                        }
+                    }
+                    let style = match next(i) {
+                        Some((_, b':')) => self.key,
+                        _ => self.string,
+                    };
+                    paint(&mut out, style, &json[at..i]);
+                }
+                _ => {
+                    while i < bytes.len()
+                        && !bytes[i].is_ascii_whitespace()
+                        && !b"{}[],:\"".contains(&bytes[i])
+                    {
+                        i += 1;
+                    }
+                    let style = if byte == b'-' || byte.is_ascii_digit() {
+                        self.number
+                    } else {
+                        self.literal
+                    };
+                    paint(&mut out, style, &json[at..i]);
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl Json<'_> {
+    /// Pretty print a `serde_json::Value`, like [`pretty`](Self::pretty) does its text.
+    /// ```
+    /// # use comat::*;
+    /// let value = serde_json::json!({ "name": "comat", "tags": ["color", 1, null], "empty": {} });
+    /// assert_eq!(Json::DEFAULT.value(&value), json(&value.to_string()));
+    /// ```
+    #[must_use]
+    pub fn value(&self, value: &serde_json::Value) -> String {
+        let mut out = String::new();
+        self.walk(&mut out, value, 0);
+        out
+    }
+
+    /// Print `value` at `depth`.
+    fn walk(&self, out: &mut String, value: &serde_json::Value, depth: usize) {
+        use serde_json::Value;
+        match value {
+            Value::Null => paint(out, self.literal, "null"),
+            Value::Bool(b) => paint(out, self.literal, if *b { "true" } else { "false" }),
+            Value::Number(n) => paint(out, self.number, &n.to_string()),
+            Value::String(s) => paint(out, self.string, &quoted(s)),
+            Value::Array(items) => {
+                let items = items.iter().map(|item| (None, item)).collect();
+                self.nested(out, ["[", "]"], items, depth);
+            }
+            Value::Object(map) => {
+                let items = map.iter().map(|(key, item)| (Some(&**key), item)).collect();
+                self.nested(out, ["{", "}"], items, depth);
+            }
+        }
+    }
+
+    /// Print an array or object, with its `items` (and their keys) on lines of their own, unless there are none.
+    fn nested(
+        &self,
+        out: &mut String,
+        [open, close]: [&str; 2],
+        items: Vec<(Option<&str>, &serde_json::Value)>,
+        depth: usize,
+    ) {
+        let newline = |out: &mut String, depth: usize| {
+            out.push('\n');
+            out.extend(std::iter::repeat_n("  ", depth));
+        };
+        paint(out, self.punctuation, open);
+        if items.is_empty() {
+            paint(out, self.punctuation, close);
+            return;
+        }
+        for (i, (key, item)) in items.into_iter().enumerate() {
+            if i > 0 {
+                paint(out, self.punctuation, ",");
+            }
+            newline(out, depth + 1);
+            if let Some(key) = key {
+                paint(out, self.key, &quoted(key));
+                paint(out, self.punctuation, ":");
+                out.push(' ');
+            }
+            self.walk(out, item, depth + 1);
+        }
+        newline(out, depth);
+        paint(out, self.punctuation, close);
+    }
+}
+
+/// `s` as a json string.
+#[cfg(feature = "serde_json")]
+fn quoted(s: &str) -> String {
+    serde_json::to_string(s).expect("strings are json")
+}
+
+impl Default for Json<'_> {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Pretty print `json` with the [default colors](Json::DEFAULT).
+/// ```
+/// # use comat::*;
+/// assert_eq!(json("[1]"), cformat!("{dim}[{reset}\n  {cyan}1{reset}\n{dim}]{reset}"));
+/// ```
+#[must_use]
+pub fn json(json: &str) -> String {
+    Json::DEFAULT.pretty(json)
+}
+
+/// What [`cjson!`](crate::cjson) prints: a `serde_json::Value`, with the `serde_json` feature, which is [walked](Json::value),
+/// or anything else that displays as json, which is [read](Json::pretty) from what it displays.
+///
+/// Which is chosen by method resolution: the [`Walk`] impl takes `&Source`, so it is found before the [`Lex`] impl, which takes `&&Source`.
+#[doc(hidden)]
+pub struct Source<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait Walk {
+    fn json(&self, style: &Json) -> String;
+}
+
+#[cfg(feature = "serde_json")]
+impl<T: std::borrow::Borrow<serde_json::Value> + ?Sized> Walk for Source<'_, T> {
+    fn json(&self, style: &Json) -> String {
+        style.value(self.0.borrow())
+    }
+}
+
+#[doc(hidden)]
+pub trait Lex {
+    fn json(&self, style: &Json) -> String;
+}
+
+impl<T: std::fmt::Display + ?Sized> Lex for &Source<'_, T> {
+    fn json(&self, style: &Json) -> String {
+        style.pretty(&self.0.to_string())
+    }
+}
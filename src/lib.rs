@@ -43,191 +43,443 @@
 //!   cprintln!("{red}{on_blue}{thing1} {thing2} {thing3:italic,yellow}");
 //!   ```
 //!
+//! to color existing code without touching every call, [`#[colorize]`](macro@colorize) turns the `println!`s (and `format!`s, ..) inside a function into comat ones,
+//! and inside a `format!` that cannot be comat syntax, [`paint!`] colors a single value.
+//!
+//! the parser itself is a library too: with the `parser` feature, `comat::parser` translates comat strings at runtime, for build scripts and other proc macros,
+//! and [`dye`](https://docs.rs/comat/latest/comat/fn.dye.html) colors templates that are only known at runtime, like ones from a config file,
+//! or [translations](https://docs.rs/comat/latest/comat/fn.dye_translated.html).
+//!
+//! ## `no_std`
+//!
+//! [`comat!`], [`comat_bytes!`], [`comat_debug!`], [`cconcat!`], [`cinclude_str!`], [`cformat_args!`], [`cformat_args_nl!`], [`cwrite!`], [`cwriteln!`], [`cwrite_ok!`], [`cwriteln_ok!`], [`cwrite_fmt!`] and [`cpanic!`] only need `core::fmt`, so they work on `no_std`.
+//! the macros that need `std` ([`cprintln!`], [`cprint!`], [`cformat!`]) are behind the default `std` feature.
+//! ```toml
+//! comat = { version = "0.1", default-features = false }
+//! ```
+//!
+//! ## build time
+//!
+//! the macros are parsed with `syn`, which is most of the time a cold build of comat takes.
+//! the [`comat-tiny`](https://docs.rs/comat-tiny) crate has [`comat!`], [`cformat!`], [`cformat_args!`], [`cprint!`], [`cprintln!`], `ceprint!`, `ceprintln!`, [`cpanic!`], [`cwrite!`] and [`cwriteln!`] without it,
+//! as they only take a string and pass the arguments after it on. it has the same colors, but not the formatters, scales and `[style]`s, or the runtime features, like [`set_plain`].
+//! ```toml
+//! comat-tiny = "0.1"
+//! ```
+//!
 //! ## syntax
 //!
+//! the format string is a string literal, or a `concat!` / `include_str!` of them:
+//! ```
+//! # use comat::cformat;
+//! let name = "comat";
+//! let s = cformat!(concat!("{name:bold} ", "v", 1, ".", 0, "{reset}"));
+//! # assert_eq!(s, "\x1b[0m\x1b[1mcomat\x1b[0m v1.0");
+//! let banner = cformat!(include_str!("../README.md"));
+//! ```
+//! string literals next to each other are joined, for long strings:
+//! ```
+//! # use comat::cformat;
+//! let s = cformat!("{bold}one long line, "
+//!                  "split in two{reset}");
+//! # assert_eq!(s, "\x1b[1mone long line, split in two\x1b[0m");
+//! ```
+//!
 //! `{{` gives you a `{`, to get a `{{` use `{{{{`.
 //!
+//! `{!red}` gives you the text `{red}`, in every macro (where `{{red}}` would be a format argument in [`cformat!`]), for showing comat syntax itself.
+//!
 //! `{color}` adds that effect/color to the string. it does not reset afterwards.
 //!
 //! if the color inside a `{}` is not found, it doesnt touch the block, for convenience.
 //!
-//! `{thing:color}` will reset everything before the block, color it, and reset that color. similar to `thing.color()` with other libs.
+//! `{thing:color}` will reset everything before the block, color it, and then go back to the colors before it. similar to `thing.color()` with other libs.
 //! it can also contain more than one color: `{thing:yelow,italic,on_red}`
 //!
+//! resets that would do nothing (like a second one in a row, or one right before a color that resets anyway) are left out.
+//!
+//! a format spec goes between the two: `{tag:>12:bold_yellow}`. the escapes go outside of the `{}`, so widths only count the text of the value:
+//! ```
+//! # use comat::cformat;
+//! let tag = "warn";
+//! assert_eq!(cformat!("[{tag:>6:bold_yellow}]"), "[\x1b[0m\x1b[1;34;33m  warn\x1b[0m]");
+//! ```
+//!
+//! and a [formatter](format) goes after the colors, to change how the value is shown: `{size:cyan:human_bytes}`.
+//!
+//! `scale(..)` in the colors picks a color by the value, from the first threshold it is below (`_` is for the values past them all):
+//! ```
+//! # use comat::cprintln;
+//! let latency_ms = 120;
+//! cprintln!("latency: {latency_ms:bold,scale(50=green,200=yellow,_=red)}ms");
+//! ```
+//!
+//! `[style]` in the colors shows the value in a [`Style`] chosen at runtime (made with its builders, or [`cstyle!`]), while the rest of the string stays compile time:
+//! ```
+//! # use comat::*;
+//! let (level, message) = ("error", "disk full");
+//! let style = match level {
+//!     "error" => cstyle!(bold_red),
+//!     _ => Style::new(),
+//! };
+//! cprintln!("{level:[style]}: {message}");
+//! ```
+//!
+//! `@style,` before the string applies a base style to all of it, which is restored after every reset:
+//! ```
+//! # use comat::cprintln;
+//! let file = "src/main.rs";
+//! cprintln!(@dim, "compiling {file:bold} {red}(debug){reset} ..");
+//! ```
+//!
+//! `dedent,` before the string strips the indentation its lines have in common (and a leading newline), so long text can be indented with the code:
+//! ```
+//! # use comat::cformat;
+//! let help = cformat!(dedent, "
+//!     {bold}usage{reset}: comat [options]
+//!       -h  show this
+//! ");
+//! assert_eq!(help, "\x1b[1musage\x1b[0m: comat [options]\n  -h  show this\n");
+//! ```
+//!
+//! `tags,` before the string uses `<token>` (or `<bold,red>`) in place of `{token}`, and `</>` for `{reset}`, leaving the braces to the format string,
+//! for strings with a lot of them, like JSON. `<<` is a `<`, and tags that are not tokens are left as they are:
+//! ```
+//! # use comat::cformat;
+//! let name = "comat";
+//! let json = cformat!(tags, r#"{{"name": "<green>{name}</>", "ok": 1 <<3 }}"#);
+//! assert_eq!(json, "{\"name\": \"\x1b[0;34;32mcomat\x1b[0m\", \"ok\": 1 <3 }");
+//! ```
+//!
+//! `close_default,` ends the `{x:color}` blocks by only going back to the default colors (`\x1b[39;49m`), rather than with a reset,
+//! and `close_none,` ends them with nothing (`close_reset,` is the default, unless the [`comat.toml`](#comattoml) says otherwise):
+//! ```
+//! # use comat::cformat;
+//! let x = 1;
+//! assert_eq!(cformat!(close_default, "{x:red}!"), "\x1b[0;34;31m1\x1b[39;49m!");
+//! assert_eq!(cformat!(close_none, "{x:red}!"), "\x1b[0;34;31m1!");
+//! ```
+//!
+//! inside tmux, `tmux,` before the string passes the escapes tmux would swallow (like [links](https://gist.github.com/egmontkob/eb114294efbcd5adb1944c9f3cb5feda) and titles) through to the terminal:
+//! ```
+//! # use comat::cformat;
+//! let title = cformat!(tmux, "\x1b]0;building\x07{bold}building{reset}");
+//! assert_eq!(title, "\x1bPtmux;\x1b\x1b]0;building\x07\x1b\\\x1b[1mbuilding\x1b[0m");
+//! ```
+//! this only reaches the text written in the string. the escapes `{src(..)}`, `{notify(..)}` and `{image(..)}` make when printing are passed through whenever `TMUX` is set.
+//!
 //! ## colors
 //!
 //! `black` `red` `green` `yellow` `blue` `magenta` `cyan` `white` `default` `bold_black` `bold_red` `bold_green` `bold_yellow` `bold_blue` `bold_magenta` `bold_cyan` `bold_white`
 //! `bold_default` `on_black_bold` `on_red_bold` `on_green_bold` `on_yellow_bold` `on_blue_bold` `on_magenta_bold` `on_cyan_bold` `on_white_bold` `on_default_bold` `on_black` `on_red`
 //! `on_green` `on_yellow` `on_blue` `on_magenta` `on_cyan` `on_white` `on_default` `reset` `dim` `italic` `underline` `blinking` `hide` `strike` `bold`
-#![forbid(unsafe_code)]
+//!
+//! with the `aliases` feature, `{r}` `{g}` `{b}` `{y}` `{c}` `{m}` `{w}` and `{k}` are short for the colors (`k` being black), and `{*}` for bold.
+//! they take the place of format arguments named `r`, `g`, ..
+//!
+//! ### 256 colors
+//!
+//! the [xterm color names](https://www.ditig.com/256-colors-cheat-sheet) are also available, lowercased: `{orange1}`, `{deeppink3}`, `{grey50}`.
+//! `on_` gives the background (`{on_orange1}`), `gray` works in place of `grey`, and leaving off the number means `1` (`{lightgoldenrod}` is `{lightgoldenrod1}`),
+//! unless the name is also a [CSS name](#named-colors), which is then the CSS color (`{orange}` is not `{orange1}`).
+//!
+//! the grayscale ramp is also available by index, from `{gray(0)}` (nearly black) to `{gray(23)}` (nearly white).
+//!
+//! ### named colors
+//!
+//! the [CSS named colors](https://developer.mozilla.org/en-US/docs/Web/CSS/named-color) give truecolor: `{rebeccapurple}`, `{on_tomato}`.
+//! where a name is also a 256 color name, the CSS color wins (`{gold}`, `{orange}`, `{purple}`); the numbered 256 colors keep their names (`{gold1}`, `{orange1}`).
+//! a `css_` prefix also names the CSS one (`{css_orange}`).
+//!
+//! ### color functions
+//!
+//! `#rrggbb` (or `#rgb`) makes a truecolor, as does `hsl(hue, saturation%, lightness%)` from hue (in degrees), saturation and lightness.
+//! use `on_#rrggbb`, `on_hsl(..)` for the background.
+//!
+//! `auto_on_` sets any color as the background, with a black or white foreground, whichever is more readable: `{auto_on_#336699}`.
+//!
+//! ### gradients
+//!
+//! `{gradient(a, b, ..)}text{/gradient}` blends the color of each character of the text through the colors, which may be any of the above (except the basic colors).
+//! only text (and `{{`, `}}`) can be inside.
+//! ```
+//! # use comat::cprintln;
+//! cprintln!("{gradient(#ff0000,#0000ff)}BANNER TEXT{/gradient} v1.0");
+//! cprintln!("{gradient(gold1,hsl(300,100%,50%),cyan1)}~~~~~~~~~~~~~~~~{/gradient}");
+//! ```
+//! `{rainbow}text{/rainbow}` does the same through the hues of the rainbow.
+//! ```
+//! # use comat::cprintln;
+//! cprintln!("{rainbow}comat{/rainbow} v{}", env!("CARGO_PKG_VERSION"));
+//! ```
+//!
+//! ### dumber terminals
+//!
+//! the `ansi256` feature makes truecolor (including themes) downgrade to the nearest 256 color, and the `ansi16` feature downgrades everything to the 16 basic colors.
+//! ```
+//! # use comat::cprintln;
+//! let (api, db, cache) = ("up", "down", "up");
+//! cprintln!("api {api:hsl(0,60%,50%)} db {db:hsl(120,60%,50%)} cache {cache:hsl(240,60%,50%)}");
+//! # #[cfg(not(any(feature = "ansi256", feature = "ansi16")))]
+//! # assert_eq!(comat::comat!("{hsl(120,100%,25%)}"), "\x1b[38;2;0;128;0m");
+//! # #[cfg(all(feature = "ansi256", not(feature = "ansi16")))]
+//! # assert_eq!(comat::comat!("{hsl(120,100%,25%)}"), "\x1b[38;5;28m");
+//! # #[cfg(feature = "ansi16")]
+//! # assert_eq!(comat::comat!("{hsl(120,100%,25%)}"), "\x1b[32m");
+//! ```
+//!
+//! at runtime, [`capabilities`] (and [`stream_capabilities`], for stderr) tells what the terminal can show, and with [`set_auto`], the printing macros leave the escapes out when the one they print to has no colors
+//! (like when the output goes to a file, `NO_COLOR` is set, or `TERM` is `dumb`, though CI logs that show colors keep them).
+//! without these features, the printing macros also keep a 256 color version of the strings with truecolor, for when the terminal does not say it has truecolor
+//! (with `COLORTERM=truecolor` or `24bit`), as terminals without it can show truecolor as black.
+//!
+//! one call can choose for itself, whatever the features, with `@ansi16,`, `@ansi256,` or `@truecolor,` before the string, and `@plain,` leaves the escapes out.
+//! the printing macros then print it as it is, ignoring [`set_plain`], [`set_markers`] and [`set_variant`]:
+//! ```
+//! # use comat::*;
+//! cprintln!(@truecolor, "{gradient(#ff0000,#0000ff)}BANNER TEXT{/gradient}");
+//! assert_eq!(cformat!(@ansi256, "{#336699}ok"), "\x1b[38;5;60mok");
+//! assert_eq!(cformat!(@plain, "{bold}ok{reset}"), "ok");
+//! ```
+//!
+//! ### source links
+//!
+//! `{src(path, line)}` shows `path:line`, as a link to the file (`file://`, with the hostname) when the terminal has [hyperlinks](Capabilities::hyperlinks),
+//! so the places in diagnostics can be clicked. the path and line are Rust expressions, and it needs a macro that formats, like [`cprintln!`]:
+//! ```
+//! # use comat::cprintln;
+//! let (file, line) = ("src/main.rs", 12);
+//! cprintln!("{error}error{reset}: unused variable at {src(file, line)}");
+//! cprintln!("{hint}note{reset}: called from {src(file!(), line!())}");
+//! ```
+//!
+//! ### notifications
+//!
+//! `{notify(title, body)}` shows a desktop notification, in the terminals that [can](Capabilities::notifications), and nothing in the others,
+//! to say when something long is done. like `src`, the title and body are Rust expressions:
+//! ```
+//! # use comat::cprintln;
+//! let (crate_name, secs) = ("comat", 94);
+//! cprintln!("{success}built{reset} {crate_name} in {secs}s{notify(crate_name, \"build finished\")}");
+//! ```
+//!
+//! ### images
+//!
+//! with the `image` feature, `{image(path, width)}` shows the image at `path`, `width` cells wide, in the terminals that [can](Capabilities::images)
+//! (iTerm2 and wezterm, or kitty and ghostty, which only show PNGs), and nothing in the others. the path and width are Rust expressions:
+//! ```
+//! # #[cfg(feature = "image")] {
+//! # use comat::cprintln;
+//! let thumbnail = "assets/logo.png";
+//! cprintln!("{bold}logo{reset} {image(thumbnail, 8)}");
+//! # }
+//! ```
+//!
+//! ### raw escapes
+//!
+//! `{sgr(..)}` gives an SGR escape with those parameters as they are, for what comat does not have a name for:
+//! ```
+//! # use comat::comat;
+//! assert_eq!(comat!("{sgr(4:3)}curly{sgr(24)}"), "\x1b[4:3mcurly\x1b[24m");
+//! ```
+//!
+//! and `{esc(..)}` gives any other escape sequence, starting with the `ESC`. inside it, `\e` is an `ESC`, `\a` a `BEL` and `\\` a `\`:
+//! ```
+//! # use comat::comat;
+//! let link = comat!(r"{esc(]8;;https://docs.rs\e\\)}docs{esc(]8;;\e\\)}");
+//! assert_eq!(link, "\x1b]8;;https://docs.rs\x1b\\docs\x1b]8;;\x1b\\");
+//! ```
+//!
+//! ### semantic tokens
+//!
+//! these name *what* the text is, rather than how it looks, so the palette can be changed in one place.
+//!
+//! | token | default |
+//! | --- | --- |
+//! | `error` | `bold_red` |
+//! | `warn` | `bold_yellow` |
+//! | `info` | `bold_blue` |
+//! | `success` | `bold_green` |
+//! | `hint` | `cyan` |
+//! | `timestamp` | `dim` |
+//!
+//! ```
+//! # use comat::cformat;
+//! let path = "src/main.rs";
+//! assert_eq!(cformat!("{error}oh no{reset}"), cformat!("{bold_red}oh no{reset}"));
+//! assert_eq!(cformat!("{path:hint}"), cformat!("{path:cyan}"));
+//! ```
+//!
+//! ### themes
+//!
+//! setting `COMAT_THEME` at build time remaps the palette and the semantic tokens during expansion.
+//! it may be the name of a builtin theme (`default`, `solarized`), or a path (relative to the crate root) to a theme file:
+//! ```toml
+//! # raw SGR parameters
+//! red = "1;38;5;160"
+//! # or other tokens
+//! error = "red,underline"
+//! ```
+//! as cargo does not know about the variable, add `println!("cargo:rerun-if-env-changed=COMAT_THEME")` to your `build.rs` to rebuild when it changes.
+//!
+//! for color blind users, `deuteranopia` and `protanopia` (red and green look alike) and `tritanopia` (blue and yellow look alike)
+//! change the semantic tokens to colors they can tell apart, like a vermillion `error` and a blue `success`.
+//! the plain colors are left alone, so these work best with strings that use the semantic tokens.
+//! `high_contrast` uses only bold and bright colors, with nothing dim, for low vision or washed out screens.
+//!
+//! ### `comat.toml`
+//!
+//! for larger projects, a `comat.toml` next to your `Cargo.toml` (or in any directory above it, such as the workspace root) configures every comat string in the crate:
+//! ```toml
+//! # a builtin theme, or a path relative to this file. `COMAT_THEME` takes precedence.
+//! theme = "solarized"
+//! # how {x:color} blocks end: "reset" (the default), "default" (the default colors, with 39;49) or "none"
+//! close = "default"
+//! # other themes for the printing macros, to switch between at runtime with comat::set_variant(1), (2), ..
+//! variants = ["high_contrast", "deuteranopia"]
+//!
+//! [tokens]
+//! # new tokens, or overrides for existing ones
+//! err = "red,bold"
+//! path = 'cyan,underline'
+//! orange = "38;5;208"
+//! ```
+//! it is read with a small toml reader, so only strings (`"basic"`, with escapes, or `'literal'`), arrays of them, `[tables]` and comments are understood.
+//! the printing, formatting and writing macros make the crate rebuild when the files change.
+//!
+//! tokens for some calls can also be defined in code, with [`comat_alias!`].
+//!
+//! ## width
+//!
+//! escape sequences take no space on the terminal, so `str::len` is no good for aligning colored text.
+//! [`visible_width`] counts what is actually drawn (a column for every `char`, so not wide or combining ones), and [`cwidth!`] does it at compile time:
+//! ```
+//! # use comat::*;
+//! let tag = cformat!("{bold_green}ok{reset}");
+//! assert_eq!(visible_width(&tag), 2);
+//! assert_eq!(cwidth!("{bold_green}ok{reset}"), 2);
+//! ```
+//!
+//! to fit colored text to a width, [`cwrap!`] (or [`wrap`]) wraps it at spaces, carrying the colors over to the next line.
+//! [`cprefixed!`] (or [`prefixed`]) puts a prefix in front of every line, for quoting output.
+//! [`cbox!`] (or [`boxed`]) draws a box around some text,
+//! [`cbar!`] (or [`bar`]) draws a progress bar, and [`cspinner!`] (or [`spinner`]) a spinner,
+//! [`cstatus_set!`] (or [`set_status`]) keeps a status line at the bottom of the terminal,
+//! [`cclear!`] (or [`clear_screen`]) and [`cclear_line!`] (or [`clear_line`]) clear the terminal, and the line, to draw it again, [`cmoveto!`] (or [`move_to`]) moves the cursor,
+//! [`cterm_reset!`] (or [`reset_terminal`]) puts the terminal back the way it started, after a panic,
+//! [`cask!`] (or [`ask`]) asks for a line of input, [`cconfirm!`] (or [`confirm`]) for a yes or no, and [`cpassword!`] (or [`password`]) for a password,
+//! [`ccolumns!`] (or [`columns`]) lines up a table,
+//! [`cdiff!`] (or [`diff`]) shows how two texts differ,
+//! [`chexdump!`] (or [`hexdump`]) shows some bytes,
+//! [`cjson!`] (or [`json`]) pretty prints json (walking a `serde_json::Value`, with the `serde_json` feature),
+//! [`report`] shows an error and its causes,
+//! with the `golden` feature [`golden::check`](https://docs.rs/comat/latest/comat/golden/fn.check.html) compares output to a checked in file,
+//! and with the `highlight` feature [`ccode!`](https://docs.rs/comat/latest/comat/macro.ccode.html) highlights a snippet of code.
+#![cfg_attr(not(feature = "std"), no_std)]
+// the windows console is only set up through its API
+#![cfg_attr(not(windows), forbid(unsafe_code))]
+#![cfg_attr(windows, deny(unsafe_code))]
 #![warn(clippy::pedantic, clippy::dbg_macro, missing_docs)]
-use proc_macro::TokenStream;
-use quote::{quote, ToTokens, TokenStreamExt};
-use syn::{parse::Parse, parse_macro_input, punctuated::Punctuated, Expr, Result, Token};
-
-mod cfstr;
-use cfstr::CFStr;
-
-#[proc_macro]
-/// Macro that simply modifies the format string to have colors.
-/// Mostly for testing. Use [`cformat_args!`] instead where possible.
-pub fn comat(input: TokenStream) -> TokenStream {
-    let str = parse_macro_input!(input as CFStr);
-    str.to_token_stream().into()
-}
-
-struct One {
-    cfstr: CFStr,
-    args: Punctuated<Expr, Token![,]>,
-}
-
-impl Parse for One {
-    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
-        let cfstr = input.parse::<CFStr>()?;
-        let _ = input.parse::<Token![,]>();
-        Ok(Self {
-            cfstr,
-            args: Punctuated::<Expr, Token![,]>::parse_terminated(input)?,
-        })
-    }
-}
-
-impl ToTokens for One {
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        self.cfstr.to_tokens(tokens);
-        tokens.append(proc_macro2::Punct::new(',', proc_macro2::Spacing::Alone));
-        self.args.to_tokens(tokens);
-    }
-}
-
-// NOTE: many of these can be made as decl macros, but decl macros can't be exported from proc macro crates yet.
-
-#[proc_macro]
-/// Print text, colorfully, to stdout, with a newline.
-///
-/// See also [`println`].
-/// ```
-/// # use comat::*;
-/// let magic = 4;
-/// cprintln!("{red}look its red{reset}! {bold_blue}{magic}{reset} is the magic number!");
-/// ```
-pub fn cprintln(input: TokenStream) -> TokenStream {
-    let f = parse_macro_input!(input as One);
-    quote! { println!(#f) }.into()
-}
-
-#[proc_macro]
-/// Print text, colorfully, to stdout, without a newline.
-///
-/// See also [`print`].
-/// ```
-/// # use comat::*;
-/// cprint!("{yellow}i am a warning. {reset}why do you dislike me?");
-/// ```
-pub fn cprint(input: TokenStream) -> TokenStream {
-    let f = parse_macro_input!(input as One);
-    quote! { print!(#f) }.into()
-}
-
-#[proc_macro]
-/// Format text, colorfully.
-///
-/// See also [`format`].
-/// ```
-/// # use comat::*;
-/// let favorite_thing = "teddy bears";
-/// let message = cformat!("the {red}bogeymen{reset} will get your {favorite_thing:underline}");
-/// # assert_eq!(message, "the \x1b[0;34;31mbogeymen\x1b[0m will get your \x1b[0m\x1b[24mteddy bears\x1b[0m");
-/// ```
-pub fn cformat(input: TokenStream) -> TokenStream {
-    let f = parse_macro_input!(input as One);
-    quote! { format!(#f) }.into()
-}
-
-#[proc_macro]
-/// Produce [`fmt::Arguments`](std::fmt::Arguments). Sometimes functions take these.
-///
-/// See also [`format_args`].
-/// ```
-/// # use comat::*;
-/// let args = cformat_args!("{bold_red}fatal error. {reset}killing {blue}everything{reset}");
-/// // NOTE: do not do this. instead use cprintln.
-/// println!("{}", args);
-pub fn cformat_args(input: TokenStream) -> TokenStream {
-    let f = parse_macro_input!(input as One);
-    quote! { format_args!(#f) }.into()
-}
-/// Colorfully panic.
-///
-/// See also [`panic`].
-/// ```should_panic
-/// # use comat::cpanic;
-/// cpanic!("why is the bound {red}bad");
-/// ```
-#[proc_macro]
-pub fn cpanic(input: TokenStream) -> TokenStream {
-    let f = parse_macro_input!(input as One);
-    quote! { panic!(#f) }.into()
-}
-
-struct Two {
-    a: Expr,
-    cfstr: CFStr,
-    args: Punctuated<Expr, Token![,]>,
-}
-
-impl Parse for Two {
-    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
-        let a = input.parse::<Expr>()?;
-        input.parse::<Token![,]>()?;
-        let cfstr = input.parse::<CFStr>()?;
-        let _ = input.parse::<Token![,]>();
-        Ok(Self {
-            a,
-            cfstr,
-            args: Punctuated::<Expr, Token![,]>::parse_terminated(input)?,
-        })
-    }
-}
-
-impl ToTokens for Two {
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        self.a.to_tokens(tokens);
-        tokens.append(proc_macro2::Punct::new(',', proc_macro2::Spacing::Alone));
-        self.cfstr.to_tokens(tokens);
-        tokens.append(proc_macro2::Punct::new(',', proc_macro2::Spacing::Alone));
-        self.args.to_tokens(tokens);
-    }
-}
-
-#[proc_macro]
-/// Write to a buffer colorfully, with no newline.
-///
-/// See also [`write`]
-/// ```
-/// # use comat::cwrite;
-/// use std::io::Write;
-/// let mut buf = vec![];
-/// cwrite!(buf, "{green}omg there's going to be ansi sequences in a {black}Vec<u8>{reset}!");
-/// # assert_eq!(buf, [27, 91, 48, 59, 51, 52, 59, 51, 50, 109, 111, 109, 103, 32, 116, 104, 101, 114, 101, 39, 115, 32, 103, 111, 105, 110, 103, 32, 116, 111, 32, 98, 101, 32, 97, 110, 115, 105, 32, 115, 101, 113, 117, 101, 110, 99, 101, 115, 32, 105, 110, 32, 97, 32, 27, 91, 48, 59, 51, 52, 59, 51, 48, 109, 86, 101, 99, 60, 117, 56, 62, 27, 91, 48, 109, 33]);
-/// ```
-pub fn cwrite(input: TokenStream) -> TokenStream {
-    let f = parse_macro_input!(input as Two);
-    quote! { write!(#f) }.into()
-}
+pub use comat_macros::*;
+#[cfg(feature = "parser")]
+pub use comat_parser as parser;
 
-#[proc_macro]
-/// Write to a buffer colorfully, with newline.
-///
-/// See also [`writeln`]
-/// ```
-/// # use comat::cwriteln;
-/// use std::io::Write;
-/// let mut buf = vec![];
-/// cwriteln!(buf, "hey look: {strike}strike'd text{reset}!");
-/// # assert_eq!(buf, [104, 101, 121, 32, 108, 111, 111, 107, 58, 32, 27, 91, 57, 109, 115, 116, 114, 105, 107, 101, 39, 100, 32, 116, 101, 120, 116, 27, 91, 48, 109, 33, 10]);
-/// ```
-pub fn cwriteln(input: TokenStream) -> TokenStream {
-    let f = parse_macro_input!(input as Two);
-    quote! { writeln!(#f) }.into()
-}
+pub mod format;
+pub mod spinner;
+mod style;
+pub use style::{Color, Style};
+mod width;
+pub use width::{pad, visible_width};
+mod console;
+pub use console::{enable_windows_ansi, Consoles};
+mod render;
+pub use render::{markers, plain, set_markers, set_plain, set_variant, variant};
+#[cfg(feature = "std")]
+pub use render::{set_auto, strip, Render};
+#[cfg(feature = "std")]
+mod capabilities;
+#[cfg(feature = "std")]
+pub use capabilities::{
+    capabilities, stream_capabilities, Capabilities, Colors, Images, Notifications, Stream,
+};
+#[cfg(feature = "std")]
+mod location;
+#[cfg(feature = "std")]
+pub use location::Location;
+#[cfg(feature = "std")]
+mod notification;
+#[cfg(feature = "std")]
+pub use notification::Notification;
+#[cfg(feature = "image")]
+mod image;
+#[cfg(feature = "image")]
+pub use image::Image;
+#[cfg(feature = "std")]
+mod wrap;
+#[cfg(feature = "std")]
+pub use wrap::wrap;
+#[cfg(feature = "std")]
+mod prefix;
+#[cfg(feature = "std")]
+pub use prefix::prefixed;
+#[cfg(feature = "std")]
+mod boxed;
+#[cfg(feature = "std")]
+pub use boxed::{boxed, Border};
+#[cfg(feature = "std")]
+mod bar;
+#[cfg(feature = "std")]
+pub use bar::bar;
+#[cfg(feature = "std")]
+mod status;
+#[cfg(feature = "std")]
+pub use status::{clear_status, set_status};
+#[cfg(feature = "std")]
+mod screen;
+#[cfg(feature = "std")]
+pub use screen::{clear_line, clear_screen, move_to, reset_terminal};
+#[cfg(feature = "std")]
+mod prompt;
+#[cfg(feature = "std")]
+pub use prompt::{ask, confirm, password};
+#[cfg(feature = "std")]
+mod columns;
+#[cfg(feature = "std")]
+pub use columns::columns;
+#[cfg(feature = "std")]
+mod diff;
+#[cfg(feature = "std")]
+pub use diff::diff;
+#[cfg(feature = "std")]
+mod hexdump;
+#[cfg(feature = "std")]
+pub use hexdump::{hexdump, Hexdump};
+#[cfg(feature = "std")]
+mod json;
+#[cfg(feature = "std")]
+pub use json::{json, Json};
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub use json::{Lex as __JsonLex, Source as __JsonSource, Walk as __JsonWalk};
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "std")]
+pub use report::report;
+#[cfg(feature = "std")]
+mod throttle;
+#[cfg(feature = "std")]
+pub use throttle::Throttle;
+#[cfg(feature = "std")]
+mod timestamp;
+#[cfg(feature = "std")]
+pub use timestamp::{rfc3339, Timestamp};
+#[cfg(feature = "parser")]
+mod dye;
+#[cfg(feature = "parser")]
+pub use dye::{dye, dye_translated, dye_with};
+#[cfg(feature = "highlight")]
+mod code;
+#[cfg(feature = "golden")]
+pub mod golden;
+#[cfg(feature = "highlight")]
+pub use code::{Code, Lang};
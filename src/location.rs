@@ -0,0 +1,67 @@
+use std::{
+    fmt::{self, Write},
+    path::Path,
+    sync::OnceLock,
+};
+
+use crate::capabilities::write_escape;
+
+/// A place in a file, shown as `path:line`, which is a link to the file when the terminal has [hyperlinks](crate::Capabilities::hyperlinks).
+/// Inside tmux (when `TMUX` is set), the link is passed through to the terminal.
+///
+/// `{src(path, line)}` makes these.
+/// ```
+/// # use comat::*;
+/// let at = Location("src/main.rs", 12);
+/// if !capabilities().hyperlinks {
+///     assert_eq!(at.to_string(), "src/main.rs:12");
+/// }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Location<P, L>(pub P, pub L);
+
+impl<P: AsRef<Path>, L: fmt::Display> fmt::Display for Location<P, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.0.as_ref();
+        if !crate::capabilities().hyperlinks {
+            return write!(f, "{}:{}", path.display(), self.1);
+        }
+        let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        // there is no standard way to link to a line, so the link is to the file
+        write_escape(
+            f,
+            format_args!("\x1b]8;;file://{}{}\x1b\\", hostname(), url_path(&absolute)),
+        )?;
+        write!(f, "{}:{}", path.display(), self.1)?;
+        write_escape(f, format_args!("\x1b]8;;\x1b\\"))
+    }
+}
+
+/// The name of this machine, for `file://` links, which terminals check to not open files on another machine (like over ssh).
+fn hostname() -> &'static str {
+    static HOSTNAME: OnceLock<String> = OnceLock::new();
+    HOSTNAME.get_or_init(|| {
+        std::env::var("HOSTNAME")
+            .or_else(|_| std::env::var("COMPUTERNAME"))
+            .or_else(|_| std::fs::read_to_string("/etc/hostname"))
+            .map(|name| name.trim().to_string())
+            .unwrap_or_default()
+    })
+}
+
+/// `path` as the path of a URL: with `/`s, starting with one, and the bytes that cannot be in it percent encoded.
+fn url_path(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut out = String::with_capacity(path.len() + 1);
+    if !path.starts_with('/') {
+        out.push('/');
+    }
+    for b in path.bytes() {
+        if b.is_ascii_alphanumeric() || b"/-._~:".contains(&b) {
+            out.push(char::from(b));
+        } else {
+            let _ = write!(out, "%{b:02X}");
+        }
+    }
+    out
+}
@@ -0,0 +1,35 @@
+use std::fmt;
+
+use crate::{capabilities::write_escape, Notifications};
+
+/// A desktop notification, with a title and a body, for the terminals that [show them](crate::Capabilities::notifications).
+/// In the others it shows nothing. Inside tmux (when `TMUX` is set), it is passed through to the terminal.
+///
+/// `{notify(title, body)}` makes these.
+/// ```
+/// # use comat::*;
+/// print!("{}", Notification("comat", "build finished"));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Notification<T, B>(pub T, pub B);
+
+impl<T: fmt::Display, B: fmt::Display> fmt::Display for Notification<T, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(notifications) = crate::capabilities().notifications else {
+            return Ok(());
+        };
+        // an escape (or bell) would end the sequence early, and a `;` would end osc 777's title
+        let clean = |text: String| text.replace(|c: char| c.is_control(), " ");
+        let (title, body) = (clean(self.0.to_string()), clean(self.1.to_string()));
+        match notifications {
+            Notifications::Osc9 if body.is_empty() => {
+                write_escape(f, format_args!("\x1b]9;{title}\x1b\\"))
+            }
+            Notifications::Osc9 => write_escape(f, format_args!("\x1b]9;{title}: {body}\x1b\\")),
+            Notifications::Osc777 => write_escape(
+                f,
+                format_args!("\x1b]777;notify;{};{body}\x1b\\", title.replace(';', ",")),
+            ),
+        }
+    }
+}
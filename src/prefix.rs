@@ -0,0 +1,22 @@
+use crate::wrap::track;
+
+/// Put `prefix` in front of every line of `s`.
+///
+/// The style a line of `s` was left with is re-applied after the prefix, so the prefix can reset freely.
+/// ```
+/// # use comat::*;
+/// let quoted = prefixed(comat!("{dim}| {reset}"), "cargo build\ncargo test\n");
+/// assert_eq!(quoted, "\x1b[2m| \x1b[0mcargo build\n\x1b[2m| \x1b[0mcargo test\n");
+/// ```
+#[must_use]
+pub fn prefixed(prefix: &str, s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + prefix.len());
+    let mut active = String::new();
+    for line in s.split_inclusive('\n') {
+        out.push_str(prefix);
+        out.push_str(&active);
+        out.push_str(line);
+        track(&mut active, line);
+    }
+    out
+}
@@ -0,0 +1,129 @@
+use std::{
+    io::{self, BufRead, IsTerminal, Write},
+    process::Command,
+};
+
+use crate::{strip, Render};
+
+/// Show `prompt` (with its colors, unless the output is [plain](crate::plain)), and read a line from stdin, without the whitespace around it.
+/// [`cask!`](crate::cask) formats the prompt, colorfully, and asks.
+///
+/// # Errors
+///
+/// when writing or reading fails, or stdin is closed before a line ([`UnexpectedEof`](io::ErrorKind::UnexpectedEof))
+/// ```no_run
+/// # use comat::*;
+/// let name = ask(&cformat!("{bold}project name{reset}: "))?;
+/// cprintln!("{green}created{reset} {name}");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn ask(prompt: &str) -> io::Result<String> {
+    read(prompt).map(|line| line.trim().to_string())
+}
+
+/// Show `prompt`, and read whether the answer was yes (`y` or `yes`) or no (`n` or `no`), asking again for anything else.
+///
+/// An empty answer is the default of the prompt, when it has `[Y/n]` (yes) or `[y/N]` (no) in it, and is asked again when it does not.
+/// When stdin is closed, or cannot be read, the answer is the default, or no.
+/// [`cconfirm!`](crate::cconfirm) formats the prompt, colorfully, and asks.
+/// ```no_run
+/// # use comat::*;
+/// if confirm(&cformat!("{yellow}overwrite config.toml?{reset} [y/N] ")) {
+///     cprintln!("{green}overwrote{reset} config.toml");
+/// }
+/// ```
+#[must_use]
+pub fn confirm(prompt: &str) -> bool {
+    let plain = strip(prompt);
+    let default = if plain.contains("[Y/n]") {
+        Some(true)
+    } else if plain.contains("[y/N]") {
+        Some(false)
+    } else {
+        None
+    };
+    loop {
+        let Ok(answer) = ask(prompt) else {
+            return default.unwrap_or(false);
+        };
+        match &*answer.to_lowercase() {
+            "y" | "yes" => return true,
+            "n" | "no" => return false,
+            "" => {
+                if let Some(default) = default {
+                    return default;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Show `prompt`, and read a line from stdin, without showing what is typed, for passwords and tokens.
+/// Only the line break is taken off the end, as spaces can be a part of a password.
+///
+/// The terminal stops showing the input with `stty -echo`, and shows it again after, even on a panic.
+/// [`cpassword!`](crate::cpassword) formats the prompt, colorfully, and asks.
+///
+/// # Errors
+///
+/// when writing or reading fails, stdin is closed before a line ([`UnexpectedEof`](io::ErrorKind::UnexpectedEof)),
+/// or stdin is a terminal that cannot stop showing the input, like on windows ([`Unsupported`](io::ErrorKind::Unsupported)),
+/// as showing a password is worse than not asking for it
+/// ```no_run
+/// # use comat::*;
+/// let token = password(&cformat!("{dim}token:{reset} "))?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn password(prompt: &str) -> io::Result<String> {
+    // input that is not from a terminal is not shown anyway
+    let hidden = io::stdin().is_terminal().then(Hidden::new).transpose()?;
+    let line = read(prompt);
+    if hidden.is_some() {
+        // the enter was not shown either
+        println!();
+    }
+    let mut line = line?;
+    line.truncate(line.trim_end_matches(['\n', '\r']).len());
+    Ok(line)
+}
+
+/// The terminal not showing what is typed, until this is dropped.
+struct Hidden;
+
+impl Hidden {
+    fn new() -> io::Result<Self> {
+        stty("-echo")?;
+        Ok(Self)
+    }
+}
+
+impl Drop for Hidden {
+    fn drop(&mut self) {
+        let _ = stty("echo");
+    }
+}
+
+/// Change a setting of the terminal on stdin.
+fn stty(setting: &str) -> io::Result<()> {
+    if cfg!(windows) {
+        return Err(io::ErrorKind::Unsupported.into());
+    }
+    if Command::new("stty").arg(setting).status()?.success() {
+        Ok(())
+    } else {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// Show `prompt`, and read a line from stdin, as it is.
+fn read(prompt: &str) -> io::Result<String> {
+    let mut stdout = io::stdout().lock();
+    write!(stdout, "{}", Render(prompt, crate::Stream::Stdout))?;
+    stdout.flush()?;
+    let mut line = String::new();
+    if io::stdin().lock().read_line(&mut line)? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(line)
+}
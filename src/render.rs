@@ -0,0 +1,160 @@
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+
+/// Whether the printing macros are plain: 0 for colored, 1 for [plain](set_plain), and 2 to [follow the terminal](set_auto).
+static PLAIN: AtomicU8 = AtomicU8::new(0);
+/// The streams that cannot show escapes, as [`enable_windows_ansi`](fn@crate::enable_windows_ansi) found: 1 for stdout, 2 for stderr.
+static UNSUPPORTED: AtomicU8 = AtomicU8::new(0);
+static MARKERS: AtomicBool = AtomicBool::new(false);
+static VARIANT: AtomicUsize = AtomicUsize::new(0);
+
+/// Make the printing macros ([`cprintln!`](crate::cprintln), [`cprint!`](crate::cprint), ..) leave out every escape sequence.
+///
+/// [`enable_windows_ansi`](fn@crate::enable_windows_ansi) does this for each stream whose console cannot show them, which this does not undo.
+/// The macros that give a string, like [`cformat!`](crate::cformat), are not affected.
+/// ```
+/// # use comat::*;
+/// set_plain(true);
+/// cprintln!("{bold_green}done{reset}"); // done
+/// # set_plain(false);
+/// ```
+pub fn set_plain(plain: bool) {
+    PLAIN.store(u8::from(plain), Ordering::Relaxed);
+}
+
+/// Make the printing macros [plain](set_plain) when the stream they print to has no [colors](crate::Colors::None),
+/// as [`stream_capabilities`](crate::stream_capabilities) finds (like when it goes to a file, `NO_COLOR` is set, or `TERM` is `dumb`),
+/// and colored otherwise. `set_auto(false)`, or [`set_plain`], keeps them one way again.
+///
+/// This is off unless called, so output piped through `less -R` keeps its colors.
+/// ```
+/// # use comat::*;
+/// set_auto(true);
+/// cprintln!("{bold_green}done{reset}"); // done, in green only on a terminal
+/// # set_auto(false);
+/// ```
+#[cfg(feature = "std")]
+pub fn set_auto(auto: bool) {
+    PLAIN.store(if auto { 2 } else { 0 }, Ordering::Relaxed);
+}
+
+/// Whether the printing macros to stdout are [plain](set_plain), which can depend on the terminal with [`set_auto`].
+#[must_use]
+pub fn plain() -> bool {
+    #[cfg(feature = "std")]
+    return plain_to(crate::Stream::Stdout);
+    #[cfg(not(feature = "std"))]
+    return PLAIN.load(Ordering::Relaxed) == 1 || UNSUPPORTED.load(Ordering::Relaxed) & 1 != 0;
+}
+
+/// Whether the printing macros to `stream` are [plain](set_plain).
+#[cfg(feature = "std")]
+fn plain_to(stream: crate::Stream) -> bool {
+    let bit = match stream {
+        crate::Stream::Stdout => 1,
+        crate::Stream::Stderr => 2,
+    };
+    if UNSUPPORTED.load(Ordering::Relaxed) & bit != 0 {
+        return true;
+    }
+    match PLAIN.load(Ordering::Relaxed) {
+        2 => crate::stream_capabilities(stream).colors == crate::Colors::None,
+        n => n == 1,
+    }
+}
+
+/// Make the printing macros to the streams that are not set up for them plain, leaving the rest as [`set_plain`] has them.
+pub(crate) fn set_unsupported(consoles: crate::Consoles) {
+    UNSUPPORTED.store(
+        u8::from(!consoles.stdout) | u8::from(!consoles.stderr) << 1,
+        Ordering::Relaxed,
+    );
+}
+
+/// Make the printing macros put text markers where the semantic tokens are, like `[error] ` for `{error}`, and leave out every escape sequence, for screen readers.
+///
+/// Screen readers do not read colors, so what they meant would be lost without these.
+/// The macros keep a version of each string with markers, so this costs nothing when printing.
+/// ```
+/// # use comat::*;
+/// set_markers(true);
+/// cprintln!("{error}error{reset}: {bold}Cargo.toml{reset} not found"); // [error] error: Cargo.toml not found
+/// # set_markers(false);
+/// ```
+pub fn set_markers(markers: bool) {
+    MARKERS.store(markers, Ordering::Relaxed);
+}
+
+/// Whether the printing macros use [markers](set_markers).
+#[must_use]
+pub fn markers() -> bool {
+    MARKERS.load(Ordering::Relaxed)
+}
+
+/// Make the printing macros use the `n`th theme of the [`comat.toml`](crate#comattoml)'s `variants` (from 1), or the usual theme with 0.
+///
+/// Every string is made with each of these themes when it is compiled, and the printing macros only choose which text to show, so switching costs nothing when printing.
+/// ```toml
+/// # comat.toml
+/// variants = ["high_contrast", "deuteranopia"]
+/// ```
+/// ```
+/// # use comat::*;
+/// let high_contrast = std::env::args().any(|arg| arg == "--high-contrast");
+/// set_variant(usize::from(high_contrast));
+/// cprintln!("{success}done{reset}");
+/// # set_variant(0);
+/// ```
+pub fn set_variant(n: usize) {
+    VARIANT.store(n, Ordering::Relaxed);
+}
+
+/// Which [variant](set_variant) the printing macros use.
+#[must_use]
+pub fn variant() -> usize {
+    VARIANT.load(Ordering::Relaxed)
+}
+
+/// Remove the escape sequences from `s`.
+/// ```
+/// # use comat::*;
+/// assert_eq!(strip(&cformat!("{bold}[{reset}{green}ok{reset}{bold}]{reset}")), "[ok]");
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+pub fn strip(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = String::with_capacity(s.len());
+    let mut i = 0;
+    while let Some(start) = s[i..].find('\x1b') {
+        out.push_str(&s[i..i + start]);
+        i = crate::width::escape_end(bytes, i + start + 1);
+    }
+    out.push_str(&s[i..]);
+    out
+}
+
+/// Shows some text the way the printing macros to a [stream](crate::Stream) should: without escapes, when [plain](set_plain) (or with [markers](set_markers)).
+///
+/// The printing macros wrap their text in this.
+/// ```
+/// # use comat::*;
+/// let ok = cformat!("{green}ok{reset}");
+/// set_plain(false);
+/// assert_eq!(Render(&ok, Stream::Stdout).to_string(), ok);
+/// set_plain(true);
+/// assert_eq!(Render(&ok, Stream::Stdout).to_string(), "ok");
+/// # set_plain(false);
+/// ```
+#[cfg(feature = "std")]
+pub struct Render<T>(pub T, pub crate::Stream);
+
+#[cfg(feature = "std")]
+impl<T: core::fmt::Display> core::fmt::Display for Render<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if plain_to(self.1) || markers() {
+            f.write_str(&strip(&self.0.to_string()))
+        } else {
+            self.0.fmt(f)
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::comat;
+use std::{error::Error, fmt::Write};
+
+const ERROR: &str = comat!("{error}");
+const CAUSE: &str = comat!("{warn}");
+const DIM: &str = comat!("{dim}");
+const ROOT: &str = comat!("{bold}");
+const RESET: &str = comat!("{reset}");
+
+/// Show an error and the chain of errors that caused it (its [`source`](Error::source)s), colorfully.
+///
+/// The error is tagged in the `error` style, each cause gets a bullet in the `warn` style, with its position dimmed,
+/// and the root cause is in bold. Lines after the first line of a message are indented to line up.
+/// ```
+/// # use comat::*;
+/// use std::{error::Error, fmt, num::ParseIntError};
+/// #[derive(Debug)]
+/// struct Config(ParseIntError);
+/// impl fmt::Display for Config {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         f.write_str("couldnt read the config")
+///     }
+/// }
+/// impl Error for Config {
+///     fn source(&self) -> Option<&(dyn Error + 'static)> {
+///         Some(&self.0)
+///     }
+/// }
+/// let error = Config("x".parse::<u8>().unwrap_err());
+/// assert_eq!(
+///     report(&error),
+///     cformat!("{error}error{reset}: couldnt read the config\n{warn}•{reset} {dim}0{reset} {bold}invalid digit found in string{reset}"),
+/// );
+/// ```
+#[must_use]
+pub fn report(error: &dyn Error) -> String {
+    let mut out = format!("{ERROR}error{RESET}: ");
+    indent(&mut out, &error.to_string(), "       ");
+    let mut source = error.source();
+    let mut n = 0;
+    while let Some(cause) = source {
+        source = cause.source();
+        let _ = write!(out, "\n{CAUSE}•{RESET} {DIM}{n}{RESET} ");
+        let pad = " ".repeat(n.to_string().len() + 3);
+        if source.is_none() {
+            out.push_str(ROOT);
+            indent(&mut out, &cause.to_string(), &pad);
+            out.push_str(RESET);
+        } else {
+            indent(&mut out, &cause.to_string(), &pad);
+        }
+        n += 1;
+    }
+    out
+}
+
+/// Push `message`, with `pad` before every line but the first.
+fn indent(out: &mut String, message: &str, pad: &str) {
+    for (n, line) in message.lines().enumerate() {
+        if n != 0 {
+            out.push('\n');
+            out.push_str(pad);
+        }
+        out.push_str(line);
+    }
+}
@@ -0,0 +1,71 @@
+use std::io::Write;
+
+/// Clear the terminal, and put the cursor at the top left, like `clear` does.
+///
+/// This does nothing when the output is [plain](crate::plain), as a file would only get the escapes.
+/// [`cclear!`](crate::cclear) does the same.
+/// ```
+/// # use comat::*;
+/// clear_screen();
+/// cprintln!("{bold}dashboard{reset}");
+/// ```
+pub fn clear_screen() {
+    write("\x1b[2J\x1b[H");
+}
+
+/// Clear the line the cursor is on, and put it at the start, to draw the line again.
+///
+/// This does nothing when the output is [plain](crate::plain). [`cclear_line!`](crate::cclear_line) does the same.
+/// ```
+/// # use comat::*;
+/// cprint!("{dim}working{reset}");
+/// clear_line();
+/// cprintln!("{green}done{reset}");
+/// ```
+pub fn clear_line() {
+    write("\r\x1b[2K");
+}
+
+/// Put the cursor at a row and column, counting from 1 at the top left, to draw there.
+///
+/// This does nothing when the output is [plain](crate::plain). [`cmoveto!`](crate::cmoveto) does the same.
+/// ```
+/// # use comat::*;
+/// clear_screen();
+/// for (row, name) in ["cpu", "memory"].into_iter().enumerate() {
+///     move_to(row + 1, 1);
+///     cprint!("{bold}{name}{reset}");
+/// }
+/// ```
+pub fn move_to(row: usize, column: usize) {
+    write(&format!("\x1b[{row};{column}H"));
+}
+
+/// Put the terminal back the way it started, with a soft reset and a reset of the colors:
+/// the cursor is shown again, and the output scrolls the whole terminal, without a [status line](crate::set_status).
+/// Unlike a full reset (`\x1bc`), what is on the screen stays.
+///
+/// This is meant for recovering after a panic, so it can go in a panic hook.
+/// This does nothing when the output is [plain](crate::plain). [`cterm_reset!`](crate::cterm_reset) does the same.
+/// ```
+/// # use comat::*;
+/// let hook = std::panic::take_hook();
+/// std::panic::set_hook(Box::new(move |info| {
+///     reset_terminal();
+///     hook(info);
+/// }));
+/// ```
+pub fn reset_terminal() {
+    crate::status::forget();
+    write("\x1b[!p\x1b[0m");
+}
+
+/// Write some escapes to stdout, right away, unless it is plain.
+fn write(escapes: &str) {
+    if crate::plain() {
+        return;
+    }
+    let mut stdout = std::io::stdout().lock();
+    let _ = stdout.write_all(escapes.as_bytes());
+    let _ = stdout.flush();
+}
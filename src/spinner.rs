@@ -0,0 +1,31 @@
+//! Frames for spinners, to show something is happening, like [`cspinner!`](crate::cspinner) does.
+//!
+//! Each set loops, so the frame for a counter is the counter modulo its length, with [`frame`].
+//! ```
+//! # use comat::*;
+//! for tick in 0..3 {
+//!     cprint_flush!("\r{} working", spinner::frame(spinner::LINE, tick));
+//! }
+//! ```
+
+/// `⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏`, a dot going around in a braille cell.
+pub const BRAILLE: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// `⣾⣽⣻⢿⡿⣟⣯⣷`, a gap going around in a full braille cell.
+pub const DOTS: &[&str] = &["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"];
+
+/// `-\|/`, a line turning, for terminals without the braille characters.
+pub const LINE: &[&str] = &["-", "\\", "|", "/"];
+
+/// `◜◝◞◟`, an arc going around.
+pub const ARC: &[&str] = &["◜", "◝", "◞", "◟"];
+
+/// The frame of `frames` for `tick`.
+/// ```
+/// # use comat::spinner::*;
+/// assert_eq!(frame(LINE, 5), "\\");
+/// ```
+#[must_use]
+pub const fn frame(frames: &[&'static str], tick: usize) -> &'static str {
+    frames[tick % frames.len()]
+}
@@ -0,0 +1,99 @@
+use std::{
+    fmt::Write as _,
+    io::{IsTerminal, Write},
+    sync::{Mutex, PoisonError},
+};
+
+use crate::Render;
+
+/// The rows of the terminal the status line was put at the bottom of, if there is one.
+static STATUS: Mutex<Option<u16>> = Mutex::new(None);
+
+/// Show `status` on the bottom line of the terminal, where it stays while the rest of the output scrolls above it,
+/// until [`clear_status`]. Setting it again replaces it.
+///
+/// This does nothing when stdout is not a terminal, is [plain](crate::set_plain), or its size is not known (from `LINES`, or `stty`).
+/// The size is found when the status is first set, so a terminal resized after needs it [cleared](clear_status) and set again.
+/// [`cstatus_set!`](crate::cstatus_set) formats the status, colorfully, and sets it.
+/// ```
+/// # use comat::*;
+/// set_status(&cformat!("{bold}building{reset} 3/8"));
+/// cprintln!("{green}compiled{reset} comat");
+/// clear_status();
+/// ```
+pub fn set_status(status: &str) {
+    let mut stdout = std::io::stdout().lock();
+    let mut shown = STATUS.lock().unwrap_or_else(PoisonError::into_inner);
+    if !stdout.is_terminal() || crate::plain() {
+        return;
+    }
+    let mut out = String::new();
+    let rows = if let Some(rows) = *shown {
+        rows
+    } else {
+        let Some(size) = size() else {
+            return;
+        };
+        // make room for the status, so the cursor is not left under the region that scrolls,
+        // then keep the scrolling above it. setting the region moves the cursor, so it is saved first
+        let _ = write!(out, "\n\x1b[1A\x1b7\x1b[1;{}r\x1b8", size - 1);
+        *shown = Some(size);
+        size
+    };
+    let _ = write!(
+        out,
+        "\x1b7\x1b[{rows};1H\x1b[2K{}\x1b[0m\x1b8",
+        Render(status, crate::Stream::Stdout)
+    );
+    let _ = stdout.write_all(out.as_bytes());
+    let _ = stdout.flush();
+}
+
+/// Take away the status line [`set_status`] put up, letting the output scroll the whole terminal again.
+///
+/// Call it before exiting, as the terminal keeps the region that scrolls. [`cstatus_clear!`](crate::cstatus_clear) does the same.
+/// When stdout has become [plain](crate::set_plain) since, the status is only forgotten, as no escapes are written.
+pub fn clear_status() {
+    let mut stdout = std::io::stdout().lock();
+    let Some(rows) = STATUS.lock().unwrap_or_else(PoisonError::into_inner).take() else {
+        return;
+    };
+    if crate::plain() {
+        return;
+    }
+    let _ = write!(stdout, "\x1b7\x1b[r\x1b[{rows};1H\x1b[2K\x1b8");
+    let _ = stdout.flush();
+}
+
+/// Forget the status line, when the terminal was reset, so the next one makes room for itself again.
+pub(crate) fn forget() {
+    STATUS.lock().unwrap_or_else(PoisonError::into_inner).take();
+}
+
+/// The number of rows of the terminal, if it has more than one.
+fn size() -> Option<u16> {
+    let rows = std::env::var("LINES")
+        .ok()
+        .and_then(|lines| lines.parse().ok())
+        .or_else(stty)?;
+    (rows > 1).then_some(rows)
+}
+
+/// The rows `stty size` says the terminal has.
+fn stty() -> Option<u16> {
+    if cfg!(windows) {
+        return None;
+    }
+    let tty = std::fs::File::open("/dev/tty").ok()?;
+    let out = std::process::Command::new("stty")
+        .arg("size")
+        .stdin(tty)
+        .output()
+        .ok()?;
+    String::from_utf8(out.stdout)
+        .ok()?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
@@ -0,0 +1,154 @@
+use core::fmt;
+
+/// A color, for a [`Style`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// One of the 16 basic colors: 0 to 7 are black, red, green, yellow, blue, magenta, cyan and white, and 8 to 15 their bright versions.
+    /// Anything past 15 is bright white, as 15 is.
+    Basic(u8),
+    /// A color of the xterm 256 color palette.
+    Fixed(u8),
+    /// A truecolor.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// Write the SGR parameters for this color, `base` being 30 for a foreground or 40 for a background.
+    fn write(self, f: &mut fmt::Formatter<'_>, base: u8) -> fmt::Result {
+        match self {
+            Self::Basic(n @ 0..=7) => write!(f, "{}", base + n),
+            Self::Basic(n) => write!(f, "{}", base + 60 + n.min(15) - 8),
+            Self::Fixed(n) => write!(f, "{};5;{n}", base + 8),
+            Self::Rgb(r, g, b) => write!(f, "{};2;{r};{g};{b}", base + 8),
+        }
+    }
+}
+
+/// A style, made with its builders or [`cstyle!`](crate::cstyle), that can be chosen at runtime, like by log level.
+///
+/// Its [`Display`](fmt::Display) writes its escape sequence (nothing, for the empty style),
+/// and `{arg:[style]}` shows a value in it.
+/// ```
+/// # use comat::*;
+/// let level = 2;
+/// let style = if level > 1 { Style::new().bold().fg(Color::Basic(1)) } else { Style::new() };
+/// assert_eq!(style.to_string(), "\x1b[1;31m");
+/// assert_eq!(cformat!("{level:[style]}"), "\x1b[0m\x1b[1;31m2\x1b[0m");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    /// bit `n` is set for the SGR parameter `n`
+    attributes: u16,
+    fg: Option<Color>,
+    bg: Option<Color>,
+}
+
+impl Style {
+    /// The empty style.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            attributes: 0,
+            fg: None,
+            bg: None,
+        }
+    }
+
+    /// This style, in the `color` foreground.
+    #[must_use]
+    pub const fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    /// This style, on the `color` background.
+    #[must_use]
+    pub const fn on(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    const fn with(mut self, param: u8) -> Self {
+        self.attributes |= 1 << param;
+        self
+    }
+
+    /// This style, bold.
+    #[must_use]
+    pub const fn bold(self) -> Self {
+        self.with(1)
+    }
+
+    /// This style, dimmed.
+    #[must_use]
+    pub const fn dim(self) -> Self {
+        self.with(2)
+    }
+
+    /// This style, in italics.
+    #[must_use]
+    pub const fn italic(self) -> Self {
+        self.with(3)
+    }
+
+    /// This style, underlined.
+    #[must_use]
+    pub const fn underline(self) -> Self {
+        self.with(4)
+    }
+
+    /// This style, blinking.
+    #[must_use]
+    pub const fn blink(self) -> Self {
+        self.with(5)
+    }
+
+    /// This style, with the foreground and background swapped.
+    #[must_use]
+    pub const fn reverse(self) -> Self {
+        self.with(7)
+    }
+
+    /// This style, hidden.
+    #[must_use]
+    pub const fn hidden(self) -> Self {
+        self.with(8)
+    }
+
+    /// This style, struck through.
+    #[must_use]
+    pub const fn strike(self) -> Self {
+        self.with(9)
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == Self::new() {
+            return Ok(());
+        }
+        f.write_str("\x1b[")?;
+        let mut first = true;
+        let mut separate = |f: &mut fmt::Formatter<'_>| {
+            if !core::mem::take(&mut first) {
+                f.write_str(";")?;
+            }
+            Ok(())
+        };
+        for param in 1..10 {
+            if self.attributes & (1 << param) != 0 {
+                separate(f)?;
+                write!(f, "{param}")?;
+            }
+        }
+        if let Some(fg) = self.fg {
+            separate(f)?;
+            fg.write(f, 30)?;
+        }
+        if let Some(bg) = self.bg {
+            separate(f)?;
+            bg.write(f, 40)?;
+        }
+        f.write_str("m")
+    }
+}
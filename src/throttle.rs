@@ -0,0 +1,34 @@
+use std::{
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// Lets something through at most once in a window of time, like [`cprintln_throttled!`](crate::cprintln_throttled) does.
+/// ```
+/// # use comat::*;
+/// use std::time::Duration;
+/// static RETRY: Throttle = Throttle::new();
+/// assert!(RETRY.ready(Duration::from_secs(5)));
+/// assert!(!RETRY.ready(Duration::from_secs(5)));
+/// ```
+#[derive(Debug, Default)]
+pub struct Throttle(Mutex<Option<Instant>>);
+
+impl Throttle {
+    /// A throttle that is ready.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Mutex::new(None))
+    }
+
+    /// Whether `window` has passed since this was last ready (or it never was). If so, the window starts again.
+    pub fn ready(&self, window: Duration) -> bool {
+        let mut last = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+        if last.is_some_and(|last| now.duration_since(last) < window) {
+            return false;
+        }
+        *last = Some(now);
+        true
+    }
+}
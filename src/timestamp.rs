@@ -0,0 +1,60 @@
+use std::{
+    fmt,
+    sync::OnceLock,
+    time::{Instant, SystemTime},
+};
+
+/// The stamp [`ctprintln!`](crate::ctprintln) puts in front of a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Timestamp {
+    /// The time now, in UTC, like `2024-05-01T13:37:00.123Z`.
+    Utc,
+    /// The time since the first elapsed stamp, in seconds, like `   1.250s`.
+    Elapsed,
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        static START: OnceLock<Instant> = OnceLock::new();
+        match self {
+            Self::Utc => f.write_str(&rfc3339(SystemTime::now())),
+            Self::Elapsed => write!(
+                f,
+                "{:>8.3}s",
+                START.get_or_init(Instant::now).elapsed().as_secs_f64()
+            ),
+        }
+    }
+}
+
+/// A time as [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339), in UTC, to the millisecond.
+/// ```
+/// # use comat::*;
+/// use std::time::{Duration, SystemTime};
+/// let time = SystemTime::UNIX_EPOCH + Duration::from_millis(1_714_570_620_123);
+/// assert_eq!(rfc3339(time), "2024-05-01T13:37:00.123Z");
+/// ```
+#[must_use]
+pub fn rfc3339(time: SystemTime) -> String {
+    let since = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    let secs = since.as_secs();
+    let (days, secs) = (secs / 86400, secs % 86400);
+    // days to a date, from http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let (era, doe) = (z / 146_097, z % 146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+        since.subsec_millis()
+    )
+}
@@ -0,0 +1,171 @@
+use core::fmt;
+
+/// The width of `s` on a terminal: how many characters it has, not counting escape sequences.
+///
+/// This is `const`, so it works on constants:
+/// ```
+/// # use comat::*;
+/// const TAG: &str = comat!("{bold_yellow}warn{reset}");
+/// const _: () = assert!(visible_width(TAG) == 4);
+/// assert_eq!(visible_width(&cformat!("{:red} {:blue}", 12, "ab")), 5);
+/// ```
+///
+/// Every `char` (code point) is counted as one column, so this is only right for text that the terminal draws that way, like most scripts:
+/// wide characters (like CJK and most emoji) count 1 rather than 2, and combining marks (like the accent of `"e\u{301}"`) count 1 rather than 0.
+/// For such text, measure [`strip`](crate::strip)ped text with a crate like `unicode-width`, which cannot be `const`.
+/// ```
+/// # use comat::*;
+/// assert_eq!(visible_width("日本"), 2); // drawn 4 wide
+/// assert_eq!(visible_width("e\u{301}"), 2); // drawn 1 wide
+/// ```
+#[must_use]
+pub const fn visible_width(s: &str) -> usize {
+    let s = s.as_bytes();
+    let mut width = 0;
+    let mut i = 0;
+    while i < s.len() {
+        if s[i] == 0x1b {
+            i = escape_end(s, i + 1);
+            continue;
+        }
+        // count the starts of characters, not their continuation bytes
+        if s[i] & 0xc0 != 0x80 {
+            width += 1;
+        }
+        i += 1;
+    }
+    width
+}
+
+/// Skip an escape sequence, from just after the `ESC`.
+pub(crate) const fn escape_end(s: &[u8], mut i: usize) -> usize {
+    if i >= s.len() {
+        return i;
+    }
+    match s[i] {
+        // CSI: parameters, then a final byte in @..=~
+        b'[' => {
+            i += 1;
+            while i < s.len() && !matches!(s[i], 0x40..=0x7e) {
+                i += 1;
+            }
+            if i < s.len() {
+                i + 1
+            } else {
+                i
+            }
+        }
+        // OSC (and the other strings): until BEL or ST (ESC \)
+        b']' | b'P' | b'_' | b'^' => {
+            i += 1;
+            while i < s.len() {
+                if s[i] == 0x07 {
+                    return i + 1;
+                }
+                if s[i] == 0x1b && i + 1 < s.len() && s[i + 1] == b'\\' {
+                    return i + 2;
+                }
+                i += 1;
+            }
+            i
+        }
+        // two byte sequences, like ESC 7
+        _ => i + 1,
+    }
+}
+
+/// Write `args` to `f`, padded to the width `f` asks for (like `{:>8}`), counting only the [`visible_width`].
+///
+/// Colored text written straight to a [`Formatter`](fmt::Formatter) ignores the width, so it breaks alignment in other format strings.
+/// [`ComatDisplay`](crate::ComatDisplay) uses this, and so can `Display` impls written by hand:
+/// ```
+/// # use comat::*;
+/// use std::fmt;
+/// struct Status(bool);
+/// impl fmt::Display for Status {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         match self.0 {
+///             true => pad(f, cformat_args!("{green}ok{reset}")),
+///             false => pad(f, cformat_args!("{red}failed{reset}")),
+///         }
+///     }
+/// }
+/// assert_eq!(format!("[{:>6}]", Status(true)), cformat!("[    {green}ok{reset}]"));
+/// assert_eq!(format!("[{:-^8}]", Status(false)), cformat!("[-{red}failed{reset}-]"));
+/// ```
+///
+/// The fill, alignment (left by default, as for strings) and width are used. The precision is not, as cutting colored text would cut its escapes.
+/// The width is counted like [`visible_width`] does, one column for every `char`.
+/// When there is a width, `args` are formatted twice: once to measure them.
+///
+/// # Errors
+///
+/// when writing to `f` fails
+pub fn pad(f: &mut fmt::Formatter<'_>, args: fmt::Arguments<'_>) -> fmt::Result {
+    let Some(width) = f.width() else {
+        return f.write_fmt(args);
+    };
+    let mut count = Count {
+        width: 0,
+        state: State::Text,
+    };
+    fmt::Write::write_fmt(&mut count, args)?;
+    let padding = width.saturating_sub(count.width);
+    let (before, after) = match f.align() {
+        Some(fmt::Alignment::Right) => (padding, 0),
+        Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+        _ => (0, padding),
+    };
+    let fill = f.fill();
+    for _ in 0..before {
+        fmt::Write::write_char(f, fill)?;
+    }
+    f.write_fmt(args)?;
+    for _ in 0..after {
+        fmt::Write::write_char(f, fill)?;
+    }
+    Ok(())
+}
+
+/// Where [`Count`] is, in an escape sequence or not.
+#[derive(Clone, Copy)]
+enum State {
+    Text,
+    Escape,
+    Csi,
+    String,
+    StringEscape,
+}
+
+/// A [`visible_width`] that can be written to a piece at a time, as escapes can be split between pieces.
+struct Count {
+    width: usize,
+    state: State,
+}
+
+impl fmt::Write for Count {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for b in s.bytes() {
+            self.state = match (self.state, b) {
+                (State::Text, 0x1b) => State::Escape,
+                (State::Text, b) => {
+                    // count the starts of characters, not their continuation bytes
+                    if b & 0xc0 != 0x80 {
+                        self.width += 1;
+                    }
+                    State::Text
+                }
+                (State::Escape, b'[') => State::Csi,
+                (State::Escape, b']' | b'P' | b'_' | b'^') => State::String,
+                (State::Escape, _)
+                | (State::Csi, 0x40..=0x7e)
+                | (State::String, 0x07)
+                | (State::StringEscape, b'\\') => State::Text,
+                (State::Csi, _) => State::Csi,
+                (State::String, 0x1b) => State::StringEscape,
+                (State::String | State::StringEscape, _) => State::String,
+            };
+        }
+        Ok(())
+    }
+}
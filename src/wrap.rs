@@ -0,0 +1,109 @@
+use crate::width::{escape_end, visible_width};
+
+/// Soft wrap `s` at `width` columns, breaking at spaces.
+///
+/// Escape sequences are never split, and each wrapped line starts with the style that was active where it broke
+/// (and the broken line is reset, so backgrounds don't bleed to the edge of the terminal).
+/// Words longer than `width` are left alone, on a line of their own.
+/// ```
+/// # use comat::*;
+/// let text = wrap(&cformat!("{red}one two three{reset}"), 8);
+/// assert_eq!(text, "\x1b[0;34;31mone two\x1b[0m\n\x1b[0;34;31mthree\x1b[0m");
+/// ```
+#[must_use]
+pub fn wrap(s: &str, width: usize) -> String {
+    let mut out = String::with_capacity(s.len());
+    // the escapes that make up the current style
+    let mut active = String::new();
+    for (n, line) in s.split('\n').enumerate() {
+        if n != 0 {
+            out.push('\n');
+        }
+        let mut column = 0;
+        let mut space = String::new();
+        let mut word = String::new();
+        let mut flush = |space: &mut String, word: &mut String, column: &mut usize| {
+            let w = visible_width(word);
+            if *column != 0 && *column + space.len() + w > width {
+                if !active.is_empty() {
+                    out.push_str("\x1b[0m");
+                }
+                out.push('\n');
+                out.push_str(&active);
+                *column = 0;
+            } else {
+                out.push_str(space);
+                *column += space.len();
+            }
+            space.clear();
+            out.push_str(word);
+            *column += w;
+            track(&mut active, word);
+            word.clear();
+        };
+        let bytes = line.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == 0x1b {
+                let end = escape_end(bytes, i + 1);
+                word.push_str(&line[i..end]);
+                i = end;
+                continue;
+            }
+            let Some(c) = line[i..].chars().next() else {
+                break;
+            };
+            if c == ' ' {
+                if visible_width(&word) != 0 {
+                    flush(&mut space, &mut word, &mut column);
+                }
+                space.push(' ');
+            } else {
+                word.push(c);
+            }
+            i += c.len_utf8();
+        }
+        if !word.is_empty() {
+            flush(&mut space, &mut word, &mut column);
+        }
+        out.push_str(&space);
+    }
+    out
+}
+
+/// Follow the SGR escapes in `s`, keeping the ones still in effect.
+pub(crate) fn track(active: &mut String, s: &str) {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != 0x1b {
+            i += 1;
+            continue;
+        }
+        let end = escape_end(bytes, i + 1);
+        let escape = &s[i..end];
+        if let Some(params) = escape
+            .strip_prefix("\x1b[")
+            .and_then(|x| x.strip_suffix('m'))
+        {
+            if params.is_empty() || params == "0" {
+                active.clear();
+            } else {
+                if params.starts_with("0;") {
+                    active.clear();
+                }
+                active.push_str(escape);
+            }
+        }
+        i = end;
+    }
+}
+
+/// Push `s` in `style`, resetting after (if there is a style).
+pub(crate) fn paint(out: &mut String, style: &str, s: &str) {
+    out.push_str(style);
+    out.push_str(s);
+    if !style.is_empty() {
+        out.push_str("\x1b[0m");
+    }
+}
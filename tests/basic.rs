@@ -2,10 +2,42 @@ use comat::comat;
 #[test]
 fn basic() {
     assert_eq!(comat!("{red}yes{reset}"), "\x1b[0;34;31myes\x1b[0m");
-    assert_eq!(comat!("{thing:red}"), "\x1b[0m\x1b[0;34;31m{thing}\x1b[0m");
+    assert_eq!(comat!("{thing:red}"), "\x1b[0;34;31m{thing}\x1b[0m");
     assert_eq!(comat!("{n:.0}"), "{n:.0}");
 }
 
+#[test]
+fn unicode_idents() {
+    let (ñ, 名前) = (1, "x");
+    assert_eq!(
+        comat::cformat!("{ñ} {名前:>ñ$} {ñ:red}"),
+        "1 x \x1b[0;34;31m1\x1b[0m"
+    );
+}
+
+#[test]
+fn squeeze() {
+    // what comes before may be styled, so the first reset stays
+    assert_eq!(comat!("{reset}{reset}a{reset}"), "\x1b[0ma");
+    assert_eq!(
+        comat!("{bold}a{reset}{x:italic}{reset}b{y:red}"),
+        "\x1b[1ma\x1b[0m\x1b[3m{x}\x1b[0mb\x1b[0;34;31m{y}\x1b[0m"
+    );
+    assert_eq!(comat!("{bold}{reset}{red}"), "\x1b[1m\x1b[0;34;31m");
+}
+
+#[test]
+fn restore() {
+    assert_eq!(
+        comat!("{green}a {b:red} c{reset}"),
+        "\x1b[0;34;32ma \x1b[0;34;31m{b}\x1b[0;34;32m c\x1b[0m"
+    );
+    assert_eq!(
+        comat!("{bold}{italic}a {b:red} c"),
+        "\x1b[1m\x1b[3ma \x1b[0;34;31m{b}\x1b[0m\x1b[1m\x1b[3m c"
+    );
+}
+
 #[test]
 fn escapes() {
     assert_eq!(comat!("{{ow}} {{red}}"), "{ow} {red}");
@@ -21,3 +53,1041 @@ fn take() {
 fn resetty() {
     assert_eq!(comat!("{:reset}"), "\x1b[0m{}\x1b[0m");
 }
+
+#[test]
+fn semantic() {
+    assert_eq!(comat!("{error}"), comat!("{bold_red}"));
+    assert_eq!(comat!("{x:warn,italic}"), comat!("{x:bold_yellow,italic}"));
+    assert_eq!(comat!("{errors}"), "{errors}");
+}
+
+#[test]
+fn base() {
+    assert_eq!(
+        comat!(@dim, "a{x:red}b{reset}c"),
+        "\x1b[2ma\x1b[0m\x1b[2m\x1b[0;34;31m{x}\x1b[0m\x1b[2mb\x1b[0m\x1b[2mc\x1b[0m"
+    );
+    assert_eq!(comat!(@bold, @italic, "a"), "\x1b[1m\x1b[3ma\x1b[0m");
+}
+
+// the colors are downgraded with the ansi256 and ansi16 features, which tests/depth.rs checks
+#[cfg(not(any(feature = "ansi256", feature = "ansi16")))]
+#[test]
+fn xterm() {
+    assert_eq!(comat!("{orange1}"), "\x1b[38;5;214m");
+    assert_eq!(comat!("{on_grey50}"), "\x1b[48;5;244m");
+    assert_eq!(
+        comat!("{gray50}{deeppink1}"),
+        "\x1b[38;5;244m\x1b[38;5;198m"
+    );
+    assert_eq!(comat!("{lightgoldenrod}"), "\x1b[38;5;227m");
+    assert_eq!(comat!("{red}"), "\x1b[0;34;31m");
+}
+
+// the colors are downgraded with the ansi256 and ansi16 features, which tests/depth.rs checks
+#[cfg(not(any(feature = "ansi256", feature = "ansi16")))]
+#[test]
+fn css() {
+    assert_eq!(comat!("{rebeccapurple}"), "\x1b[38;2;102;51;153m");
+    assert_eq!(comat!("{on_tomato}"), "\x1b[48;2;255;99;71m");
+    assert_eq!(
+        comat!("{css_orange}{orange}{orange1}"),
+        "\x1b[38;2;255;165;0m\x1b[38;2;255;165;0m\x1b[38;5;214m"
+    );
+    assert_eq!(
+        comat!("{gold}{on_purple}"),
+        "\x1b[38;2;255;215;0m\x1b[48;2;128;0;128m"
+    );
+}
+
+// the colors are downgraded with the ansi256 and ansi16 features, which tests/depth.rs checks
+#[cfg(not(any(feature = "ansi256", feature = "ansi16")))]
+#[test]
+fn hsl() {
+    assert_eq!(comat!("{hsl(0,100%,50%)}"), "\x1b[38;2;255;0;0m");
+    assert_eq!(comat!("{on_hsl(240, 100, 50)}"), "\x1b[48;2;0;0;255m");
+    assert_eq!(
+        comat!("{x:hsl(120,100%,25%),bold}"),
+        "\x1b[0m\x1b[38;2;0;128;0m\x1b[1m{x}\x1b[0m"
+    );
+    assert_eq!(comat!("{hsl(0,200%,50%)}"), "{hsl(0,200%,50%)}");
+}
+
+// the colors are downgraded with the ansi256 and ansi16 features, which tests/depth.rs checks
+#[cfg(not(any(feature = "ansi256", feature = "ansi16")))]
+#[test]
+fn hex() {
+    assert_eq!(
+        comat!("{#336699}{on_#fff}"),
+        "\x1b[38;2;51;102;153m\x1b[48;2;255;255;255m"
+    );
+    assert_eq!(
+        comat!("{auto_on_#336699}"),
+        "\x1b[48;2;51;102;153m\x1b[38;2;255;255;255m"
+    );
+    assert_eq!(comat!("{auto_on_gold1}"), "\x1b[48;5;220m\x1b[38;2;0;0;0m");
+    assert_eq!(
+        comat!("{tag:auto_on_lavender}"),
+        "\x1b[0m\x1b[48;2;230;230;250m\x1b[38;2;0;0;0m{tag}\x1b[0m"
+    );
+}
+
+// the colors are downgraded with the ansi256 and ansi16 features, which tests/depth.rs checks
+#[cfg(not(any(feature = "ansi256", feature = "ansi16")))]
+#[test]
+fn gray() {
+    assert_eq!(
+        comat!("{gray(0)}{on_grey(23)}"),
+        "\x1b[38;5;232m\x1b[48;5;255m"
+    );
+    assert_eq!(comat!("{gray(24)}"), "{gray(24)}");
+}
+
+// the colors are downgraded with the ansi256 and ansi16 features, which tests/depth.rs checks
+#[cfg(not(any(feature = "ansi256", feature = "ansi16")))]
+#[test]
+fn gradient() {
+    assert_eq!(
+        comat!("{gradient(#ff0000,#0000ff)}a b{/gradient}!"),
+        "\x1b[38;2;255;0;0ma \x1b[38;2;0;0;255mb\x1b[0m!"
+    );
+    assert_eq!(
+        comat!("{gradient(#000,#fff)}{{}}{/gradient}"),
+        "\x1b[38;2;0;0;0m{\x1b[38;2;255;255;255m}\x1b[0m"
+    );
+}
+
+// the colors are downgraded with the ansi256 and ansi16 features, which tests/depth.rs checks
+#[cfg(not(any(feature = "ansi256", feature = "ansi16")))]
+#[test]
+fn rainbow() {
+    assert_eq!(
+        comat!("{rainbow}ab{/rainbow}"),
+        "\x1b[38;2;255;0;0ma\x1b[38;2;255;0;255mb\x1b[0m"
+    );
+}
+
+#[test]
+fn into() {
+    fn log(to: &mut String, level: &str) {
+        comat::cformat_into!(to, "{level:bold} ");
+        comat::cformat_into!(to, "{dim}");
+    }
+    let mut s = String::new();
+    log(&mut s, "warn");
+    assert_eq!(s, "\x1b[0m\x1b[1mwarn\x1b[0m \x1b[2m");
+    let mut s = String::new();
+    let done = "done";
+    comat::cwriteln_fmt!(s, "{done:green}").unwrap();
+    assert_eq!(s, "\x1b[0;34;32mdone\x1b[0m\n");
+}
+
+#[test]
+fn include() {
+    assert_eq!(
+        comat::cinclude_str!("banner.txt"),
+        "\x1b[1;34;36mcomat\x1b[0m {ok}\n"
+    );
+}
+
+#[test]
+fn spec() {
+    assert_eq!(comat!("{x:>12:red}"), "\x1b[0;34;31m{x:>12}\x1b[0m");
+    assert_eq!(
+        comat!("{:^5.1:bold,dim}"),
+        "\x1b[0m\x1b[1m\x1b[2m{:^5.1}\x1b[0m"
+    );
+    assert_eq!(comat!("{x:#?}"), "{x:#?}");
+}
+
+#[test]
+fn width() {
+    assert_eq!(
+        comat::cwidth!("{red}héllo{reset} {gradient(red,blue)}ab{/gradient}"),
+        8
+    );
+    assert_eq!(comat::visible_width(&comat::cformat!("{:bold}", 42)), 2);
+    assert_eq!(
+        comat::visible_width("\x1b]8;;https://x\x1b\\link\x1b]8;;\x07"),
+        4
+    );
+    assert_eq!(comat::visible_width("\x1b"), 0);
+    // a column for every char, whatever the terminal draws
+    assert_eq!(comat::cwidth!("{bold}日本{reset}"), 2);
+    assert_eq!(comat::visible_width("e\u{301}🎉"), 3);
+    assert_eq!(
+        comat::cformat!("[{:>4}]", comat::paint!("日本", bold)),
+        "[\x1b[1m  日本\x1b[0m]"
+    );
+}
+
+#[test]
+fn wrap() {
+    let n = 3;
+    assert_eq!(
+        comat::cwrap!(6, "{on_blue}ab {n} cd{reset} ef"),
+        "\x1b[0;34;44mab 3\x1b[0m\n\x1b[0;34;44mcd\x1b[0m ef"
+    );
+    assert_eq!(comat::wrap("  a b\nlonger word", 3), "  a\nb\nlonger\nword");
+}
+
+#[test]
+fn prefixed() {
+    assert_eq!(
+        comat::cprefixed!("> ", "{red}a\nb{reset}\n"),
+        "> \x1b[0;34;31ma\n> \x1b[0;34;31mb\x1b[0m\n"
+    );
+    assert_eq!(comat::prefixed("> ", ""), "");
+}
+
+#[test]
+fn boxes() {
+    assert_eq!(
+        comat::cbox!(border(red), "{blue}a\nbc{reset}"),
+        "\x1b[0;34;31m┌────┐\x1b[0m
+\x1b[0;34;31m│ \x1b[0m\x1b[0;34;34ma\x1b[0m \x1b[0;34;31m │\x1b[0m
+\x1b[0;34;31m│ \x1b[0m\x1b[0;34;34mbc\x1b[0m\x1b[0;34;31m │\x1b[0m
+\x1b[0;34;31m└────┘\x1b[0m"
+    );
+    assert_eq!(comat::cbox!(double, "{}", 1), "╔═══╗\n║ 1 ║\n╚═══╝");
+}
+
+#[test]
+fn columns() {
+    let x = "héllo";
+    assert_eq!(
+        comat::ccolumns!(["a", "{x:red}", "c"], ["dd", "e"], []),
+        "a   \x1b[0;34;31mhéllo\x1b[0m  c\ndd  e\n"
+    );
+}
+
+#[test]
+fn diff() {
+    assert_eq!(comat::cdiff!("same", "same"), "");
+    assert_eq!(
+        comat::diff("", "new\n", false),
+        "\x1b[0;34;36m@@ -0,0 +1,1 @@\x1b[0m\n\x1b[0;34;32m+new\x1b[0m\n"
+    );
+    let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n";
+    let new = "1\n2\n3\nfour\n5\n6\n7\n8\n9\n10\n11\n";
+    assert_eq!(
+        comat::diff(old, new, false),
+        "\x1b[0;34;36m@@ -1,7 +1,7 @@\x1b[0m\n 1\n 2\n 3\n\x1b[0;34;31m-4\x1b[0m\n\x1b[0;34;32m+four\x1b[0m\n 5\n 6\n 7\n\
+         \x1b[0;34;36m@@ -9,4 +9,3 @@\x1b[0m\n 9\n 10\n 11\n\x1b[0;34;31m-12\x1b[0m\n"
+    );
+    // the shortest diff, with the removals of a change first
+    assert_eq!(
+        comat::strip(&comat::diff(
+            "a\nb\nc\na\nb\nb\na\n",
+            "c\nb\na\nb\na\nc\n",
+            false
+        )),
+        "@@ -1,7 +1,6 @@\n-a\n+c\n b\n-c\n a\n b\n-b\n a\n+c\n"
+    );
+    // big texts take space for the changes, not for every pair of lines
+    let old = (0..20_000).map(|i| format!("{i}\n")).collect::<String>();
+    let new = old.replace("\n10000\n", "\nten thousand\n");
+    assert_eq!(
+        comat::strip(&comat::diff(&old, &new, false)),
+        "@@ -9998,7 +9998,7 @@\n 9997\n 9998\n 9999\n-10000\n+ten thousand\n 10001\n 10002\n 10003\n"
+    );
+}
+
+#[test]
+fn hexdump() {
+    let bytes = (0..=17).collect::<Vec<u8>>();
+    assert_eq!(
+        comat::chexdump!(offset(), zero(), printable(), other(), bytes),
+        "00000000  00 01 02 03 04 05 06 07  08 09 0a 0b 0c 0d 0e 0f  |................|
+00000010  10 11                                             |..|"
+    );
+    // the ascii of a row of 8 lines up with the full rows
+    assert_eq!(
+        comat::chexdump!(offset(), zero(), printable(), other(), b"12345678"),
+        "00000000  31 32 33 34 35 36 37 38                           |12345678|"
+    );
+}
+
+#[test]
+fn json() {
+    let style = comat::Json {
+        key: "K",
+        string: "S",
+        number: "N",
+        literal: "L",
+        punctuation: "",
+    };
+    assert_eq!(
+        style.pretty(r#" {"a\"": [ -1.5e3, {}, "x:" ], "b" :true} "#),
+        "{\n  K\"a\\\"\"\x1b[0m: [\n    N-1.5e3\x1b[0m,\n    {},\n    S\"x:\"\x1b[0m\n  ],\n  K\"b\"\x1b[0m: Ltrue\x1b[0m\n}"
+    );
+}
+
+#[cfg(feature = "highlight")]
+#[test]
+fn code() {
+    let style = comat::Code {
+        keyword: "K",
+        string: "S",
+        number: "N",
+        comment: "C",
+    };
+    assert_eq!(
+        style.highlight(comat::Lang::Rust, "let s = \"a\\\"\"; /* x */ 1.5 // é"),
+        "Klet\x1b[0m s = S\"a\\\"\"\x1b[0m; C/* x */\x1b[0m N1.5\x1b[0m C// é\x1b[0m"
+    );
+    assert_eq!(
+        style.highlight(comat::Lang::Toml, "name = 'x' # c\nok = true"),
+        "name = S'x'\x1b[0m C# c\x1b[0m\nok = Ktrue\x1b[0m"
+    );
+    assert_eq!(
+        comat::ccode!(sh, comment(), "\"unterminated"),
+        "\x1b[0;34;32m\"unterminated\x1b[0m"
+    );
+}
+
+#[test]
+fn formatters() {
+    fn twice(x: &i32, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", x * 2)
+    }
+    let (n, size) = (4, 512_u16);
+    assert_eq!(
+        comat::cformat!("{n::twice} {n:red:twice} {size:human_bytes} {n}"),
+        "8 \x1b[0;34;31m8\x1b[0m 512 B 4"
+    );
+    let d = std::time::Duration::from_secs(125);
+    assert_eq!(comat::cformat!("{d:<7:human_duration}|"), "2m 5s  |");
+    assert_eq!(
+        comat::cformat!("{x:red:human_bytes} {n}", x = 2048_u64, n = n),
+        "\x1b[0;34;31m2.0 KiB\x1b[0m 4"
+    );
+    let bytes = |n: i64| comat::cformat!("{n::human_bytes}");
+    assert_eq!(bytes(-5), "-5 B");
+    assert_eq!(bytes(-1536), "-1.5 KiB");
+    assert_eq!(bytes(1_048_575), "1.0 MiB");
+    assert_eq!(bytes(1_048_524), "1023.9 KiB");
+    assert_eq!(bytes(i64::MIN), "-8.0 EiB");
+    assert_eq!(
+        comat::cformat!("{x::human_bytes}", x = u128::MAX),
+        "147573952589676412928.0 EiB"
+    );
+    let duration = |nanos: u64| {
+        let d = std::time::Duration::from_nanos(nanos);
+        comat::cformat!("{d::human_duration}")
+    };
+    assert_eq!(duration(59_960_000_000), "1m 0s");
+    assert_eq!(duration(59_940_000_000), "59.9s");
+    assert_eq!(duration(3_599_600_000_000), "1h 0m");
+    assert_eq!(duration(999_960_000), "1.0s");
+    assert_eq!(duration(999_940_000), "999.9ms");
+    assert_eq!(duration(999_960), "1.0ms");
+    assert_eq!(duration(1_500), "1.5µs");
+    assert_eq!(duration(999), "999ns");
+}
+
+#[test]
+fn scale() {
+    const SLOW: f64 = 1.5;
+    let (a, b, c) = (0.5, 1.5, 9.0);
+    assert_eq!(
+        comat::cformat!("{a:scale(1.0=green,SLOW=yellow,_=red)} {b:<4:scale(1.0=green,SLOW=yellow)}|{c:bold,scale(1.0=green,_=on_red)}"),
+        "\x1b[0;34;32m0.5\x1b[0m 1.5 \x1b[0m|\x1b[1m\x1b[0;34;41m9\x1b[0m"
+    );
+    assert_eq!(
+        comat::cformat!(
+            "{x:scale(1.0=green,_=red)} {:scale(1.0=green,_=red)} {} {2:>4:scale(1.0=green,_=red)}",
+            a,
+            2.0,
+            3.0,
+            x = c * 2.0
+        ),
+        "\x1b[0;34;31m18\x1b[0m \x1b[0;34;32m0.5\x1b[0m 2 \x1b[0;34;31m   3\x1b[0m"
+    );
+}
+
+#[test]
+fn conditional() {
+    let calls = std::cell::Cell::new(0);
+    let count = || calls.replace(calls.get() + 1);
+    comat::cprintln_if!(false, "{:red}", count());
+    comat::cprint_if!(1 > 2, "{:?}", count());
+    assert_eq!(calls.get(), 0);
+}
+
+#[test]
+fn once() {
+    let calls = std::cell::Cell::new(0);
+    for _ in 0..3 {
+        comat::cprintln_once!("{:dim}", calls.replace(calls.get() + 1));
+    }
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn throttled() {
+    let calls = std::cell::Cell::new(0);
+    for window in [60, 60, 0] {
+        comat::cprintln_throttled!(
+            std::time::Duration::from_secs(window),
+            "{:dim}",
+            calls.replace(calls.get() + 1)
+        );
+    }
+    assert_eq!(calls.get(), 2);
+}
+
+#[test]
+fn timestamp() {
+    use std::time::{Duration, SystemTime};
+    let at = |secs| comat::rfc3339(SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+    assert_eq!(at(0), "1970-01-01T00:00:00.000Z");
+    assert_eq!(at(951_825_599), "2000-02-29T11:59:59.000Z");
+    assert!(comat::Timestamp::Elapsed.to_string().ends_with('s'));
+}
+
+#[comat::colorize]
+fn colorized(to: &mut String, n: i32) -> String {
+    use std::fmt::Write;
+    let _ = writeln!(to, "{n:red}");
+    let plain = std::format!("{{red}}{n}");
+    [plain].map(|p| format!("{bold}{}{reset}", p)).concat()
+}
+
+#[test]
+fn colorize() {
+    let mut s = String::new();
+    assert_eq!(colorized(&mut s, 1), "\x1b[1m{red}1\x1b[0m");
+    assert_eq!(s, "\x1b[0;34;31m1\x1b[0m\n");
+}
+
+#[comat::colorize]
+fn shadowed(error: &str, red: u8) -> [String; 3] {
+    [
+        format!("failed: {error}"),
+        format!("{red:>3}"),
+        format!("{error:bold} {warn}!{reset}"),
+    ]
+}
+
+#[comat::colorize]
+fn escaped() -> [String; 2] {
+    [
+        format!("set {{}} of {}", 3),
+        format!("{bold}set{reset} {{}} of {}", 3),
+    ]
+}
+
+#[test]
+fn colorize_std() {
+    let [failed, red, both] = shadowed("oops", 2);
+    assert_eq!(failed, "failed: oops");
+    assert_eq!(red, "  2");
+    assert_eq!(
+        both,
+        comat::cformat!("{error:bold} {warn}!{reset}", error = "oops")
+    );
+    let [plain, colored] = escaped();
+    assert_eq!(plain, "set {} of 3");
+    assert_eq!(colored, "\x1b[1mset\x1b[0m {} of 3");
+}
+
+macro_rules! messages {
+    ($($(#[error($fmt:literal $(, $arg:expr)*)])* $name:ident),*) => {
+        $(const $name: &str = $($fmt)*;)*
+    };
+}
+
+#[comat::errors]
+messages! {
+    #[error("{red}oops{reset} {0:bold}")]
+    OOPS,
+    #[error("{x}", x = 1)]
+    PLAIN
+}
+
+#[test]
+fn errors() {
+    assert_eq!(OOPS, "\x1b[0;34;31moops\x1b[0m \x1b[1m{0}\x1b[0m");
+    assert_eq!(PLAIN, "{x}");
+}
+
+#[derive(comat::ComatDisplay)]
+#[comat("{0:red}{1:>3}")]
+struct Pair<T: std::fmt::Display>(T, u8);
+
+#[derive(comat::ComatDisplay)]
+enum Shape {
+    #[comat("({r:bold})")]
+    Circle { r: u8 },
+    #[comat("{dim}none{reset}")]
+    Empty,
+}
+
+#[test]
+fn display() {
+    assert_eq!(Pair("a", 1).to_string(), "\x1b[0;34;31ma\x1b[0m  1");
+    assert_eq!(
+        Shape::Circle { r: 2 }.to_string(),
+        "(\x1b[0m\x1b[1m2\x1b[0m)"
+    );
+    assert_eq!(Shape::Empty.to_string(), "\x1b[2mnone\x1b[0m");
+}
+
+#[derive(Debug)]
+struct Chain(&'static str, Option<Box<Chain>>);
+
+impl std::fmt::Display for Chain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl std::error::Error for Chain {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.1.as_deref().map(|c| c as _)
+    }
+}
+
+#[test]
+fn report() {
+    let error = Chain(
+        "a\nb",
+        Some(Box::new(Chain("c", Some(Box::new(Chain("d\ne", None)))))),
+    );
+    assert_eq!(
+        comat::report(&error),
+        comat::cformat!(
+            "{error}error{reset}: a
+       b
+{warn}•{reset} {dim}0{reset} c
+{warn}•{reset} {dim}1{reset} {bold}d
+    e{reset}"
+        )
+    );
+    assert_eq!(
+        comat::report(&Chain("x", None)),
+        comat::cformat!("{error}error{reset}: x")
+    );
+}
+
+#[test]
+fn ps1() {
+    assert_eq!(
+        comat::comat_ps1!("{bold}{red}$ {x:blue}"),
+        "\\[\x1b[1m\x1b[0;34;31m\\]$ \\[\x1b[0;34;34m\\]{x}\\[\x1b[0;34;31m\\]"
+    );
+    assert_eq!(comat::comat_ps1!("plain"), "plain");
+    assert_eq!(
+        comat::comat_zsh!("%{dim}%%{reset}"),
+        "%%{\x1b[2m%}%%%{\x1b[0m%}"
+    );
+}
+
+#[test]
+fn tmux() {
+    assert_eq!(
+        comat!(tmux, "\x1b]8;;x\x1b\\{red}x"),
+        "\x1bPtmux;\x1b\x1b]8;;x\x1b\x1b\\\x1b\\\x1b[0;34;31mx"
+    );
+}
+
+#[cfg(feature = "parser")]
+#[test]
+fn dye() {
+    assert_eq!(
+        comat::dye("{red}{}{reset} ({0:bold}) {1}", &[&1, &"x"]).unwrap(),
+        comat::cformat!("{red}{}{reset} ({0:bold}) {1}", 1, "x")
+    );
+    assert!(comat::dye("{}{}", &[&1]).is_err());
+    assert!(comat::dye("{name}", &[]).is_err());
+    assert!(comat::dye("{:>5}", &[&1]).is_err());
+    assert!(comat::dye("{0:nonsense}", &[&1]).is_err());
+}
+
+#[test]
+fn runtime_style() {
+    use comat::{Color, Style};
+    let styles = [
+        Style::new(),
+        Style::new().fg(Color::Fixed(208)).on(Color::Basic(12)),
+    ];
+    let x = 5;
+    assert_eq!(comat::cformat!("{x:[styles[0]]}"), "\x1b[0m5\x1b[0m");
+    assert_eq!(
+        comat::cformat!("{x:>2:bold,[styles[1]]}!"),
+        "\x1b[0m\x1b[1m\x1b[38;5;208;104m 5\x1b[0m!"
+    );
+    assert_eq!(
+        Style::new().fg(Color::Basic(200)).to_string(),
+        Style::new().fg(Color::Basic(15)).to_string()
+    );
+    assert_eq!(
+        comat::cformat!("{y:[styles[0]]}{:[styles[0]]}", x + 1, y = x * 2),
+        "\x1b[0m10\x1b[0m6\x1b[0m"
+    );
+}
+
+// the colors are downgraded with the ansi256 and ansi16 features, which tests/depth.rs checks
+#[cfg(not(any(feature = "ansi256", feature = "ansi16")))]
+#[test]
+fn cstyle() {
+    use comat::{cstyle, Color, Style};
+    const WARN: Style = cstyle!(yellow, bold, italic);
+    assert_eq!(WARN, Style::new().bold().italic().fg(Color::Basic(3)));
+    assert_eq!(WARN.to_string(), "\x1b[1;3;33m");
+    // the colors reset what came before them, as they do in the terminal
+    assert_eq!(cstyle!(italic, red), Style::new().fg(Color::Basic(1)));
+    assert_eq!(cstyle!(reset), Style::new());
+    assert_eq!(cstyle!(dim, italic, reset, bold), Style::new().bold());
+    assert_eq!(cstyle!(on_orange1), Style::new().on(Color::Fixed(214)));
+}
+
+// the colors are downgraded with the ansi256 and ansi16 features, which tests/depth.rs checks
+#[cfg(not(any(feature = "ansi256", feature = "ansi16")))]
+#[test]
+fn paint() {
+    let n = 4;
+    assert_eq!(
+        format!(
+            "{:<3}|{}",
+            comat::paint!(n, bold),
+            comat::paint!(&n, #ff0000)
+        ),
+        "\x1b[1m4  \x1b[0m|\x1b[38;2;255;0;0m4\x1b[0m"
+    );
+}
+
+mod templates {
+    comat::ctemplate!(pub(crate) STATUS = "{bold}{}{reset}: {status:green}");
+}
+
+#[test]
+fn template() {
+    use comat::{cformat, ctemplate};
+    ctemplate!(
+        HELP = dedent,
+        "
+        {bold}usage{reset}: {cmd}
+    "
+    );
+    assert_eq!(
+        cformat!(HELP, cmd = "comat"),
+        "\x1b[1musage\x1b[0m: comat\n"
+    );
+    assert_eq!(
+        cformat!(templates::STATUS, "build", status = "ok"),
+        cformat!("{bold}{}{reset}: {status:green}", "build", status = "ok")
+    );
+    use std::fmt::Write as _;
+    let mut s = String::new();
+    comat::cwriteln!(s, templates::STATUS, 1, status = 2).unwrap();
+    assert_eq!(s, cformat!("{bold}1{reset}: {status:green}\n", status = 2));
+}
+
+#[cfg(feature = "parser")]
+#[test]
+fn translated() {
+    let source = "{bold}{}{reset} is {x:green}";
+    assert_eq!(
+        comat::dye_translated(source, "{bold}{}{reset} est {0:green}", &[&"x"]).unwrap(),
+        comat::cformat!("{bold}{}{reset} est {0:green}", "x")
+    );
+    assert!(comat::dye_translated(source, "{0:red}", &[&"x"]).is_err());
+    assert!(comat::dye_translated(source, "{rainbow}x", &[]).is_err());
+}
+
+#[test]
+fn close() {
+    assert_eq!(
+        comat!(close_default, "{green}a {b:bold_red} c"),
+        "\x1b[0;34;32ma \x1b[0m\x1b[1;34;31m{b}\x1b[39;49m c"
+    );
+    assert_eq!(comat!(close_none, "{b:red} c"), "\x1b[0;34;31m{b} c");
+    assert_eq!(
+        comat!(close_reset, dedent, tmux, "{b:red} c"),
+        "\x1b[0;34;31m{b}\x1b[0m c"
+    );
+}
+
+#[cfg(feature = "aliases")]
+#[test]
+fn aliases() {
+    assert_eq!(comat!("{*}{r}x{k}"), comat!("{bold}{red}x{black}"));
+    assert_eq!(comat!("{x:g}"), comat!("{x:green}"));
+}
+
+#[test]
+fn sgr() {
+    assert_eq!(comat!("{sgr(1;38;5;208)}x"), "\x1b[1;38;5;208mx");
+    assert_eq!(comat!("{x:sgr(53)}"), "\x1b[0m\x1b[53m{x}\x1b[0m");
+    assert_eq!(comat!("{sgr(x)}"), "{sgr(x)}");
+}
+
+#[test]
+fn esc() {
+    assert_eq!(comat!(r"{esc(]0;title\a)}x"), "\x1b]0;title\x07x");
+    assert_eq!(comat!(r"{esc(7)}{red}{esc(8)}"), "\x1b7\x1b[0;34;31m\x1b8");
+}
+
+#[test]
+fn literal_block() {
+    let red = 1;
+    assert_eq!(comat!("{!red}{red}"), "{red}\x1b[0;34;31m");
+    assert_eq!(
+        comat::cformat!("{!red} {red:red}"),
+        "{red} \x1b[0;34;31m1\x1b[0m"
+    );
+    comat::ctemplate!(SYNTAX = "{!bold}: {}");
+    assert_eq!(comat::cformat!(SYNTAX, "bold"), "{bold}: bold");
+}
+
+#[test]
+fn tags() {
+    assert_eq!(
+        comat!(tags, "<bold,red>{x}</> <div> a < b <<red>"),
+        "\x1b[1m\x1b[0;34;31m{x}\x1b[0m <div> a < b <red>"
+    );
+    let x = 1;
+    assert_eq!(
+        comat::cformat!(tags, "fn f() {{ <italic>{x}</> }}"),
+        "fn f() { \x1b[3m1\x1b[0m }"
+    );
+}
+
+#[test]
+fn adjacent() {
+    let x = 1;
+    assert_eq!(
+        comat::cformat!("{bold}{x:red} "
+                        "and {x:blue}"),
+        comat::cformat!("{bold}{x:red} and {x:blue}")
+    );
+    assert_eq!(comat!(dedent, "{red}" "a{reset}"), "\x1b[0;34;31ma\x1b[0m");
+}
+
+#[test]
+fn pad() {
+    #[derive(comat::ComatDisplay)]
+    #[comat("{bold}{0}{reset}")]
+    struct Bold(&'static str);
+    assert_eq!(format!("{:>5}|", Bold("ab")), "   \x1b[1mab\x1b[0m|");
+    assert_eq!(format!("{:*^6}|", Bold("ab")), "**\x1b[1mab\x1b[0m**|");
+    assert_eq!(format!("{}|", Bold("ab")), "\x1b[1mab\x1b[0m|");
+}
+
+#[test]
+fn assert_colored_eq() {
+    let n = 2;
+    comat::assert_colored_eq!(comat::cformat!("{n:red} {bold}ok"), "2 ok");
+    let panic = std::panic::catch_unwind(|| {
+        comat::assert_colored_eq!(comat::cformat!("{n:red}"), "3", "n is {}", n);
+    })
+    .unwrap_err();
+    let message = panic.downcast_ref::<String>().unwrap();
+    assert!(message.starts_with("assertion `left == right` failed, ignoring colors: n is 2\n"));
+    assert!(comat::strip(message).ends_with("-2\n+3\n"));
+}
+
+#[test]
+fn debug() {
+    assert_eq!(
+        comat::comat_debug!(r"{red}a{reset} {esc(]0;title\a)}"),
+        r"\x1b[0;34;31ma\x1b[0m \x1b]0;title\x07"
+    );
+}
+
+#[test]
+#[cfg(feature = "golden")]
+fn golden() {
+    let path = std::env::temp_dir().join(format!("comat-golden-{}.txt", std::process::id()));
+    std::fs::write(&path, r"\x1b[1mok\x1b[0m").unwrap();
+    comat::golden::check(&path, comat!("{bold}ok{reset}"));
+    assert!(std::panic::catch_unwind(|| comat::golden::check(&path, comat!("{dim}ok"))).is_err());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+#[cfg(feature = "parser")]
+fn markers() {
+    use comat::parser::{parse, Options, Theme};
+    let options = Options {
+        markers: true,
+        ..Options::default()
+    };
+    let parsed = parse(
+        "{error}error{reset}: {x:bold} {n:success}",
+        &Theme::default(),
+        &options,
+    )
+    .unwrap();
+    assert_eq!(parsed.text, "[error] error: {x} [ok] {n}");
+}
+
+#[test]
+fn force() {
+    assert_eq!(comat!(@ansi256, "{#336699}"), "\x1b[38;5;60m");
+    assert_eq!(comat!(@ansi16, "{#336699}"), "\x1b[36m");
+    assert_eq!(comat!(@truecolor, "{#336699}"), "\x1b[38;2;51;102;153m");
+    let x = 4;
+    assert_eq!(comat::cformat!(@plain, "{bold}{x:red}{reset}!"), "4!");
+}
+
+#[test]
+fn src() {
+    let (file, line) = ("src/main.rs", 12);
+    let at = comat::cformat!("{bold}at{reset} {src(file, line)}");
+    if !comat::capabilities().hyperlinks {
+        assert_eq!(at, "\x1b[1mat\x1b[0m src/main.rs:12");
+    }
+    assert_eq!(comat::strip(&at), "at src/main.rs:12");
+}
+
+#[test]
+fn notify() {
+    let secs = 94;
+    let done = comat::cformat!("{bold}done{reset}{notify(\"comat\", secs)}");
+    if comat::capabilities().notifications.is_none() {
+        assert_eq!(done, "\x1b[1mdone\x1b[0m");
+    }
+    assert_eq!(comat::strip(&done), "done");
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn image() {
+    let path = "Cargo.toml";
+    let thumbnail = comat::cformat!("[{image(path, 4)}]");
+    if comat::capabilities().images.is_none() {
+        assert_eq!(thumbnail, "[]");
+    }
+    assert_eq!(comat::strip(&thumbnail), "[]");
+    assert_eq!(comat::Image("missing.png", 4).to_string(), "");
+}
+
+#[test]
+fn bar() {
+    assert_eq!(comat::bar(0.0, 3, "", ""), "░░░");
+    assert_eq!(comat::bar(2.0, 3, "", ""), "███");
+    assert_eq!(comat::bar(f64::NAN, 1, "", ""), "░");
+    assert_eq!(comat::bar(0.5 / 8.0, 1, "", ""), "▏");
+    assert_eq!(
+        comat::cbar!(0.25, 2),
+        comat::cformat!("{green}▌{reset}{dim}░{reset}")
+    );
+    assert_eq!(
+        comat::cbar!(1.0, 1, empty = (dim, italic), filled = on_green,),
+        comat::cformat!("{on_green}█{reset}")
+    );
+}
+
+comat::comat_alias! { pub(crate) macro colors { err = bold_red, path = "cyan,underline" } }
+
+mod aliased {
+    use std::fmt::Write as _;
+
+    #[test]
+    fn alias() {
+        let file = "Cargo.toml";
+        assert_eq!(
+            crate::colors!(cformat!("{err}no{reset} {file:path}")),
+            comat::cformat!("{bold_red}no{reset} {file:cyan,underline}")
+        );
+        let mut s = String::new();
+        crate::colors!(cwrite!(s, "{err}{}", 1)).unwrap();
+        assert_eq!(s, "\x1b[1;34;31m1");
+    }
+}
+
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+#[test]
+fn cawrite() {
+    let (mut out, user) = (Vec::new(), "bendn");
+    let write = async {
+        comat::cawrite!(out, "{green}welcome{reset}, {user:bold}!").await?;
+        comat::cawriteln!(out, " {bold}>{reset}").await
+    };
+    #[cfg(feature = "tokio")]
+    let written = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(write);
+    #[cfg(not(feature = "tokio"))]
+    let written = async_std::task::block_on(write);
+    written.unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "\x1b[0;34;32mwelcome\x1b[0m, \x1b[1mbendn\x1b[0m! \x1b[1m>\x1b[0m\n"
+    );
+}
+
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+#[test]
+fn cawrite_named() {
+    let (mut out, user) = (Vec::new(), "bendn");
+    async_std::task::block_on(async {
+        comat::cawrite!(tokio::io::AsyncWriteExt; out, "{green}welcome{reset}, ").await?;
+        comat::cawriteln!(::async_std::io::WriteExt; out, "{user:bold}!").await
+    })
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "\x1b[0;34;32mwelcome\x1b[0m, \x1b[0m\x1b[1mbendn\x1b[0m!\n"
+    );
+}
+
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+#[test]
+fn error_crates() {
+    #[cfg(feature = "anyhow")]
+    use anyhow::Result;
+    #[cfg(not(feature = "anyhow"))]
+    use eyre::Result;
+    fn read(path: &str) -> Result<String> {
+        comat::ccontext!(std::fs::read_to_string(path), "couldnt read {path:bold}")
+    }
+    fn take(got: usize) -> Result<usize> {
+        comat::censure!(got <= 8, "{got:bold} is more than 8");
+        if got == 0 {
+            comat::cbail!("{error}nothing{reset} to take");
+        }
+        Ok(got)
+    }
+    assert_eq!(
+        read("nowhere.toml").unwrap_err().to_string(),
+        "couldnt read \x1b[0m\x1b[1mnowhere.toml\x1b[0m"
+    );
+    assert_eq!(take(4).unwrap(), 4);
+    assert_eq!(
+        take(12).unwrap_err().to_string(),
+        "\x1b[0m\x1b[1m12\x1b[0m is more than 8"
+    );
+    assert_eq!(
+        take(0).unwrap_err().to_string(),
+        "\x1b[1;34;31mnothing\x1b[0m to take"
+    );
+}
+
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+#[test]
+fn error_crates_named() {
+    fn anyhow(got: Option<usize>) -> anyhow::Result<usize> {
+        let got = comat::ccontext!(anyhow; got, "{error}nothing{reset}")?;
+        comat::censure!(::anyhow; got <= 8, "{got:bold} is more than 8");
+        Ok(got)
+    }
+    fn eyre(got: Result<usize, std::num::ParseIntError>) -> eyre::Result<usize> {
+        let got = comat::ccontext!(eyre; got, "{error}nothing{reset}")?;
+        if got > 8 {
+            comat::cbail!(eyre; "{got:bold} is more than 8");
+        }
+        Ok(got)
+    }
+    assert_eq!(
+        anyhow(None).unwrap_err().to_string(),
+        "\x1b[1;34;31mnothing\x1b[0m"
+    );
+    assert_eq!(
+        anyhow(Some(12)).unwrap_err().to_string(),
+        "\x1b[0m\x1b[1m12\x1b[0m is more than 8"
+    );
+    assert_eq!(
+        eyre("x".parse()).unwrap_err().to_string(),
+        "\x1b[1;34;31mnothing\x1b[0m"
+    );
+    assert_eq!(
+        eyre(Ok(12)).unwrap_err().to_string(),
+        "\x1b[0m\x1b[1m12\x1b[0m is more than 8"
+    );
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_value() {
+    let value = serde_json::json!({ "a\"": [-1.5e3, {}, "x:"], "b": true, "c": [] });
+    let style = comat::Json {
+        key: "K",
+        string: "S",
+        number: "N",
+        literal: "L",
+        punctuation: "P",
+    };
+    assert_eq!(style.value(&value), style.pretty(&value.to_string()));
+    assert_eq!(
+        comat::cjson!(&value),
+        comat::Json::DEFAULT.pretty(&value.to_string())
+    );
+    // a string that is not json is read as text, as without the feature
+    assert_eq!(comat::cjson!(string(), "[1]"), comat::cjson!("[1]"));
+}
+
+#[cfg(feature = "parser")]
+#[test]
+fn theme_file() {
+    use comat::parser::Theme;
+    let theme = Theme::load_variant("tests/theme.toml").unwrap();
+    assert_eq!(theme.get("quoted key").unwrap(), "\x1b[1;3m");
+    assert_eq!(theme.get("literal").unwrap(), "\x1b[0;34;31m\x1b[1m");
+    assert_eq!(theme.get("escaped").unwrap(), "\x1b[31m");
+    // the arrays are read, but tokens are strings
+    let error = Theme::load_variant("tests/array.toml").err().unwrap();
+    assert!(error.ends_with("expected a string for \"names\", not an array"));
+}
+
+#[comat::clap]
+#[derive(clap::Parser)]
+#[comat_help("{bold}comat{reset}: colors")]
+#[comat_long_help("{bold}comat{reset}: a better coloring crate")]
+struct Args {
+    #[comat_help("the {bold}file{reset} to color")]
+    #[comat_long_help("the {bold}file{reset} to color, or {dim}-{reset} for stdin")]
+    file: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[comat::clap]
+#[derive(clap::Subcommand)]
+enum Command {
+    #[comat_help("{green}run{reset} it")]
+    #[comat_long_help("{green}run{reset} it, now")]
+    Run {
+        #[arg(short)]
+        #[comat_help("print {dim}more{reset}")]
+        verbose: bool,
+    },
+}
+
+#[test]
+fn clap() {
+    use clap::CommandFactory;
+    let ansi = |s: Option<&clap::builder::StyledStr>| s.unwrap().ansi().to_string();
+    let args = Args::command();
+    assert_eq!(ansi(args.get_about()), "\x1b[1mcomat\x1b[0m: colors");
+    assert_eq!(
+        ansi(args.get_long_about()),
+        "\x1b[1mcomat\x1b[0m: a better coloring crate"
+    );
+    let file = args.get_arguments().find(|a| a.get_id() == "file").unwrap();
+    assert_eq!(ansi(file.get_help()), "the \x1b[1mfile\x1b[0m to color");
+    assert_eq!(
+        ansi(file.get_long_help()),
+        "the \x1b[1mfile\x1b[0m to color, or \x1b[2m-\x1b[0m for stdin"
+    );
+    let run = args.find_subcommand("run").unwrap();
+    assert_eq!(ansi(run.get_about()), "\x1b[0;34;32mrun\x1b[0m it");
+    assert_eq!(
+        ansi(run.get_long_about()),
+        "\x1b[0;34;32mrun\x1b[0m it, now"
+    );
+    let verbose = run
+        .get_arguments()
+        .find(|a| a.get_id() == "verbose")
+        .unwrap();
+    assert_eq!(ansi(verbose.get_help()), "print \x1b[2mmore\x1b[0m");
+    let Args {
+        file,
+        command: Command::Run { verbose },
+    } = clap::Parser::parse_from(["comat", "a.txt", "run", "-v"]);
+    assert_eq!((&*file, verbose), ("a.txt", true));
+}
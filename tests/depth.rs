@@ -0,0 +1,159 @@
+//! The colors, as the `ansi256` and `ansi16` features downgrade them. Without either, tests/basic.rs checks them.
+#![cfg(any(feature = "ansi256", feature = "ansi16"))]
+use comat::comat;
+
+/// The expected escapes with `ansi256`, or with `ansi16`, which wins when both are on.
+macro_rules! depth {
+    ($ansi256:expr, $ansi16:expr $(,)?) => {
+        if cfg!(feature = "ansi16") {
+            $ansi16
+        } else {
+            $ansi256
+        }
+    };
+}
+
+#[test]
+fn xterm() {
+    assert_eq!(comat!("{orange1}"), depth!("\x1b[38;5;214m", "\x1b[93m"));
+    assert_eq!(comat!("{on_grey50}"), depth!("\x1b[48;5;244m", "\x1b[100m"));
+    assert_eq!(
+        comat!("{gray50}{deeppink1}"),
+        depth!("\x1b[38;5;244m\x1b[38;5;198m", "\x1b[90m\x1b[95m")
+    );
+    assert_eq!(
+        comat!("{lightgoldenrod}"),
+        depth!("\x1b[38;5;227m", "\x1b[93m")
+    );
+    assert_eq!(comat!("{red}"), "\x1b[0;34;31m");
+}
+
+#[test]
+fn css() {
+    assert_eq!(
+        comat!("{rebeccapurple}"),
+        depth!("\x1b[38;5;60m", "\x1b[35m")
+    );
+    assert_eq!(comat!("{on_tomato}"), depth!("\x1b[48;5;203m", "\x1b[101m"));
+    assert_eq!(
+        comat!("{css_orange}{orange}{orange1}"),
+        depth!(
+            "\x1b[38;5;214m\x1b[38;5;214m\x1b[38;5;214m",
+            "\x1b[93m\x1b[93m\x1b[93m"
+        )
+    );
+    assert_eq!(
+        comat!("{gold}{on_purple}"),
+        depth!("\x1b[38;5;220m\x1b[48;5;90m", "\x1b[93m\x1b[45m")
+    );
+}
+
+#[test]
+fn hsl() {
+    assert_eq!(
+        comat!("{hsl(0,100%,50%)}"),
+        depth!("\x1b[38;5;196m", "\x1b[91m")
+    );
+    assert_eq!(
+        comat!("{on_hsl(240, 100, 50)}"),
+        depth!("\x1b[48;5;21m", "\x1b[104m")
+    );
+    assert_eq!(
+        comat!("{x:hsl(120,100%,25%),bold}"),
+        depth!(
+            "\x1b[0m\x1b[38;5;28m\x1b[1m{x}\x1b[0m",
+            "\x1b[0m\x1b[32m\x1b[1m{x}\x1b[0m"
+        )
+    );
+    assert_eq!(comat!("{hsl(0,200%,50%)}"), "{hsl(0,200%,50%)}");
+}
+
+#[test]
+fn hex() {
+    assert_eq!(
+        comat!("{#336699}{on_#fff}"),
+        depth!("\x1b[38;5;60m\x1b[48;5;231m", "\x1b[36m\x1b[107m")
+    );
+    assert_eq!(
+        comat!("{auto_on_#336699}"),
+        depth!("\x1b[48;5;60m\x1b[38;5;231m", "\x1b[46m\x1b[97m")
+    );
+    assert_eq!(
+        comat!("{auto_on_gold1}"),
+        depth!("\x1b[48;5;220m\x1b[38;5;16m", "\x1b[103m\x1b[30m")
+    );
+    assert_eq!(
+        comat!("{tag:auto_on_lavender}"),
+        depth!(
+            "\x1b[0m\x1b[48;5;255m\x1b[38;5;16m{tag}\x1b[0m",
+            "\x1b[0m\x1b[107m\x1b[30m{tag}\x1b[0m"
+        )
+    );
+}
+
+#[test]
+fn gray() {
+    assert_eq!(
+        comat!("{gray(0)}{on_grey(23)}"),
+        depth!("\x1b[38;5;232m\x1b[48;5;255m", "\x1b[30m\x1b[107m")
+    );
+    assert_eq!(comat!("{gray(24)}"), "{gray(24)}");
+}
+
+#[test]
+fn gradient() {
+    assert_eq!(
+        comat!("{gradient(#ff0000,#0000ff)}a b{/gradient}!"),
+        depth!(
+            "\x1b[38;5;196ma \x1b[38;5;21mb\x1b[0m!",
+            "\x1b[91ma \x1b[94mb\x1b[0m!"
+        )
+    );
+    assert_eq!(
+        comat!("{gradient(#000,#fff)}{{}}{/gradient}"),
+        depth!(
+            "\x1b[38;5;16m{\x1b[38;5;231m}\x1b[0m",
+            "\x1b[30m{\x1b[97m}\x1b[0m"
+        )
+    );
+}
+
+#[test]
+fn rainbow() {
+    assert_eq!(
+        comat!("{rainbow}ab{/rainbow}"),
+        depth!(
+            "\x1b[38;5;196ma\x1b[38;5;201mb\x1b[0m",
+            "\x1b[91ma\x1b[95mb\x1b[0m"
+        )
+    );
+}
+
+#[test]
+fn cstyle() {
+    use comat::{cstyle, Color, Style};
+    assert_eq!(
+        cstyle!(yellow, bold, italic),
+        Style::new().bold().italic().fg(Color::Basic(3))
+    );
+    assert_eq!(
+        cstyle!(on_orange1),
+        Style::new().on(depth!(Color::Fixed(214), Color::Basic(11)))
+    );
+}
+
+#[test]
+fn paint() {
+    let n = 4;
+    assert_eq!(
+        format!(
+            "{:<3}|{}",
+            comat::paint!(n, bold),
+            comat::paint!(&n, #ff0000)
+        ),
+        depth!(
+            "\x1b[1m4  \x1b[0m|\x1b[38;5;196m4\x1b[0m",
+            "\x1b[1m4  \x1b[0m|\x1b[91m4\x1b[0m"
+        )
+    );
+}
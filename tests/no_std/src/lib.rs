@@ -0,0 +1,36 @@
+//! makes sure the `core::fmt` macros build without `std`.
+#![no_std]
+use comat::*;
+use core::fmt::{self, Write};
+
+pub const BANNER: &str = comat!("{bold_cyan}firmware{reset} v1");
+pub const VERSION: &str = cconcat!(BANNER, "{dim}.2{reset}");
+pub const ACK: &[u8] = comat_bytes!("{green}ack{reset}\r\n");
+pub const WARN: &str = comat_code!(bold_yellow, underline);
+
+pub struct Led(pub bool);
+
+impl fmt::Display for Led {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            true => cwrite!(f, "{green}on{reset}"),
+            false => cwrite_fmt!(f, "{red}off{reset}"),
+        }
+    }
+}
+
+#[derive(ComatDisplay)]
+#[comat("{bold}{0}{reset}C")]
+pub struct Celsius(pub i32);
+
+pub fn report(serial: &mut impl Write, led: &Led, temp: i32) -> fmt::Result {
+    cwriteln!(serial, "led: {led} temp: {temp:yellow}")?;
+    serial.write_fmt(cformat_args!("{dim}--{reset}\r\n"))?;
+    serial.write_fmt(cformat_args_nl!("{temp:dim}"))
+}
+
+pub fn check(temp: i32) {
+    if temp > 90 {
+        cpanic!("{temp:bold_red} is too hot");
+    }
+}
@@ -0,0 +1,23 @@
+//! These change how the printing macros show text for the whole process, so they are kept out of the other tests, which run at the same time.
+use comat::{Colors, Render, Stream};
+
+#[test]
+fn plain() {
+    let s = comat::cformat!("{bold}a\x1b]8;;x\x07b{reset}");
+    assert_eq!(comat::strip(&s), "ab");
+    assert_eq!(Render(&s, Stream::Stdout).to_string(), s);
+    comat::set_plain(true);
+    assert_eq!(Render(&s, Stream::Stdout).to_string(), "ab");
+    assert_eq!(Render(&s, Stream::Stderr).to_string(), "ab");
+    comat::set_plain(false);
+    assert_eq!(Render(&s, Stream::Stdout).to_string(), s);
+
+    comat::set_auto(true);
+    for stream in [Stream::Stdout, Stream::Stderr] {
+        let none = comat::stream_capabilities(stream).colors == Colors::None;
+        let shown = Render(&s, stream).to_string();
+        assert_eq!(shown, if none { "ab" } else { &s });
+    }
+    comat::set_auto(false);
+    assert_eq!(Render(&s, Stream::Stderr).to_string(), s);
+}
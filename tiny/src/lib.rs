@@ -0,0 +1,279 @@
+//! comat's basic macros, without `syn` or `quote`, for when comat is on the path of a cold build.
+//!
+//! the macros only take a string literal and the arguments after it, passing the arguments on to the std macros as they are,
+//! so the input is read by hand and the colors are the same as [comat](https://docs.rs/comat)'s, `comat.toml` included:
+//! ```
+//! use comat_tiny::*;
+//! let magic = 4;
+//! cprintln!("{red}look its red{reset}! {bold_blue}{magic}{reset} is the magic number!");
+//! assert_eq!(cformat!("{magic:bold}"), comat!("{magic:bold}").replace("{magic}", "4"));
+//! ```
+//!
+//! what needs the arguments parsed is left to comat: formatters, scales and `[style]`s, options like `dedent`,
+//! and what the printing macros do at runtime, like [`set_plain`](https://docs.rs/comat/latest/comat/fn.set_plain.html).
+#![forbid(unsafe_code)]
+#![warn(clippy::pedantic, clippy::dbg_macro, missing_docs)]
+use comat_parser::{Made, Options, Theme};
+use proc_macro::{Delimiter, Group, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use std::path::PathBuf;
+
+#[proc_macro]
+/// Colorize a string at compile time, giving a format string, like comat's.
+/// ```
+/// # use comat_tiny::comat;
+/// assert_eq!(comat!("{red}yes{reset}"), "\x1b[0;34;31myes\x1b[0m");
+/// ```
+pub fn comat(input: TokenStream) -> TokenStream {
+    expand(input, |text, args| match args.into_iter().next() {
+        Some(arg) => error("comat! takes only a string", arg.span()),
+        None => TokenTree::from(text).into(),
+    })
+}
+
+#[proc_macro]
+/// Format text, colorfully, like [`format!`].
+/// ```
+/// # use comat_tiny::cformat;
+/// let n = 4;
+/// assert_eq!(cformat!("{n:red} {}", 2), "\x1b[0;34;31m4\x1b[0m 2");
+/// ```
+pub fn cformat(input: TokenStream) -> TokenStream {
+    expand(input, |text, args| call("::std::format", text, args))
+}
+
+#[proc_macro]
+/// Format text, colorfully, like [`format_args!`].
+/// ```
+/// # use comat_tiny::cformat_args;
+/// assert_eq!(cformat_args!("{bold}{}{reset}", 1).to_string(), "\x1b[1m1\x1b[0m");
+/// ```
+pub fn cformat_args(input: TokenStream) -> TokenStream {
+    expand(input, |text, args| call("::core::format_args", text, args))
+}
+
+#[proc_macro]
+/// Print text, colorfully, to stdout, like [`print!`].
+/// ```
+/// # use comat_tiny::cprint;
+/// cprint!("{yellow}i am a warning. {reset}why do you dislike me?");
+/// ```
+pub fn cprint(input: TokenStream) -> TokenStream {
+    expand(input, |text, args| call("::std::print", text, args))
+}
+
+#[proc_macro]
+/// Print text, colorfully, to stdout, with a newline, like [`println!`].
+/// ```
+/// # use comat_tiny::cprintln;
+/// cprintln!("{green}ok{reset}");
+/// ```
+pub fn cprintln(input: TokenStream) -> TokenStream {
+    expand(input, |text, args| call("::std::println", text, args))
+}
+
+#[proc_macro]
+/// Print text, colorfully, to stderr, like [`eprint!`].
+/// ```
+/// # use comat_tiny::ceprint;
+/// ceprint!("{dim}...{reset}");
+/// ```
+pub fn ceprint(input: TokenStream) -> TokenStream {
+    expand(input, |text, args| call("::std::eprint", text, args))
+}
+
+#[proc_macro]
+/// Print text, colorfully, to stderr, with a newline, like [`eprintln!`].
+/// ```
+/// # use comat_tiny::ceprintln;
+/// let file = "Cargo.toml";
+/// ceprintln!("{error}error{reset}: {file:bold} not found");
+/// ```
+pub fn ceprintln(input: TokenStream) -> TokenStream {
+    expand(input, |text, args| call("::std::eprintln", text, args))
+}
+
+#[proc_macro]
+/// Panic, with colorful text, like [`panic!`].
+/// ```should_panic
+/// # use comat_tiny::cpanic;
+/// cpanic!("{red}at the disco{reset}");
+/// ```
+pub fn cpanic(input: TokenStream) -> TokenStream {
+    expand(input, |text, args| call("::core::panic", text, args))
+}
+
+#[proc_macro]
+/// Write text, colorfully, to a writer, like [`write!`].
+/// ```
+/// # use comat_tiny::cwrite;
+/// use std::fmt::Write;
+/// let mut s = String::new();
+/// cwrite!(s, "{bold}{}{reset}", 1).unwrap();
+/// assert_eq!(s, "\x1b[1m1\x1b[0m");
+/// ```
+pub fn cwrite(input: TokenStream) -> TokenStream {
+    writer(input, "::core::write")
+}
+
+#[proc_macro]
+/// Write text, colorfully, to a writer, with a newline, like [`writeln!`].
+/// ```
+/// # use comat_tiny::cwriteln;
+/// use std::fmt::Write;
+/// let mut s = String::new();
+/// cwriteln!(s, "{green}ok{reset}").unwrap();
+/// assert_eq!(s, "\x1b[0;34;32mok\x1b[0m\n");
+/// ```
+pub fn cwriteln(input: TokenStream) -> TokenStream {
+    writer(input, "::core::writeln")
+}
+
+/// Call `write` with the writer, which is everything up to the first comma, and the string and arguments after it.
+fn writer(input: TokenStream, write: &str) -> TokenStream {
+    let mut tokens = input.into_iter();
+    let mut out = tokens
+        .by_ref()
+        .take_while(|token| !matches!(token, TokenTree::Punct(p) if p.as_char() == ','))
+        .collect::<TokenStream>();
+    out.extend([TokenTree::from(Punct::new(',', Spacing::Alone))]);
+    expand(tokens.collect(), |text, args| {
+        out.extend([TokenTree::from(text)]);
+        out.extend(args);
+        call_with(write, out)
+    })
+}
+
+/// Read the string literal at the start of `input`, and give its format string, and the tokens after it, to `f`,
+/// or make an error.
+fn expand(input: TokenStream, f: impl FnOnce(Literal, TokenStream) -> TokenStream) -> TokenStream {
+    let mut tokens = input.into_iter();
+    let literal = match tokens.next() {
+        Some(TokenTree::Literal(literal)) => literal,
+        // like the literal of a macro_rules! $s:literal
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::None => {
+            match group.stream().into_iter().next() {
+                Some(TokenTree::Literal(literal)) => literal,
+                _ => return error("expected a string literal", group.span()),
+            }
+        }
+        Some(token) => return error("expected a string literal", token.span()),
+        None => return error("expected a string literal", Span::call_site()),
+    };
+    let Some(string) = unquote(&literal.to_string()) else {
+        return error("expected a string literal", literal.span());
+    };
+    let (text, files) = match translate(&string) {
+        Ok(translated) => translated,
+        Err(e) => return error(&e, literal.span()),
+    };
+    let mut text = Literal::string(&text);
+    text.set_span(literal.span());
+    // the crate rebuilds when the comat.toml (or theme) does
+    let mut out = TokenStream::new();
+    for path in files.iter().filter_map(|path| path.to_str()) {
+        out.extend("const _: &[u8] = ".parse::<TokenStream>());
+        out.extend(call_with(
+            "::core::include_bytes",
+            TokenTree::from(Literal::string(path)).into(),
+        ));
+        out.extend([TokenTree::from(Punct::new(';', Spacing::Alone))]);
+    }
+    out.extend(f(text, tokens.collect()));
+    TokenTree::from(Group::new(Delimiter::Brace, out)).into()
+}
+
+/// The format string comat makes from `input`, and the files its theme came from.
+fn translate(input: &str) -> Result<(String, Vec<PathBuf>), String> {
+    let theme = Theme::load()?;
+    let parsed = comat_parser::parse(input, &theme, &Options::default())?;
+    if parsed
+        .made
+        .iter()
+        .any(|(_, made)| !matches!(made, Made::Literal(_)))
+    {
+        return Err(
+            "formatters, scales and [style]s need their arguments parsed, so they are only in comat"
+                .into(),
+        );
+    }
+    Ok((
+        comat_parser::inline(&parsed.text, &parsed.made, true),
+        theme.files,
+    ))
+}
+
+/// The value of a string literal, from how it is written, or `None` if it is not one.
+fn unquote(source: &str) -> Option<String> {
+    if let Some(raw) = source.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let quoted = raw.get(hashes..raw.len().checked_sub(hashes)?)?;
+        return Some(quoted.strip_prefix('"')?.strip_suffix('"')?.to_string());
+    }
+    let inner = source.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        let escaped = match chars.next()? {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            c @ ('\\' | '\'' | '"') => c,
+            'x' => {
+                let hex = chars.as_str().get(..2)?;
+                chars.nth(1);
+                char::from(u8::from_str_radix(hex, 16).ok().filter(u8::is_ascii)?)
+            }
+            'u' => {
+                let rest = chars.as_str().strip_prefix('{')?;
+                let end = rest.find('}')?;
+                let code = u32::from_str_radix(&rest[..end].replace('_', ""), 16).ok()?;
+                chars = rest[end + 1..].chars();
+                char::from_u32(code)?
+            }
+            // a line continuation, which skips the whitespace at the start of the next line
+            '\n' => {
+                chars = chars.as_str().trim_start().chars();
+                continue;
+            }
+            _ => return None,
+        };
+        out.push(escaped);
+    }
+    Some(out)
+}
+
+/// `path!(args)`.
+fn call(path: &str, text: Literal, args: TokenStream) -> TokenStream {
+    let mut inside = TokenStream::from(TokenTree::from(text));
+    inside.extend(args);
+    call_with(path, inside)
+}
+
+/// `path!(args)`, for any args.
+fn call_with(path: &str, args: TokenStream) -> TokenStream {
+    let mut out = path.parse::<TokenStream>().expect("a path");
+    out.extend([
+        TokenTree::from(Punct::new('!', Spacing::Alone)),
+        Group::new(Delimiter::Parenthesis, args).into(),
+    ]);
+    out
+}
+
+/// A `compile_error!` with `message`, at `span`.
+fn error(message: &str, span: Span) -> TokenStream {
+    call_with(
+        "::core::compile_error",
+        TokenTree::from(Literal::string(message)).into(),
+    )
+    .into_iter()
+    .map(|mut token| {
+        token.set_span(span);
+        token
+    })
+    .collect()
+}
@@ -0,0 +1,36 @@
+#[test]
+fn same_as_comat() {
+    let (n, name) = (4, "comat");
+    assert_eq!(
+        comat_tiny::cformat!("{bold_red}{n}{reset} {name:green} {:>3}", 2),
+        comat::cformat!("{bold_red}{n}{reset} {name:green} {:>3}", 2)
+    );
+    assert_eq!(
+        comat_tiny::comat!("{n:italic}"),
+        comat::comat!("{n:italic}")
+    );
+}
+
+#[test]
+fn literals() {
+    assert_eq!(
+        comat_tiny::comat!(
+            "\t\"{red}\u{e9}\x41\
+                            b{reset}\\"
+        ),
+        comat::comat!("\t\"{red}éAb{reset}\\")
+    );
+    assert_eq!(
+        comat_tiny::comat!(r#"{red}"raw"\n{reset}"#),
+        comat::comat!(r#"{red}"raw"\n{reset}"#)
+    );
+}
+
+#[test]
+fn writers() {
+    use std::fmt::Write;
+    let mut s = String::new();
+    comat_tiny::cwrite!(s, "{green}{}", 1).unwrap();
+    comat_tiny::cwriteln!(s, "{reset}").unwrap();
+    assert_eq!(s, "\x1b[0;34;32m1\x1b[0m\n");
+}